@@ -0,0 +1,20 @@
+create table snapshot_diffs_old
+(
+    diff_id      text not null,
+    snapshot_id1 text not null,
+    snapshot_id2 text not null,
+    data         text not null,
+    primary key (diff_id),
+    unique (snapshot_id1, snapshot_id2),
+    foreign key (snapshot_id1) references snapshot_summaries (snapshot_id) on delete cascade,
+    foreign key (snapshot_id2) references snapshot_summaries (snapshot_id) on delete cascade
+);
+
+insert into snapshot_diffs_old (diff_id, snapshot_id1, snapshot_id2, data)
+select diff_id, snapshot_id1, snapshot_id2, data
+from snapshot_diffs
+where ignore_rules = '';
+
+drop table snapshot_diffs;
+
+alter table snapshot_diffs_old rename to snapshot_diffs;
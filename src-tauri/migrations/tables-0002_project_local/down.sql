@@ -0,0 +1,25 @@
+PRAGMA foreign_keys = OFF;
+
+create table projects_old
+(
+    project_id text not null,
+    name       text not null,
+    color      text not null,
+    rdbms      text not null,
+    user       text not null,
+    password   text not null,
+    host       text not null,
+    port       text not null,
+    `schema`   text not null,
+    primary key (project_id)
+);
+
+insert into projects_old (project_id, name, color, rdbms, user, password, host, port, `schema`)
+select project_id, name, color, rdbms, user, password, host, port, `schema`
+from projects;
+
+drop table projects;
+
+alter table projects_old rename to projects;
+
+PRAGMA foreign_keys = ON;
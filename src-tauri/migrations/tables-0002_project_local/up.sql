@@ -0,0 +1 @@
+alter table projects add column local integer not null default 0;
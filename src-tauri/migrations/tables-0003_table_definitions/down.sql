@@ -0,0 +1 @@
+drop table table_definitions;
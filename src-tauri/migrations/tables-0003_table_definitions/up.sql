@@ -0,0 +1,10 @@
+PRAGMA foreign_keys = ON;
+
+create table table_definitions
+(
+    snapshot_id text not null,
+    table_name  text not null,
+    data        text not null,
+    primary key (snapshot_id, table_name),
+    foreign key (snapshot_id) references snapshot_summaries (snapshot_id) on delete cascade
+);
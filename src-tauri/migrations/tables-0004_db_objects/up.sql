@@ -0,0 +1,9 @@
+PRAGMA foreign_keys = ON;
+
+create table db_objects
+(
+    snapshot_id text not null,
+    data        text not null,
+    primary key (snapshot_id),
+    foreign key (snapshot_id) references snapshot_summaries (snapshot_id) on delete cascade
+);
@@ -0,0 +1,22 @@
+create table snapshot_diffs_old
+(
+    diff_id      text not null,
+    snapshot_id1 text not null,
+    snapshot_id2 text not null,
+    ignore_rules text not null default '',
+    data         text not null,
+    primary key (diff_id),
+    unique (snapshot_id1, snapshot_id2, ignore_rules),
+    foreign key (snapshot_id1) references snapshot_summaries (snapshot_id) on delete cascade,
+    foreign key (snapshot_id2) references snapshot_summaries (snapshot_id) on delete cascade
+);
+
+-- 名前の変更を指定した差分は、除外ルールだけのキーでは区別できないので残さない
+insert into snapshot_diffs_old (diff_id, snapshot_id1, snapshot_id2, ignore_rules, data)
+select diff_id, snapshot_id1, snapshot_id2, ignore_rules, data
+from snapshot_diffs
+where renames = '';
+
+drop table snapshot_diffs;
+
+alter table snapshot_diffs_old rename to snapshot_diffs;
@@ -0,0 +1,23 @@
+PRAGMA foreign_keys = ON;
+
+create table snapshot_diffs_new
+(
+    diff_id      text not null,
+    snapshot_id1 text not null,
+    snapshot_id2 text not null,
+    ignore_rules text not null default '',
+    renames      text not null default '',
+    data         text not null,
+    primary key (diff_id),
+    unique (snapshot_id1, snapshot_id2, ignore_rules, renames),
+    foreign key (snapshot_id1) references snapshot_summaries (snapshot_id) on delete cascade,
+    foreign key (snapshot_id2) references snapshot_summaries (snapshot_id) on delete cascade
+);
+
+insert into snapshot_diffs_new (diff_id, snapshot_id1, snapshot_id2, ignore_rules, data)
+select diff_id, snapshot_id1, snapshot_id2, ignore_rules, data
+from snapshot_diffs;
+
+drop table snapshot_diffs;
+
+alter table snapshot_diffs_new rename to snapshot_diffs;
@@ -0,0 +1,18 @@
+PRAGMA foreign_keys = ON;
+
+create table table_definitions_old
+(
+    snapshot_id text not null,
+    table_name  text not null,
+    data        text not null,
+    primary key (snapshot_id, table_name),
+    foreign key (snapshot_id) references snapshot_summaries (snapshot_id) on delete cascade
+);
+
+insert into table_definitions_old (snapshot_id, table_name, data)
+select table_definitions.snapshot_id, json_extract(definition.value, '$.table_name'), definition.value
+from table_definitions, json_each(table_definitions.data) as definition;
+
+drop table table_definitions;
+
+alter table table_definitions_old rename to table_definitions;
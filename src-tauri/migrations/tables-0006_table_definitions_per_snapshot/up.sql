@@ -0,0 +1,18 @@
+PRAGMA foreign_keys = ON;
+
+create table table_definitions_new
+(
+    snapshot_id text not null,
+    data        text not null,
+    primary key (snapshot_id),
+    foreign key (snapshot_id) references snapshot_summaries (snapshot_id) on delete cascade
+);
+
+insert into table_definitions_new (snapshot_id, data)
+select snapshot_id, '[' || group_concat(data, ',') || ']'
+from (select snapshot_id, data from table_definitions order by snapshot_id, table_name)
+group by snapshot_id;
+
+drop table table_definitions;
+
+alter table table_definitions_new rename to table_definitions;
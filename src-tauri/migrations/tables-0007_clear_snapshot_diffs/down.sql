@@ -0,0 +1,2 @@
+-- 消した差分はキャッシュなので戻さない ( 次に比較したときに作り直される )
+select 1;
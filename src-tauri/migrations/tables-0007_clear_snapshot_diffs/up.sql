@@ -0,0 +1,2 @@
+-- 保存済みの差分はキャッシュなので、差分の作り方を変えたら消して作り直させる
+delete from snapshot_diffs;
@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+use anyhow::anyhow;
+use diesel::{Connection, SqliteConnection};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::db::db_object::{find_db_objects, insert_db_objects};
+use crate::db::diff::{find_snapshot_diffs, insert_snapshot_diff};
+use crate::db::dump_config::{find_dump_config, insert_dump_configs};
+use crate::db::snapshot::{all_snapshot_summaries, find_table_snapshots, insert_snapshot_summary, insert_table_snapshots};
+use crate::db::table_definition::{find_table_definitions, insert_table_definitions};
+use crate::domain::db_object::DbObject;
+use crate::domain::diff::{create_diff_id, SnapshotDiff};
+use crate::domain::dump_config::DumpConfig;
+use crate::domain::project::{Project, Rdbms};
+use crate::domain::snapshot::{SnapshotId, SnapshotName, SnapshotSummary, TableSnapshot};
+use crate::domain::table_definition::TableDefinition;
+
+const ARCHIVE_FORMAT: &str = "db-diff-archive";
+
+// 形式を変更したら上げる ( 古いバージョンのアプリでは新しいアーカイブを読み込めない )
+const ARCHIVE_VERSION: u64 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct Archive {
+    format: String,
+    version: u64,
+    rdbms: Rdbms,
+    snapshots: Vec<ArchivedSnapshot>,
+    snapshot_diffs: Vec<SnapshotDiff>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ArchivedSnapshot {
+    snapshot_id: SnapshotId,
+    snapshot_name: SnapshotName,
+    create_at: String,
+    dump_configs: Vec<DumpConfig>,
+    #[serde(default)]
+    table_definitions: Option<Vec<TableDefinition>>,
+    #[serde(default)]
+    db_objects: Option<Vec<DbObject>>,
+    table_snapshots: Vec<TableSnapshot>,
+}
+
+// スナップショットと、その間でキャッシュされている差分を gzip 圧縮した json の 1 ファイルにまとめる
+pub fn export_archive(conn: &SqliteConnection, project: &Project, snapshot_ids: &[SnapshotId], path: &Path) -> anyhow::Result<()> {
+    if snapshot_ids.is_empty() {
+        return Err(anyhow!("no snapshot selected"));
+    }
+
+    let snapshot_summaries = all_snapshot_summaries(conn, &project.project_id)?;
+
+    let snapshots = snapshot_ids
+        .iter()
+        .unique()
+        .map(|snapshot_id| {
+            let snapshot_summary = snapshot_summaries
+                .iter()
+                .find(|snapshot_summary| &snapshot_summary.snapshot_id == snapshot_id)
+                .ok_or_else(|| anyhow!("snapshot not found: {}", snapshot_id))?;
+
+            Ok(ArchivedSnapshot {
+                snapshot_id: snapshot_id.clone(),
+                snapshot_name: snapshot_summary.snapshot_name.clone(),
+                create_at: snapshot_summary.create_at.clone(),
+                dump_configs: find_dump_config(conn, snapshot_id)?,
+                table_definitions: find_table_definitions(conn, snapshot_id)?,
+                db_objects: find_db_objects(conn, snapshot_id)?,
+                table_snapshots: find_table_snapshots(conn, snapshot_id)?,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let archive = Archive {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        rdbms: project.rdbms.clone(),
+        snapshots,
+        snapshot_diffs: find_snapshot_diffs(conn, snapshot_ids)?,
+    };
+
+    let file = File::create(path).map_err(|e| anyhow!("cannot create archive: {}: {}", path.display(), e))?;
+    let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+    serde_json::to_writer(&mut encoder, &archive)?;
+    encoder.finish()?.flush()?;
+
+    Ok(())
+}
+
+// 別の環境と ID が重複しないように、スナップショットと差分には新しい ID を振り直して取り込む
+pub fn import_archive(conn: &SqliteConnection, project: &Project, path: &Path) -> anyhow::Result<Vec<SnapshotSummary>> {
+    let archive = read_archive(path)?;
+
+    if archive.rdbms != project.rdbms {
+        return Err(anyhow!("rdbms mismatch: archive is {:?}, project is {:?}", archive.rdbms, project.rdbms));
+    }
+
+    // 同じ名前と作成日時のスナップショットは、取り込み済みとみなす
+    let snapshot_summaries = all_snapshot_summaries(conn, &project.project_id)?;
+    let conflicts = archive
+        .snapshots
+        .iter()
+        .filter(|snapshot| {
+            snapshot_summaries
+                .iter()
+                .any(|snapshot_summary| snapshot_summary.snapshot_name == snapshot.snapshot_name && snapshot_summary.create_at == snapshot.create_at)
+        })
+        .map(|snapshot| format!("{} ( {} )", snapshot.snapshot_name, snapshot.create_at))
+        .collect_vec();
+    if !conflicts.is_empty() {
+        return Err(anyhow!("snapshots already exist in the project: {}", conflicts.join(", ")));
+    }
+
+    let snapshot_ids: HashMap<SnapshotId, SnapshotId> =
+        archive.snapshots.iter().map(|snapshot| (snapshot.snapshot_id.clone(), Uuid::new_v4().to_string())).collect();
+
+    conn.transaction::<_, anyhow::Error, _>(|| {
+        let mut imported = vec![];
+
+        for snapshot in archive.snapshots {
+            let snapshot_id = &snapshot_ids[&snapshot.snapshot_id];
+            let snapshot_summary = SnapshotSummary::new(snapshot_id, snapshot.snapshot_name, snapshot.create_at);
+
+            insert_snapshot_summary(conn, &project.project_id, &snapshot_summary)?;
+            insert_dump_configs(conn, &project.project_id, snapshot_id, &snapshot.dump_configs)?;
+            if let Some(table_definitions) = &snapshot.table_definitions {
+                insert_table_definitions(conn, snapshot_id, table_definitions)?;
+            }
+            if let Some(db_objects) = &snapshot.db_objects {
+                insert_db_objects(conn, snapshot_id, db_objects)?;
+            }
+            insert_table_snapshots(conn, snapshot_id, snapshot.table_snapshots)?;
+
+            imported.push(snapshot_summary);
+        }
+
+        for snapshot_diff in archive.snapshot_diffs {
+            let (snapshot_id1, snapshot_id2) = match (snapshot_ids.get(&snapshot_diff.snapshot_id1), snapshot_ids.get(&snapshot_diff.snapshot_id2)) {
+                (Some(snapshot_id1), Some(snapshot_id2)) => (snapshot_id1.clone(), snapshot_id2.clone()),
+                _ => return Err(anyhow!("broken archive: snapshot diff refers to a snapshot not in the archive")),
+            };
+
+            insert_snapshot_diff(conn, &SnapshotDiff { diff_id: create_diff_id(), snapshot_id1, snapshot_id2, ..snapshot_diff })?;
+        }
+
+        Ok(imported)
+    })
+}
+
+// 中身を解釈する前に、形式とバージョンを確認する
+fn read_archive(path: &Path) -> anyhow::Result<Archive> {
+    let file = File::open(path).map_err(|e| anyhow!("cannot open archive: {}: {}", path.display(), e))?;
+    let value: Value =
+        serde_json::from_reader(GzDecoder::new(BufReader::new(file))).map_err(|_| anyhow!("not a db-diff archive: {}", path.display()))?;
+
+    if value.get("format").and_then(Value::as_str) != Some(ARCHIVE_FORMAT) {
+        return Err(anyhow!("not a db-diff archive: {}", path.display()));
+    }
+    match value.get("version").and_then(Value::as_u64) {
+        Some(version) if version <= ARCHIVE_VERSION => {}
+        Some(version) => return Err(anyhow!("unsupported archive version: {} ( supported up to {} )", version, ARCHIVE_VERSION)),
+        None => return Err(anyhow!("not a db-diff archive: {}", path.display())),
+    }
+
+    serde_json::from_value(value).map_err(|e| anyhow!("broken archive: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::temp_dir;
+    use std::fs::File;
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    use crate::archive::{export_archive, import_archive};
+    use crate::db::db_object::{find_db_objects, insert_db_objects};
+    use crate::db::diff::{find_snapshot_diff, insert_snapshot_diff};
+    use crate::db::dump_config::{find_dump_config, insert_dump_configs};
+    use crate::db::project::insert_project;
+    use crate::db::snapshot::{all_snapshot_summaries, find_table_snapshots, insert_snapshot_summary, insert_table_snapshots};
+    use crate::db::table_definition::{find_table_definitions, insert_table_definitions};
+    use crate::db::{create_sqlite_connection, migrate_sqlite};
+    use crate::domain::db_object::DbObject;
+    use crate::domain::db_object::DbObjectType::View;
+    use crate::domain::diff::{create_diff_id, create_table_diff, SnapshotDiff};
+    use crate::domain::dump_config::DumpConfig;
+    use crate::domain::ignore_rule::IgnoreRules;
+    use crate::domain::project::Rdbms::{Mysql, Postgres};
+    use crate::domain::project::{create_project_id, Project};
+    use crate::domain::rename::RenameRules;
+    use crate::domain::snapshot::ColValue::{SimpleNumber, SimpleString};
+    use crate::domain::snapshot::{create_snapshot_id, RowSnapshot, SnapshotSummary, TableSnapshot};
+    use crate::domain::table_definition::{ColDefinition, TableDefinition};
+
+    fn mk_table_snapshot(name: &str) -> TableSnapshot {
+        let row_snapshots = vec![RowSnapshot::new(vec![SimpleNumber("1".to_string())], vec![SimpleString(name.to_string())])];
+        TableSnapshot::new(&"users".to_string(), "id".to_string(), vec!["name".to_string()], row_snapshots)
+    }
+
+    #[test]
+    fn export_and_import() -> anyhow::Result<()> {
+        // setup
+
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+
+        let project1 = Project::new(&create_project_id(), "test-project", "red", Mysql, "user", "password", "127.0.0.1", "3306", "test-db");
+        insert_project(&conn, &project1)?;
+
+        let ignore_rules = IgnoreRules::parse(&["*.updated_at".to_string()])?;
+        let dump_configs = vec![DumpConfig::new("users", vec!["id", "name"], "all")];
+        let table_definitions = vec![TableDefinition {
+            table_name: "users".to_string(),
+            cols: vec![ColDefinition::new("id", "int", false)],
+            indexes: vec![],
+            foreign_keys: vec![],
+        }];
+
+        let db_objects = vec![DbObject::new(View, "active_users", "select * from users")];
+
+        let snapshot_ids = vec![create_snapshot_id(), create_snapshot_id()];
+        for (i, (snapshot_id, name)) in snapshot_ids.iter().zip(["John", "Jane"]).enumerate() {
+            insert_snapshot_summary(
+                &conn,
+                &project1.project_id,
+                &SnapshotSummary::new(snapshot_id, format!("test{i}").as_str(), "2023-07-03 08:17:52"),
+            )?;
+            insert_dump_configs(&conn, &project1.project_id, snapshot_id, &dump_configs)?;
+            insert_table_definitions(&conn, snapshot_id, &table_definitions)?;
+            insert_db_objects(&conn, snapshot_id, &db_objects)?;
+            insert_table_snapshots(&conn, snapshot_id, vec![mk_table_snapshot(name)])?;
+        }
+
+        let table_diff = create_table_diff(Some(&mk_table_snapshot("John")), Some(&mk_table_snapshot("Jane")), &ignore_rules);
+        let snapshot_diff =
+            SnapshotDiff::new(&create_diff_id(), &snapshot_ids[0], &snapshot_ids[1], vec![table_diff]).with_ignore_rules(&ignore_rules);
+        insert_snapshot_diff(&conn, &snapshot_diff)?;
+
+        let path = temp_dir().join(format!("db-diff-test-{}.dbdiff", create_project_id()));
+
+        // export
+        export_archive(&conn, &project1, &snapshot_ids, &path)?;
+
+        // import
+        let project2 = Project::new(&create_project_id(), "test-project-2", "blue", Mysql, "user", "password", "127.0.0.2", "3306", "test-db");
+        insert_project(&conn, &project2)?;
+
+        let imported = import_archive(&conn, &project2, &path)?;
+
+        // ID は振り直される
+        assert_eq!(2, imported.len());
+        assert_eq!(vec!["test0", "test1"], imported.iter().map(|snapshot_summary| &snapshot_summary.snapshot_name).collect::<Vec<_>>());
+        assert!(imported.iter().all(|snapshot_summary| !snapshot_ids.contains(&snapshot_summary.snapshot_id)));
+        assert_eq!(2, all_snapshot_summaries(&conn, &project2.project_id)?.len());
+
+        assert_eq!(find_table_snapshots(&conn, &snapshot_ids[1])?, find_table_snapshots(&conn, &imported[1].snapshot_id)?);
+        assert_eq!(dump_configs, find_dump_config(&conn, &imported[0].snapshot_id)?);
+        assert_eq!(Some(table_definitions), find_table_definitions(&conn, &imported[0].snapshot_id)?);
+        assert_eq!(Some(db_objects), find_db_objects(&conn, &imported[0].snapshot_id)?);
+
+        let act = find_snapshot_diff(&conn, &imported[0].snapshot_id, &imported[1].snapshot_id, &ignore_rules, &RenameRules::default())?.unwrap();
+        assert_ne!(snapshot_diff.diff_id, act.diff_id);
+        assert_eq!(snapshot_diff.table_diffs, act.table_diffs);
+
+        // 取り込み済みのスナップショットは重複して取り込まない
+        let act = import_archive(&conn, &project2, &path).unwrap_err().to_string();
+        assert_eq!("snapshots already exist in the project: test0 ( 2023-07-03 08:17:52 ), test1 ( 2023-07-03 08:17:52 )", act);
+
+        // RDBMS の異なるプロジェクトには取り込めない
+        let project3 = Project::new(&create_project_id(), "test-project-3", "red", Postgres, "user", "password", "127.0.0.1", "5432", "test-db");
+        let act = import_archive(&conn, &project3, &path).unwrap_err().to_string();
+        assert_eq!("rdbms mismatch: archive is Mysql, project is Postgres", act);
+
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_version() -> anyhow::Result<()> {
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+
+        let project = Project::new(&create_project_id(), "test-project", "red", Mysql, "user", "password", "127.0.0.1", "3306", "test-db");
+
+        let path = temp_dir().join(format!("db-diff-test-{}.dbdiff", create_project_id()));
+        let mut encoder = GzEncoder::new(File::create(&path)?, Compression::default());
+        encoder.write_all(br#"{"format": "db-diff-archive", "version": 99}"#)?;
+        encoder.finish()?;
+
+        let act = import_archive(&conn, &project, &path).unwrap_err().to_string();
+        assert_eq!("unsupported archive version: 99 ( supported up to 1 )", act);
+
+        let path = temp_dir().join(format!("db-diff-test-{}.dbdiff", create_project_id()));
+        File::create(&path)?.write_all(b"not gzip")?;
+
+        let act = import_archive(&conn, &project, &path).unwrap_err().to_string();
+        assert!(act.starts_with("not a db-diff archive: "));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+use std::env;
+use std::process;
+
+use db_diff_lib::cli;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    process::exit(cli::run(&args));
+}
@@ -0,0 +1,350 @@
+use std::fs::{read_to_string, write};
+use std::path::Path;
+
+use anyhow::anyhow;
+use diesel::SqliteConnection;
+use itertools::Itertools;
+
+use crate::command::diff::SnapshotDiffJson;
+use crate::command::snapshot::SnapshotSummaryJson;
+use crate::db::diff::find_or_insert_snapshot_diff;
+use crate::db::dump_config::{find_recent_dump_configs, insert_dump_configs};
+use crate::db::project::all_projects;
+use crate::db::snapshot::all_snapshot_summaries;
+use crate::db::snapshot_result::update_snapshot_result;
+use crate::db::{create_sqlite_connection, migrate_sqlite};
+use crate::domain::dump_config::DumpConfig;
+use crate::domain::ignore_rule::IgnoreRules;
+use crate::domain::project::Project;
+use crate::domain::snapshot::{create_snapshot_id, SnapshotSummary};
+use crate::domain::snapshot_result::SnapshotResult;
+use crate::dump::{dump, get_dump_configs};
+use crate::logger;
+use crate::report::{compare_golden, create_golden, create_text};
+
+const USAGE: &str = "usage:
+  db-diff-cli projects [--format text|json]
+  db-diff-cli list <project> [--format text|json]
+  db-diff-cli dump <project> <snapshot name>
+  db-diff-cli diff <project> <snapshot 1> <snapshot 2> [--format text|json|golden] [--ignore <rule>]... [--exit-code]
+  db-diff-cli verify <project> <snapshot 1> <snapshot 2> <golden file> [--ignore <rule>]... [--update]
+
+  <project> is a project name or id, and <snapshot> is a snapshot name or id.
+  diff exits with 1 when --exit-code is given and the snapshots differ.
+  verify exits with 1 when the diff does not match the golden file, and --update re-records it.";
+
+// 差分がある場合と区別するため、エラーは 2 で終了する
+const EXIT_ERROR: i32 = 2;
+
+#[derive(Eq, PartialEq, Debug)]
+enum OutputFormat {
+    Text,
+    Json,
+    Golden,
+}
+
+#[derive(Eq, PartialEq, Debug)]
+enum Command {
+    Help,
+    Projects { format: OutputFormat },
+    List { project: String, format: OutputFormat },
+    Dump { project: String, snapshot_name: String },
+    Diff { project: String, snapshot1: String, snapshot2: String, format: OutputFormat, ignore_rules: Vec<String>, exit_code: bool },
+    Verify { project: String, snapshot1: String, snapshot2: String, path: String, ignore_rules: Vec<String>, update: bool },
+}
+
+pub fn run(args: &[String]) -> i32 {
+    logger::info(format!("start cli [ {} ]", args.join(" ")));
+
+    let x = match parse(args).and_then(execute) {
+        Ok(code) => code,
+        Err(e) => {
+            logger::error(e.to_string());
+            eprintln!("error: {e}");
+            EXIT_ERROR
+        }
+    };
+
+    logger::info("end   cli");
+    x
+}
+
+fn parse(args: &[String]) -> anyhow::Result<Command> {
+    let mut positionals = vec![];
+    let mut format = OutputFormat::Text;
+    let mut ignore_rules = vec![];
+    let mut exit_code = false;
+    let mut update = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().map(|s| s.as_str()) {
+                    Some("text") => OutputFormat::Text,
+                    Some("json") => OutputFormat::Json,
+                    Some("golden") => OutputFormat::Golden,
+                    Some(s) => return Err(anyhow!("unknown format: {}", s)),
+                    None => return Err(anyhow!("--format requires a value")),
+                }
+            }
+            "--ignore" => ignore_rules.push(args.next().ok_or_else(|| anyhow!("--ignore requires a value"))?.clone()),
+            "--exit-code" => exit_code = true,
+            "--update" => update = true,
+            "--help" => positionals.push("help".to_string()),
+            s if s.starts_with("--") => return Err(anyhow!("unknown option: {}\n\n{}", s, USAGE)),
+            s => positionals.push(s.to_string()),
+        }
+    }
+
+    match positionals.iter().map(|s| s.as_str()).collect_vec().as_slice() {
+        ["help"] | ["-h"] | [] => Ok(Command::Help),
+        ["projects" | "list", ..] if format == OutputFormat::Golden => Err(anyhow!("golden format is only for diff")),
+        ["projects"] => Ok(Command::Projects { format }),
+        ["list", project] => Ok(Command::List { project: project.to_string(), format }),
+        ["dump", project, snapshot_name] => Ok(Command::Dump { project: project.to_string(), snapshot_name: snapshot_name.to_string() }),
+        ["diff", project, snapshot1, snapshot2] => Ok(Command::Diff {
+            project: project.to_string(),
+            snapshot1: snapshot1.to_string(),
+            snapshot2: snapshot2.to_string(),
+            format,
+            ignore_rules,
+            exit_code,
+        }),
+        ["verify", project, snapshot1, snapshot2, path] => Ok(Command::Verify {
+            project: project.to_string(),
+            snapshot1: snapshot1.to_string(),
+            snapshot2: snapshot2.to_string(),
+            path: path.to_string(),
+            ignore_rules,
+            update,
+        }),
+        _ => Err(anyhow!("invalid arguments: {}\n\n{}", positionals.join(" "), USAGE)),
+    }
+}
+
+fn execute(command: Command) -> anyhow::Result<i32> {
+    if command == Command::Help {
+        println!("{USAGE}");
+        return Ok(0);
+    }
+
+    migrate_sqlite()?;
+    let conn = create_sqlite_connection()?;
+
+    match command {
+        Command::Help => Ok(0),
+        Command::Projects { format } => {
+            let projects = all_projects(&conn)?;
+            match format {
+                OutputFormat::Text | OutputFormat::Golden => {
+                    projects.iter().for_each(|project| println!("{}\t{:?}\t{}", project.project_id, project.rdbms, project.name))
+                }
+                OutputFormat::Json => {
+                    let names =
+                        projects.iter().map(|project| serde_json::json!({ "projectId": project.project_id, "name": project.name })).collect_vec();
+                    println!("{}", serde_json::to_string_pretty(&names)?);
+                }
+            }
+            Ok(0)
+        }
+        Command::List { project, format } => {
+            let project = find_project(&conn, &project)?;
+            let snapshot_summaries = all_snapshot_summaries(&conn, &project.project_id)?;
+            match format {
+                OutputFormat::Text | OutputFormat::Golden => {
+                    snapshot_summaries.iter().for_each(|s| println!("{}\t{}\t{}", s.snapshot_id, s.create_at, s.snapshot_name))
+                }
+                OutputFormat::Json => {
+                    let snapshot_summaries = snapshot_summaries.into_iter().map(SnapshotSummaryJson::from).collect_vec();
+                    println!("{}", serde_json::to_string_pretty(&snapshot_summaries)?);
+                }
+            }
+            Ok(0)
+        }
+        Command::Dump { project, snapshot_name } => {
+            let project = find_project(&conn, &project)?;
+            execute_dump(&conn, &project, snapshot_name)
+        }
+        Command::Diff { project, snapshot1, snapshot2, format, ignore_rules, exit_code } => {
+            let project = find_project(&conn, &project)?;
+            let snapshot_summaries = all_snapshot_summaries(&conn, &project.project_id)?;
+            let snapshot_summary1 = find_snapshot_summary(&snapshot_summaries, &snapshot1)?;
+            let snapshot_summary2 = find_snapshot_summary(&snapshot_summaries, &snapshot2)?;
+            let ignore_rules = IgnoreRules::parse(&ignore_rules)?;
+            let snapshot_diff = find_or_insert_snapshot_diff(&conn, &snapshot_summary1.snapshot_id, &snapshot_summary2.snapshot_id, &ignore_rules)?;
+
+            let differs = !snapshot_diff.is_empty();
+            match format {
+                OutputFormat::Text if differs => {
+                    println!("--- {} ( {} )", snapshot_summary1.snapshot_name, snapshot_summary1.create_at);
+                    println!("+++ {} ( {} )", snapshot_summary2.snapshot_name, snapshot_summary2.create_at);
+                    print!(
+                        "{}",
+                        create_text(
+                            &snapshot_diff.schema_changes,
+                            &snapshot_diff.db_object_diffs,
+                            &snapshot_diff.table_diffs.iter().sorted_by_key(|table_diff| &table_diff.table_name).collect_vec()
+                        )
+                    );
+                }
+                OutputFormat::Text => {}
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&SnapshotDiffJson::from(snapshot_diff))?),
+                OutputFormat::Golden => print!("{}", create_golden(&snapshot_diff)),
+            }
+
+            Ok(if exit_code && differs { 1 } else { 0 })
+        }
+        Command::Verify { project, snapshot1, snapshot2, path, ignore_rules, update } => {
+            let project = find_project(&conn, &project)?;
+            let snapshot_summaries = all_snapshot_summaries(&conn, &project.project_id)?;
+            let snapshot_summary1 = find_snapshot_summary(&snapshot_summaries, &snapshot1)?;
+            let snapshot_summary2 = find_snapshot_summary(&snapshot_summaries, &snapshot2)?;
+            let ignore_rules = IgnoreRules::parse(&ignore_rules)?;
+            let snapshot_diff = find_or_insert_snapshot_diff(&conn, &snapshot_summary1.snapshot_id, &snapshot_summary2.snapshot_id, &ignore_rules)?;
+
+            execute_verify(&create_golden(&snapshot_diff), Path::new(&path), update)
+        }
+    }
+}
+
+// 期待値のファイルと比較し、--update の場合は実際の差分で期待値を記録し直す
+fn execute_verify(actual: &str, path: &Path, update: bool) -> anyhow::Result<i32> {
+    if update {
+        write(path, actual).map_err(|e| anyhow!("cannot write golden diff: {}: {}", path.display(), e))?;
+        println!("recorded: {}", path.display());
+        return Ok(0);
+    }
+
+    if !path.is_file() {
+        return Err(anyhow!("golden diff not found: {} ( run with --update to record it )", path.display()));
+    }
+    let expected = read_to_string(path)?;
+
+    match compare_golden(&expected, actual) {
+        Some(mismatch) => {
+            println!("golden diff mismatch: {}", path.display());
+            println!("{mismatch}");
+            Ok(1)
+        }
+        None => Ok(0),
+    }
+}
+
+// 画面と同じく、前回の dump 設定を引き継いで全テーブルを dump する
+fn execute_dump(conn: &SqliteConnection, project: &Project, snapshot_name: String) -> anyhow::Result<i32> {
+    let dump_configs = get_dump_configs(project)?;
+    let dump_configs = match find_recent_dump_configs(conn, &project.project_id)? {
+        Some(recent_dump_configs) => DumpConfig::merge(dump_configs, recent_dump_configs),
+        None => dump_configs,
+    };
+
+    let snapshot_id = create_snapshot_id();
+    match dump(conn, project, &snapshot_id, snapshot_name, &dump_configs) {
+        Ok(snapshot_result) => {
+            insert_dump_configs(conn, &project.project_id, &snapshot_result.snapshot_id, &dump_configs)?;
+            println!("{snapshot_id}");
+            Ok(0)
+        }
+        Err(e) => {
+            update_snapshot_result(conn, &SnapshotResult::failed(&snapshot_id))?;
+            Err(e)
+        }
+    }
+}
+
+fn find_project(conn: &SqliteConnection, project: &str) -> anyhow::Result<Project> {
+    let mut projects = all_projects(conn)?;
+
+    if let Some(i) = projects.iter().position(|p| p.project_id == project) {
+        return Ok(projects.swap_remove(i));
+    }
+
+    let mut found = projects.into_iter().filter(|p| p.name == project).collect_vec();
+    match found.len() {
+        0 => Err(anyhow!("project not found: {}", project)),
+        1 => Ok(found.remove(0)),
+        _ => Err(anyhow!("project name is ambiguous: {} ( use the project id )", project)),
+    }
+}
+
+// ID が一致するものを優先し、名前が重複する場合は ID での指定を求める
+fn find_snapshot_summary<'a>(snapshot_summaries: &'a [SnapshotSummary], snapshot: &str) -> anyhow::Result<&'a SnapshotSummary> {
+    if let Some(snapshot_summary) = snapshot_summaries.iter().find(|s| s.snapshot_id == snapshot) {
+        return Ok(snapshot_summary);
+    }
+
+    match snapshot_summaries.iter().filter(|s| s.snapshot_name == snapshot).collect_vec().as_slice() {
+        [] => Err(anyhow!("snapshot not found: {}", snapshot)),
+        [snapshot_summary] => Ok(snapshot_summary),
+        _ => Err(anyhow!("snapshot name is ambiguous: {} ( use the snapshot id )", snapshot)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::OutputFormat::{Json, Text};
+    use crate::cli::{find_snapshot_summary, parse, Command};
+    use crate::domain::snapshot::SnapshotSummary;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(Command::Help, parse(&args("help")).unwrap());
+        assert_eq!(Command::Help, parse(&args("--help")).unwrap());
+        assert_eq!(Command::Projects { format: Json }, parse(&args("projects --format json")).unwrap());
+        assert_eq!(Command::List { project: "foo".to_string(), format: Text }, parse(&args("list foo")).unwrap());
+        assert_eq!(Command::Dump { project: "foo".to_string(), snapshot_name: "before".to_string() }, parse(&args("dump foo before")).unwrap());
+        assert_eq!(
+            Command::Diff {
+                project: "foo".to_string(),
+                snapshot1: "before".to_string(),
+                snapshot2: "after".to_string(),
+                format: Json,
+                ignore_rules: vec!["*.updated_at".to_string(), "users.*".to_string()],
+                exit_code: true,
+            },
+            parse(&args("diff foo before after --ignore *.updated_at --format json --ignore users.* --exit-code")).unwrap()
+        );
+        assert_eq!(
+            Command::Verify {
+                project: "foo".to_string(),
+                snapshot1: "before".to_string(),
+                snapshot2: "after".to_string(),
+                path: "expected.diff".to_string(),
+                ignore_rules: vec![],
+                update: true,
+            },
+            parse(&args("verify foo before after expected.diff --update")).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!("unknown format: yaml", parse(&args("projects --format yaml")).unwrap_err().to_string());
+        assert_eq!("--format requires a value", parse(&args("projects --format")).unwrap_err().to_string());
+        assert_eq!("golden format is only for diff", parse(&args("list foo --format golden")).unwrap_err().to_string());
+        assert!(parse(&args("diff foo before")).unwrap_err().to_string().starts_with("invalid arguments: diff foo before\n\nusage:"));
+        assert!(parse(&args("list foo --verbose")).unwrap_err().to_string().starts_with("unknown option: --verbose\n\nusage:"));
+    }
+
+    #[test]
+    fn find_snapshot() {
+        let snapshot_summaries = vec![
+            SnapshotSummary::new(&"id-1".to_string(), "before", "2023-01-01 00:00:00"),
+            SnapshotSummary::new(&"id-2".to_string(), "after", "2023-01-01 00:01:00"),
+            SnapshotSummary::new(&"id-3".to_string(), "after", "2023-01-01 00:02:00"),
+        ];
+
+        assert_eq!("id-1", find_snapshot_summary(&snapshot_summaries, "before").unwrap().snapshot_id);
+        assert_eq!("id-3", find_snapshot_summary(&snapshot_summaries, "id-3").unwrap().snapshot_id);
+        assert_eq!(
+            "snapshot name is ambiguous: after ( use the snapshot id )",
+            find_snapshot_summary(&snapshot_summaries, "after").unwrap_err().to_string()
+        );
+        assert_eq!("snapshot not found: none", find_snapshot_summary(&snapshot_summaries, "none").unwrap_err().to_string());
+    }
+}
@@ -0,0 +1,44 @@
+use std::path::Path;
+
+use tauri::State;
+
+use crate::archive::{export_archive, import_archive};
+use crate::command::state::AppState;
+use crate::db::project::all_projects;
+use crate::domain::project::ProjectId;
+use crate::domain::snapshot::SnapshotId;
+use crate::logger;
+
+#[tauri::command]
+pub async fn export_archive_command(app_state: State<'_, AppState>, snapshot_ids: Vec<SnapshotId>, path: String) -> Result<(), String> {
+    logger::info("start export_archive_command");
+
+    let conn = app_state.conn.lock().unwrap();
+    let project_id = app_state.project_id.lock().unwrap();
+    let project_id = project_id.as_ref().unwrap();
+
+    let projects = all_projects(&conn).map_err(|e| e.to_string())?;
+    let project = projects.iter().find(|project| &project.project_id == project_id).unwrap();
+
+    let x = export_archive(&conn, project, &snapshot_ids, Path::new(&path)).map_err(|e| e.to_string());
+    logger::info("end   export_archive_command");
+    x
+}
+
+// 取り込んだスナップショットの数を返す
+#[tauri::command]
+pub async fn import_archive_command(app_state: State<'_, AppState>, project_id: ProjectId, path: String) -> Result<usize, String> {
+    logger::info("start import_archive_command");
+
+    let conn = app_state.conn.lock().unwrap();
+
+    let projects = all_projects(&conn).map_err(|e| e.to_string())?;
+    let project = match projects.iter().find(|project| project.project_id == project_id) {
+        Some(project) => Ok(project),
+        None => Err(format!("project not found: {project_id}")),
+    }?;
+
+    let x = import_archive(&conn, project, Path::new(&path)).map(|snapshot_summaries| snapshot_summaries.len()).map_err(|e| e.to_string());
+    logger::info("end   import_archive_command");
+    x
+}
@@ -1,17 +1,31 @@
 use std::collections::HashMap;
 
+use diesel::Connection;
 use itertools::Itertools;
-
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
+use crate::command::snapshot::SnapshotSummaryJson;
 use crate::command::state::AppState;
-use crate::db::diff::{find_snapshot_diff, insert_snapshot_diff};
-use crate::db::snapshot::find_table_snapshots;
+use crate::db::db_object::find_db_objects;
+use crate::db::diff::{find_or_insert_chained_diffs, find_snapshot_diff, insert_snapshot_diff};
+use crate::db::dump_config::find_dump_config;
+use crate::db::project::all_projects;
+use crate::db::snapshot::{all_snapshot_summaries, find_table_snapshots};
+use crate::db::table_definition::find_table_definitions;
+use crate::domain::db_object::{create_db_object_diffs, DbObjectDiff, LineDiff};
 use crate::domain::diff::ColDiff::{Added, Deleted, NoValue, Stay};
-use crate::domain::diff::{create_diff_id, create_table_diff, ColDiff, DiffId, SnapshotDiff, TableDiff};
+use crate::domain::diff::{create_snapshot_diff, ColDiff, DiffId, SnapshotDiff, TableDiff};
+use crate::domain::dump_config::DumpConfig;
+use crate::domain::ignore_rule::IgnoreRules;
+use crate::domain::inline_diff::InlineSegment;
+use crate::domain::json_diff::JsonPathDiff;
+use crate::domain::rename::{detect_renames, RenameRules};
 use crate::domain::schema::{ColName, PrimaryValue, TableName};
-use crate::domain::snapshot::{SnapshotId, TableSnapshot};
+use crate::domain::snapshot::{create_snapshot_id, SnapshotId, SnapshotName};
+use crate::domain::table_definition::{create_schema_changes, SchemaChange};
+use crate::domain::table_mapping::TableMappings;
+use crate::dump::{dump_live, keep_live};
 use crate::logger;
 
 #[derive(Serialize, Deserialize)]
@@ -21,19 +35,86 @@ pub struct SnapshotDiffJson {
     pub snapshot_id1: SnapshotId,
     pub snapshot_id2: SnapshotId,
     pub table_diffs: Vec<TableDiffJson>,
+    pub ignore_rules: Vec<String>,
+    pub schema_changes: Vec<SchemaChangeJson>,
+    pub db_object_diffs: Vec<DbObjectDiffJson>,
+    pub renames: Vec<String>,
+    pub suggested_renames: Vec<String>,
 }
 
 impl SnapshotDiffJson {
-    fn from(snapshot_diff: SnapshotDiff) -> Self {
+    pub fn from(snapshot_diff: SnapshotDiff) -> Self {
         Self {
             diff_id: snapshot_diff.diff_id,
             snapshot_id1: snapshot_diff.snapshot_id1,
             snapshot_id2: snapshot_diff.snapshot_id2,
             table_diffs: snapshot_diff.table_diffs.into_iter().map(TableDiffJson::from).collect(),
+            ignore_rules: snapshot_diff.ignore_rules,
+            schema_changes: snapshot_diff.schema_changes.into_iter().map(SchemaChangeJson::from).collect(),
+            db_object_diffs: snapshot_diff.db_object_diffs.into_iter().map(DbObjectDiffJson::from).collect(),
+            renames: snapshot_diff.renames,
+            suggested_renames: snapshot_diff.suggested_renames,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaChangeJson {
+    pub table_name: TableName,
+    pub object: String,
+    pub name: String,
+    pub status: String,
+    pub definition1: Option<String>,
+    pub definition2: Option<String>,
+}
+
+impl SchemaChangeJson {
+    fn from(schema_change: SchemaChange) -> Self {
+        Self {
+            table_name: schema_change.table_name,
+            object: schema_change.object.as_str().to_string(),
+            name: schema_change.name,
+            status: schema_change.status.as_str().to_string(),
+            definition1: schema_change.definition1,
+            definition2: schema_change.definition2,
         }
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbObjectDiffJson {
+    pub object_type: String,
+    pub object_name: String,
+    pub status: String,
+    pub line_diffs: Vec<LineDiffJson>,
+}
+
+impl DbObjectDiffJson {
+    fn from(db_object_diff: DbObjectDiff) -> Self {
+        Self {
+            object_type: db_object_diff.object_type.as_str().to_string(),
+            object_name: db_object_diff.object_name,
+            status: db_object_diff.status.as_str().to_string(),
+            line_diffs: db_object_diff.line_diffs.into_iter().map(LineDiffJson::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineDiffJson {
+    pub status: String,
+    pub line: String,
+}
+
+impl LineDiffJson {
+    fn from(line_diff: LineDiff) -> Self {
+        Self { status: line_diff.status.as_str().to_string(), line: line_diff.line }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TableDiffJson {
@@ -43,6 +124,16 @@ pub struct TableDiffJson {
     pub col_names: Vec<ColName>,
     pub row_diffs1: HashMap<PrimaryValue, HashMap<ColName, HashMap<String, String>>>,
     pub row_diffs2: HashMap<PrimaryValue, HashMap<ColName, HashMap<String, String>>>,
+    pub truncated: bool,
+    pub excluded_col_names1: Vec<ColName>,
+    pub excluded_col_names2: Vec<ColName>,
+    pub ignored_col_names: Vec<ColName>,
+    pub keyless: bool,
+    pub row_counts: HashMap<PrimaryValue, (usize, usize)>,
+    pub renamed_from: Option<TableName>,
+    pub renamed_col_names: HashMap<ColName, ColName>,
+    pub json_diffs: HashMap<PrimaryValue, HashMap<ColName, Vec<JsonPathDiffJson>>>,
+    pub inline_diffs: HashMap<PrimaryValue, HashMap<ColName, Vec<InlineSegmentJson>>>,
 }
 
 impl TableDiffJson {
@@ -72,6 +163,36 @@ impl TableDiffJson {
             col_names: table_diff.col_names,
             row_diffs1,
             row_diffs2,
+            truncated: table_diff.truncated,
+            excluded_col_names1: table_diff.excluded_col_names1,
+            excluded_col_names2: table_diff.excluded_col_names2,
+            ignored_col_names: table_diff.ignored_col_names,
+            keyless: table_diff.keyless,
+            row_counts: table_diff.row_counts,
+            renamed_from: table_diff.renamed_from,
+            renamed_col_names: table_diff.renamed_col_names,
+            json_diffs: table_diff
+                .json_diffs
+                .into_iter()
+                .map(|(primary_value, cols)| {
+                    let cols = cols
+                        .into_iter()
+                        .map(|(col_name, json_path_diffs)| (col_name, json_path_diffs.into_iter().map(JsonPathDiffJson::from).collect()))
+                        .collect();
+                    (primary_value, cols)
+                })
+                .collect(),
+            inline_diffs: table_diff
+                .inline_diffs
+                .into_iter()
+                .map(|(primary_value, cols)| {
+                    let cols = cols
+                        .into_iter()
+                        .map(|(col_name, segments)| (col_name, segments.into_iter().map(InlineSegmentJson::from).collect()))
+                        .collect();
+                    (primary_value, cols)
+                })
+                .collect(),
         }
     }
 
@@ -87,22 +208,69 @@ impl TableDiffJson {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonPathDiffJson {
+    pub path: String,
+    pub status: String,
+    pub value1: Option<String>,
+    pub value2: Option<String>,
+}
+
+impl JsonPathDiffJson {
+    fn from(json_path_diff: JsonPathDiff) -> Self {
+        Self {
+            path: json_path_diff.path,
+            status: json_path_diff.status.as_str().to_string(),
+            value1: json_path_diff.value1,
+            value2: json_path_diff.value2,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineSegmentJson {
+    pub status: String,
+    pub text: String,
+}
+
+impl InlineSegmentJson {
+    fn from(segment: InlineSegment) -> Self {
+        Self { status: segment.status.as_str().to_string(), text: segment.text }
+    }
+}
+
+// 指定された場合だけ、指定した文字数以上の文字列の変更を単語単位で比較して返す
+fn with_inline_diffs(snapshot_diff: SnapshotDiff, inline_diff_min_chars: Option<usize>) -> SnapshotDiff {
+    match inline_diff_min_chars {
+        Some(min_chars) => snapshot_diff.with_inline_diffs(min_chars),
+        None => snapshot_diff,
+    }
+}
+
 #[tauri::command]
 pub async fn find_snapshot_diff_command(
     app_state: State<'_, AppState>,
     snapshot_id1: SnapshotId,
     snapshot_id2: SnapshotId,
+    ignore_rules: Option<Vec<String>>,
+    renames: Option<Vec<String>>,
+    inline_diff_min_chars: Option<usize>,
 ) -> Result<SnapshotDiffJson, String> {
     logger::info("start find_snapshot_diff_command");
 
+    let ignore_rules = IgnoreRules::parse(&ignore_rules.unwrap_or_default()).map_err(|e| e.to_string())?;
+    let renames = RenameRules::parse(&renames.unwrap_or_default()).map_err(|e| e.to_string())?;
+
     let conn = app_state.conn.lock().unwrap();
 
-    let snapshot_diff = match find_snapshot_diff(&conn, &snapshot_id1, &snapshot_id2).map_err(|e| e.to_string())? {
+    let snapshot_diff = match find_snapshot_diff(&conn, &snapshot_id1, &snapshot_id2, &ignore_rules, &renames).map_err(|e| e.to_string())? {
         Some(snapshot_diff) => Ok(snapshot_diff),
         None => Err("snapshot diff not created".to_string()),
     }?;
 
-    let x = Ok(SnapshotDiffJson::from(snapshot_diff));
+    let x = Ok(SnapshotDiffJson::from(with_inline_diffs(snapshot_diff, inline_diff_min_chars)));
     logger::info("end   find_snapshot_diff_command");
     x
 }
@@ -112,46 +280,190 @@ pub async fn create_snapshot_diff_command(
     app_state: State<'_, AppState>,
     snapshot_id1: SnapshotId,
     snapshot_id2: SnapshotId,
+    ignore_rules: Option<Vec<String>>,
+    renames: Option<Vec<String>>,
+    inline_diff_min_chars: Option<usize>,
 ) -> Result<SnapshotDiffJson, String> {
     logger::info("start create_snapshot_diff_command");
 
+    let ignore_rules = IgnoreRules::parse(&ignore_rules.unwrap_or_default()).map_err(|e| e.to_string())?;
+    let renames = renames.map(|renames| RenameRules::parse(&renames)).transpose().map_err(|e| e.to_string())?;
+
     let conn = app_state.conn.lock().unwrap();
 
     let table_snapshots1 = find_table_snapshots(&conn, &snapshot_id1).map_err(|e| e.to_string())?;
-    let table_snapshots1: HashMap<&TableName, &TableSnapshot> = table_snapshots1
-        .iter()
-        .into_group_map_by(|table_snapshot| &table_snapshot.table_name)
-        .iter()
-        .map(|(&table_name, table_snapshots)| (table_name, table_snapshots[0]))
-        .collect();
-
     let table_snapshots2 = find_table_snapshots(&conn, &snapshot_id2).map_err(|e| e.to_string())?;
-    let table_snapshots2: HashMap<&TableName, &TableSnapshot> = table_snapshots2
-        .iter()
-        .into_group_map_by(|table_snapshot| &table_snapshot.table_name)
-        .iter()
-        .map(|(&table_name, table_snapshots)| (table_name, table_snapshots[0]))
-        .collect();
-
-    let mut table_names1 = table_snapshots1.keys().cloned().collect_vec();
-    let mut table_names2 = table_snapshots2.keys().cloned().collect_vec();
-    table_names1.append(&mut table_names2);
-
-    let snapshot_diff = SnapshotDiff::new(
-        &create_diff_id(),
-        &snapshot_id1,
-        &snapshot_id2,
-        table_names1
-            .into_iter()
-            .unique()
-            .map(|table_name| create_table_diff(table_snapshots1.get(table_name).copied(), table_snapshots2.get(table_name).copied()))
-            .filter(|table_diff| !table_diff.empty())
-            .collect(),
-    );
+
+    // 指定がなければ、検出した名前の変更を候補として返す ( 比較には、ユーザーが確認して指定したものだけを使う )
+    let suggested_renames = match renames {
+        Some(_) => RenameRules::default(),
+        None => detect_renames(&table_snapshots1, &table_snapshots2),
+    };
+    let renames = renames.unwrap_or_default();
+    let table_snapshots1 = renames.apply(table_snapshots1);
+
+    let table_definitions1 = find_table_definitions(&conn, &snapshot_id1)
+        .map_err(|e| e.to_string())?
+        .map(|table_definitions| renames.apply_definitions(table_definitions));
+    let table_definitions2 = find_table_definitions(&conn, &snapshot_id2).map_err(|e| e.to_string())?;
+    let db_objects1 = find_db_objects(&conn, &snapshot_id1).map_err(|e| e.to_string())?;
+    let db_objects2 = find_db_objects(&conn, &snapshot_id2).map_err(|e| e.to_string())?;
+
+    let snapshot_diff = create_snapshot_diff(&snapshot_id1, &table_snapshots1, &snapshot_id2, &table_snapshots2, &ignore_rules)
+        .with_schema_changes(create_schema_changes(table_definitions1.as_deref(), table_definitions2.as_deref()))
+        .with_db_object_diffs(create_db_object_diffs(db_objects1.as_deref(), db_objects2.as_deref()))
+        .with_renames(&renames)
+        .with_suggested_renames(&suggested_renames);
 
     insert_snapshot_diff(&conn, &snapshot_diff).map_err(|e| e.to_string())?;
 
-    let x = Ok(SnapshotDiffJson::from(snapshot_diff));
+    let x = Ok(SnapshotDiffJson::from(with_inline_diffs(snapshot_diff, inline_diff_min_chars)));
     logger::info("end   create_snapshot_diff_command");
     x
 }
+
+// 保存しない場合、現在の状態は LIVE_SNAPSHOT_ID のスナップショットとして返す
+const LIVE_SNAPSHOT_ID: &str = "live";
+
+// スナップショットと現在のデータベースを比較する ( snapshot_name を指定した場合だけ、現在の状態をスナップショットとして保存する )
+#[tauri::command]
+pub async fn diff_live_command(
+    app_state: State<'_, AppState>,
+    snapshot_id: SnapshotId,
+    ignore_rules: Option<Vec<String>>,
+    snapshot_name: Option<SnapshotName>,
+    inline_diff_min_chars: Option<usize>,
+) -> Result<SnapshotDiffJson, String> {
+    logger::info("start diff_live_command");
+
+    let ignore_rules = IgnoreRules::parse(&ignore_rules.unwrap_or_default()).map_err(|e| e.to_string())?;
+
+    let conn = app_state.conn.lock().unwrap();
+    let project_id = app_state.project_id.lock().unwrap();
+    let project_id = project_id.as_ref().unwrap();
+
+    let projects = all_projects(&conn).map_err(|e| e.to_string())?;
+    let project = projects.iter().find(|project| &project.project_id == project_id).unwrap();
+
+    let table_snapshots = find_table_snapshots(&conn, &snapshot_id).map_err(|e| e.to_string())?;
+    let table_definitions = find_table_definitions(&conn, &snapshot_id).map_err(|e| e.to_string())?;
+    let db_objects = find_db_objects(&conn, &snapshot_id).map_err(|e| e.to_string())?;
+    let live_snapshot = dump_live(&conn, project, &snapshot_id).map_err(|e| e.to_string())?;
+
+    let live_snapshot_id = match snapshot_name {
+        Some(_) => create_snapshot_id(),
+        None => LIVE_SNAPSHOT_ID.to_string(),
+    };
+    let snapshot_diff = create_snapshot_diff(&snapshot_id, &table_snapshots, &live_snapshot_id, &live_snapshot.table_snapshots, &ignore_rules)
+        .with_schema_changes(create_schema_changes(table_definitions.as_deref(), Some(&live_snapshot.table_definitions)))
+        .with_db_object_diffs(create_db_object_diffs(db_objects.as_deref(), live_snapshot.db_objects.as_deref()));
+
+    // スナップショットだけが残って差分が保存されない状態にならないように、まとめて保存する
+    if let Some(snapshot_name) = snapshot_name {
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            keep_live(&conn, project, &live_snapshot_id, snapshot_name, live_snapshot)?;
+            insert_snapshot_diff(&conn, &snapshot_diff)
+        })
+        .map_err(|e| e.to_string())?;
+    }
+
+    let x = Ok(SnapshotDiffJson::from(with_inline_diffs(snapshot_diff, inline_diff_min_chars)));
+    logger::info("end   diff_live_command");
+    x
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrossProjectDiffJson {
+    pub snapshot_diff: SnapshotDiffJson,
+    pub table_mappings: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+// 別々のプロジェクトのスナップショットを比較する ( テーブル名の対応づけを変えて比較し直せるように、差分は保存しない )
+#[tauri::command]
+pub async fn create_cross_project_diff_command(
+    app_state: State<'_, AppState>,
+    snapshot_id1: SnapshotId,
+    snapshot_id2: SnapshotId,
+    ignore_rules: Option<Vec<String>>,
+    table_mappings: Option<Vec<String>>,
+    inline_diff_min_chars: Option<usize>,
+) -> Result<CrossProjectDiffJson, String> {
+    logger::info("start create_cross_project_diff_command");
+
+    let ignore_rules = IgnoreRules::parse(&ignore_rules.unwrap_or_default()).map_err(|e| e.to_string())?;
+    let table_mappings = TableMappings::parse(&table_mappings.unwrap_or_default()).map_err(|e| e.to_string())?;
+
+    let conn = app_state.conn.lock().unwrap();
+
+    let table_snapshots1 = find_table_snapshots(&conn, &snapshot_id1).map_err(|e| e.to_string())?;
+    let table_snapshots2 = table_mappings.apply(find_table_snapshots(&conn, &snapshot_id2).map_err(|e| e.to_string())?);
+
+    let table_definitions1 = find_table_definitions(&conn, &snapshot_id1).map_err(|e| e.to_string())?;
+    let table_definitions2 = find_table_definitions(&conn, &snapshot_id2)
+        .map_err(|e| e.to_string())?
+        .map(|table_definitions| table_mappings.apply_definitions(table_definitions));
+    let db_objects1 = find_db_objects(&conn, &snapshot_id1).map_err(|e| e.to_string())?;
+    let db_objects2 = find_db_objects(&conn, &snapshot_id2).map_err(|e| e.to_string())?;
+
+    let dump_configs1 = find_dump_config(&conn, &snapshot_id1).map_err(|e| e.to_string())?;
+    let dump_configs2 = find_dump_config(&conn, &snapshot_id2).map_err(|e| e.to_string())?;
+
+    let snapshot_diff = create_snapshot_diff(&snapshot_id1, &table_snapshots1, &snapshot_id2, &table_snapshots2, &ignore_rules)
+        .with_schema_changes(create_schema_changes(table_definitions1.as_deref(), table_definitions2.as_deref()))
+        .with_db_object_diffs(create_db_object_diffs(db_objects1.as_deref(), db_objects2.as_deref()));
+
+    let x = Ok(CrossProjectDiffJson {
+        snapshot_diff: SnapshotDiffJson::from(with_inline_diffs(snapshot_diff, inline_diff_min_chars)),
+        table_mappings: table_mappings.as_strings(),
+        warnings: DumpConfig::find_incompatibilities(&dump_configs1, &dump_configs2, &table_mappings),
+    });
+    logger::info("end   create_cross_project_diff_command");
+    x
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainedDiffJson {
+    pub snapshot_summaries: Vec<SnapshotSummaryJson>,
+    pub snapshot_diffs: Vec<SnapshotDiffJson>,
+    pub net_diff: SnapshotDiffJson,
+}
+
+// 選択したスナップショットを作成順に並べ、隣り合うもの同士の差分と全体の変化をまとめて返す ( 保存済みの差分は再利用する )
+#[tauri::command]
+pub async fn create_chained_diff_command(
+    app_state: State<'_, AppState>,
+    snapshot_ids: Vec<SnapshotId>,
+    ignore_rules: Option<Vec<String>>,
+    inline_diff_min_chars: Option<usize>,
+) -> Result<ChainedDiffJson, String> {
+    logger::info("start create_chained_diff_command");
+
+    let ignore_rules = IgnoreRules::parse(&ignore_rules.unwrap_or_default()).map_err(|e| e.to_string())?;
+
+    let conn = app_state.conn.lock().unwrap();
+    let project_id = app_state.project_id.lock().unwrap();
+    let project_id = project_id.as_ref().unwrap();
+
+    let snapshot_summaries = all_snapshot_summaries(&conn, project_id)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|snapshot_summary| snapshot_ids.contains(&snapshot_summary.snapshot_id))
+        .sorted_by(|s1, s2| s1.create_at.cmp(&s2.create_at))
+        .collect_vec();
+
+    let (snapshot_diffs, net_diff) = find_or_insert_chained_diffs(&conn, &snapshot_summaries, &ignore_rules).map_err(|e| e.to_string())?;
+
+    let x = Ok(ChainedDiffJson {
+        snapshot_summaries: snapshot_summaries.into_iter().map(SnapshotSummaryJson::from).collect(),
+        snapshot_diffs: snapshot_diffs
+            .into_iter()
+            .map(|snapshot_diff| SnapshotDiffJson::from(with_inline_diffs(snapshot_diff, inline_diff_min_chars)))
+            .collect(),
+        net_diff: SnapshotDiffJson::from(with_inline_diffs(net_diff, inline_diff_min_chars)),
+    });
+    logger::info("end   create_chained_diff_command");
+    x
+}
@@ -19,15 +19,37 @@ pub struct DumpConfigJson {
     pub table_name: TableName,
     pub col_names: Vec<ColName>,
     pub value: String,
+    pub row_limit: Option<usize>,
+    pub filter: Option<String>,
+    #[serde(default)]
+    pub excluded_col_names: Vec<ColName>,
+    #[serde(default)]
+    pub key_col_names: Vec<ColName>,
+    #[serde(default)]
+    pub multiset: bool,
 }
 
 impl DumpConfigJson {
     fn from(dump_config: DumpConfig) -> Self {
-        Self { table_name: dump_config.table_name, col_names: dump_config.col_names, value: dump_config.value }
+        Self {
+            table_name: dump_config.table_name,
+            col_names: dump_config.col_names,
+            value: dump_config.value,
+            row_limit: dump_config.row_limit,
+            filter: dump_config.filter,
+            excluded_col_names: dump_config.excluded_col_names,
+            key_col_names: dump_config.key_col_names,
+            multiset: dump_config.multiset,
+        }
     }
 
     pub fn into(self) -> DumpConfig {
         DumpConfig::new(self.table_name, self.col_names, self.value)
+            .with_row_limit(self.row_limit)
+            .with_filter(self.filter)
+            .with_excluded_col_names(self.excluded_col_names)
+            .with_key_col_names(self.key_col_names)
+            .with_multiset(self.multiset)
     }
 }
 
@@ -1,5 +1,9 @@
+pub mod archive;
 pub mod diff;
 pub mod dump_config;
 pub mod project;
+pub mod report;
+pub mod script;
 pub mod snapshot;
 pub mod state;
+pub mod timeline;
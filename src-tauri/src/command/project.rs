@@ -4,7 +4,7 @@ use tauri::State;
 
 use crate::command::state::AppState;
 use crate::db::project::{all_projects, delete_project, insert_project, update_project};
-use crate::domain::project::Rdbms::Mysql;
+use crate::domain::project::Rdbms::{Mariadb, Mysql, Postgres, Sqlite};
 use crate::domain::project::{Project, ProjectId};
 use crate::logger;
 
@@ -20,6 +20,8 @@ pub struct ProjectJson {
     pub host: String,
     pub port: String,
     pub schema: String,
+    #[serde(default)]
+    pub local: bool,
 }
 
 impl ProjectJson {
@@ -30,12 +32,16 @@ impl ProjectJson {
             color: project.color,
             rdbms: match project.rdbms {
                 Mysql => "MySQL".to_string(),
+                Mariadb => "MariaDB".to_string(),
+                Postgres => "PostgreSQL".to_string(),
+                Sqlite => "SQLite".to_string(),
             },
             user: project.user,
             password: project.password,
             host: project.host,
             port: project.port,
             schema: project.schema,
+            local: project.local,
         }
     }
 
@@ -46,6 +52,9 @@ impl ProjectJson {
             &self.color,
             match self.rdbms.as_ref() {
                 "MySQL" => Mysql,
+                "MariaDB" => Mariadb,
+                "PostgreSQL" => Postgres,
+                "SQLite" => Sqlite,
                 _ => unreachable!(),
             },
             &self.user,
@@ -54,6 +63,7 @@ impl ProjectJson {
             &self.port,
             &self.schema,
         )
+        .with_local(self.local)
     }
 }
 
@@ -77,7 +87,7 @@ pub async fn select_project_command(app_state: State<'_, AppState>, project_id:
     let projects = all_projects(&conn).map_err(|e| e.to_string())?;
     let project = projects.iter().find(|project| project.project_id == project_id).unwrap();
 
-    let x = match project.create_connection() {
+    let x = match project.test_connection() {
         Ok(_) => {
             app_state.set_project_id(project_id);
             Ok(())
@@ -94,7 +104,7 @@ pub async fn test_connection_project_command(project_json: ProjectJson) -> Resul
 
     let project = project_json.into();
 
-    let x = match &project.create_connection() {
+    let x = match &project.test_connection() {
         Ok(_) => Ok(project.create_url()),
         Err(_e) => Err(project.create_url()),
     };
@@ -0,0 +1,54 @@
+use std::path::Path;
+
+use tauri::State;
+
+use crate::command::state::AppState;
+use crate::db::diff::find_snapshot_diff;
+use crate::db::snapshot::all_snapshot_summaries;
+use crate::domain::ignore_rule::IgnoreRules;
+use crate::domain::rename::RenameRules;
+use crate::domain::snapshot::SnapshotId;
+use crate::logger;
+use crate::report::{write_report, ReportFormat};
+
+#[tauri::command]
+pub async fn export_diff_report_command(
+    app_state: State<'_, AppState>,
+    snapshot_id1: SnapshotId,
+    snapshot_id2: SnapshotId,
+    ignore_rules: Option<Vec<String>>,
+    renames: Option<Vec<String>>,
+    format: String,
+    path: String,
+) -> Result<Vec<String>, String> {
+    logger::info("start export_diff_report_command");
+
+    let ignore_rules = IgnoreRules::parse(&ignore_rules.unwrap_or_default()).map_err(|e| e.to_string())?;
+    let renames = RenameRules::parse(&renames.unwrap_or_default()).map_err(|e| e.to_string())?;
+    let format = ReportFormat::parse(&format).map_err(|e| e.to_string())?;
+
+    let conn = app_state.conn.lock().unwrap();
+    let project_id = app_state.project_id.lock().unwrap();
+    let project_id = project_id.as_ref().unwrap();
+
+    let snapshot_diff = match find_snapshot_diff(&conn, &snapshot_id1, &snapshot_id2, &ignore_rules, &renames).map_err(|e| e.to_string())? {
+        Some(snapshot_diff) => Ok(snapshot_diff),
+        None => Err("snapshot diff not created".to_string()),
+    }?;
+
+    // スナップショット名を見出しにする
+    let snapshot_summaries = all_snapshot_summaries(&conn, project_id).map_err(|e| e.to_string())?;
+    let snapshot_name = |snapshot_id: &SnapshotId| {
+        snapshot_summaries
+            .iter()
+            .find(|snapshot_summary| &snapshot_summary.snapshot_id == snapshot_id)
+            .map_or(snapshot_id.clone(), |snapshot_summary| snapshot_summary.snapshot_name.clone())
+    };
+    let title = format!("{} → {}", snapshot_name(&snapshot_id1), snapshot_name(&snapshot_id2));
+
+    let x = write_report(&format, Path::new(&path), &title, &snapshot_diff.table_diffs)
+        .map(|paths| paths.iter().map(|path| path.display().to_string()).collect())
+        .map_err(|e| e.to_string());
+    logger::info("end   export_diff_report_command");
+    x
+}
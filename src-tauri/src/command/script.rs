@@ -0,0 +1,40 @@
+use tauri::State;
+
+use crate::command::state::AppState;
+use crate::db::diff::find_snapshot_diff;
+use crate::db::project::all_projects;
+use crate::domain::ignore_rule::IgnoreRules;
+use crate::domain::rename::RenameRules;
+use crate::domain::snapshot::SnapshotId;
+use crate::logger;
+use crate::script::create_script;
+
+#[tauri::command]
+pub async fn create_sql_script_command(
+    app_state: State<'_, AppState>,
+    snapshot_id1: SnapshotId,
+    snapshot_id2: SnapshotId,
+    ignore_rules: Option<Vec<String>>,
+    renames: Option<Vec<String>>,
+) -> Result<String, String> {
+    logger::info("start create_sql_script_command");
+
+    let ignore_rules = IgnoreRules::parse(&ignore_rules.unwrap_or_default()).map_err(|e| e.to_string())?;
+    let renames = RenameRules::parse(&renames.unwrap_or_default()).map_err(|e| e.to_string())?;
+
+    let conn = app_state.conn.lock().unwrap();
+    let project_id = app_state.project_id.lock().unwrap();
+    let project_id = project_id.as_ref().unwrap();
+
+    let projects = all_projects(&conn).map_err(|e| e.to_string())?;
+    let project = projects.iter().find(|project| &project.project_id == project_id).unwrap();
+
+    let snapshot_diff = match find_snapshot_diff(&conn, &snapshot_id1, &snapshot_id2, &ignore_rules, &renames).map_err(|e| e.to_string())? {
+        Some(snapshot_diff) => Ok(snapshot_diff),
+        None => Err("snapshot diff not created".to_string()),
+    }?;
+
+    let x = create_script(&project.rdbms, &snapshot_diff.table_diffs).map_err(|e| e.to_string());
+    logger::info("end   create_sql_script_command");
+    x
+}
@@ -8,9 +8,10 @@ use crate::db::dump_config::insert_dump_configs;
 use crate::db::project::all_projects;
 use crate::db::snapshot::{all_snapshot_summaries, delete_snapshot_summary, update_snapshot_summary};
 use crate::db::snapshot_result::{find_snapshot_result, update_snapshot_result};
+use crate::domain::project::ProjectId;
 use crate::domain::snapshot::{SnapshotId, SnapshotName, SnapshotSummary};
 use crate::domain::snapshot_result::SnapshotResult;
-use crate::dump::dump;
+use crate::dump::{dump, restore};
 use crate::logger;
 
 #[derive(Serialize, Deserialize)]
@@ -22,7 +23,7 @@ pub struct SnapshotSummaryJson {
 }
 
 impl SnapshotSummaryJson {
-    fn from(snapshot_summary: SnapshotSummary) -> Self {
+    pub fn from(snapshot_summary: SnapshotSummary) -> Self {
         Self { snapshot_id: snapshot_summary.snapshot_id, snapshot_name: snapshot_summary.snapshot_name, create_at: snapshot_summary.create_at }
     }
 
@@ -32,12 +33,17 @@ impl SnapshotSummaryJson {
 }
 
 #[tauri::command]
-pub async fn all_snapshot_summaries_command(app_state: State<'_, AppState>) -> Result<Vec<SnapshotSummaryJson>, String> {
+pub async fn all_snapshot_summaries_command(
+    app_state: State<'_, AppState>,
+    project_id: Option<ProjectId>,
+) -> Result<Vec<SnapshotSummaryJson>, String> {
     logger::info("start all_snapshot_summaries_command");
 
     let conn = app_state.conn.lock().unwrap();
-    let project_id = app_state.project_id.lock().unwrap();
-    let project_id = project_id.as_ref().unwrap();
+    let selected_project_id = app_state.project_id.lock().unwrap();
+
+    // 別のプロジェクトのスナップショットと比較する場合は、プロジェクトを指定する
+    let project_id = project_id.as_ref().or(selected_project_id.as_ref()).unwrap();
 
     let x = all_snapshot_summaries(&conn, project_id)
         .map(|snapshot_summaries| snapshot_summaries.into_iter().map(SnapshotSummaryJson::from).collect_vec())
@@ -101,6 +107,27 @@ pub async fn dump_snapshot_command(
     }
 }
 
+// ローカルや開発用の環境でないプロジェクトは、画面で確認してからでないとリストアしない
+#[tauri::command]
+pub async fn restore_snapshot_command(app_state: State<'_, AppState>, snapshot_id: SnapshotId, confirmed: bool) -> Result<usize, String> {
+    logger::info("start restore_snapshot_command");
+
+    let conn = app_state.conn.lock().unwrap();
+    let project_id = app_state.project_id.lock().unwrap();
+    let project_id = project_id.as_ref().unwrap();
+
+    let projects = all_projects(&conn).map_err(|e| e.to_string())?;
+    let project = projects.iter().find(|project| &project.project_id == project_id).unwrap();
+
+    if !project.local && !confirmed {
+        return Err("restore not confirmed: the project is not marked as a local environment".to_string());
+    }
+
+    let x = restore(&conn, project, &snapshot_id).map_err(|e| e.to_string());
+    logger::info("end   restore_snapshot_command");
+    x
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SnapshotResultJson {
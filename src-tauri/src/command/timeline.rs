@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::command::state::AppState;
+use crate::db::snapshot::{all_snapshot_summaries, find_table_snapshot};
+use crate::domain::schema::{ColName, PrimaryColName, PrimaryValue, TableName};
+use crate::domain::snapshot::{SnapshotId, SnapshotName};
+use crate::domain::timeline::{create_row_timeline, RowPoint, RowState, RowTimeline};
+use crate::logger;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowTimelineJson {
+    pub table_name: TableName,
+    pub primary_col_name: PrimaryColName,
+    pub primary_value: PrimaryValue,
+    pub col_names: Vec<ColName>,
+    pub points: Vec<RowPointJson>,
+}
+
+impl RowTimelineJson {
+    fn from(row_timeline: RowTimeline) -> Self {
+        Self {
+            table_name: row_timeline.table_name,
+            primary_col_name: row_timeline.primary_col_name,
+            primary_value: row_timeline.primary_value,
+            col_names: row_timeline.col_names,
+            points: row_timeline.points.into_iter().map(RowPointJson::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowPointJson {
+    pub snapshot_id: SnapshotId,
+    pub snapshot_name: SnapshotName,
+    pub create_at: String,
+    pub state: String,
+    pub values: HashMap<ColName, String>,
+    pub changed_col_names: Vec<ColName>,
+}
+
+impl RowPointJson {
+    fn from(row_point: RowPoint) -> Self {
+        Self {
+            snapshot_id: row_point.snapshot_summary.snapshot_id,
+            snapshot_name: row_point.snapshot_summary.snapshot_name,
+            create_at: row_point.snapshot_summary.create_at,
+            state: match row_point.state {
+                RowState::Present => "present",
+                RowState::Absent => "absent",
+                RowState::Unknown => "unknown",
+            }
+            .to_string(),
+            values: row_point.col_values.into_iter().map(|(col_name, col_value)| (col_name, col_value.as_display_value())).collect(),
+            changed_col_names: row_point.changed_col_names,
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn find_row_timeline_command(
+    app_state: State<'_, AppState>,
+    table_name: TableName,
+    primary_value: PrimaryValue,
+) -> Result<RowTimelineJson, String> {
+    logger::info("start find_row_timeline_command");
+
+    let conn = app_state.conn.lock().unwrap();
+    let project_id = app_state.project_id.lock().unwrap();
+    let project_id = project_id.as_ref().unwrap();
+
+    let snapshot_summaries = all_snapshot_summaries(&conn, project_id).map_err(|e| e.to_string())?;
+
+    let mut snapshots = vec![];
+    for snapshot_summary in snapshot_summaries {
+        let table_snapshot = find_table_snapshot(&conn, &snapshot_summary.snapshot_id, &table_name).map_err(|e| e.to_string())?;
+        snapshots.push((snapshot_summary, table_snapshot));
+    }
+
+    let x = Ok(RowTimelineJson::from(create_row_timeline(&table_name, &primary_value, snapshots)));
+    logger::info("end   find_row_timeline_command");
+    x
+}
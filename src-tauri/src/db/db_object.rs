@@ -0,0 +1,87 @@
+use anyhow::anyhow;
+use diesel::prelude::*;
+use diesel::{RunQueryDsl, SqliteConnection};
+
+use schema::db_objects as db_objects_table;
+
+use crate::db::schema;
+use crate::domain::db_object::DbObject;
+use crate::domain::snapshot::SnapshotId;
+
+// オブジェクトがないデータベースと区別できるように、スナップショットごとに 1 行で記録する
+#[derive(Queryable, Insertable)]
+#[table_name = "db_objects_table"]
+struct DbObjectsRecord {
+    snapshot_id: SnapshotId,
+    data: String,
+}
+
+impl DbObjectsRecord {
+    fn from(db_objects: &[DbObject], fk: &SnapshotId) -> Self {
+        Self { snapshot_id: fk.clone(), data: serde_json::to_string(db_objects).unwrap() }
+    }
+
+    fn to(self) -> Vec<DbObject> {
+        serde_json::from_str(&self.data).unwrap()
+    }
+}
+
+// オブジェクトを記録する前に作成したスナップショットや、取得に失敗したスナップショットでは None になる
+pub fn find_db_objects(conn: &SqliteConnection, snapshot_id: &SnapshotId) -> anyhow::Result<Option<Vec<DbObject>>> {
+    let row: Option<DbObjectsRecord> =
+        schema::db_objects::table.filter(schema::db_objects::snapshot_id.eq(snapshot_id)).first(conn).optional().map_err(|e| anyhow!(e))?;
+    Ok(row.map(|row| row.to()))
+}
+
+pub fn insert_db_objects(conn: &SqliteConnection, snapshot_id: &SnapshotId, db_objects: &[DbObject]) -> anyhow::Result<()> {
+    diesel::insert_into(schema::db_objects::table).values(DbObjectsRecord::from(db_objects, snapshot_id)).execute(conn).map_err(|e| anyhow!(e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::db_object::{find_db_objects, insert_db_objects};
+    use crate::db::project::insert_project;
+    use crate::db::snapshot::{delete_snapshot_summary, insert_snapshot_summary};
+    use crate::db::{create_sqlite_connection, migrate_sqlite};
+    use crate::domain::db_object::DbObject;
+    use crate::domain::db_object::DbObjectType::{Trigger, View};
+    use crate::domain::project::Rdbms::Mysql;
+    use crate::domain::project::{create_project_id, Project};
+    use crate::domain::snapshot::{create_snapshot_id, SnapshotSummary};
+
+    #[test]
+    fn db_object() -> anyhow::Result<()> {
+        // setup
+
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+
+        let project_id = create_project_id();
+
+        let project = Project::new(&project_id, "test-project", "red", Mysql, "user", "password", "127.0.0.1", "3306", "test-db");
+        insert_project(&conn, &project)?;
+
+        let snapshot_id1 = create_snapshot_id();
+        insert_snapshot_summary(&conn, &project_id, &SnapshotSummary::new(&snapshot_id1, "test1", "2023-07-03 08:17:52"))?;
+        let snapshot_id2 = create_snapshot_id();
+        insert_snapshot_summary(&conn, &project_id, &SnapshotSummary::new(&snapshot_id2, "test2", "2023-07-03 08:17:53"))?;
+
+        // find
+        assert_eq!(None, find_db_objects(&conn, &snapshot_id1)?);
+
+        // insert
+        let db_objects = vec![DbObject::new(View, "active_users", "select * from users"), DbObject::new(Trigger, "users_bi", "begin\nend")];
+        insert_db_objects(&conn, &snapshot_id1, &db_objects)?;
+        insert_db_objects(&conn, &snapshot_id2, &[])?;
+
+        assert_eq!(Some(db_objects), find_db_objects(&conn, &snapshot_id1)?);
+        assert_eq!(Some(vec![]), find_db_objects(&conn, &snapshot_id2)?);
+
+        // スナップショットと一緒に削除される
+        delete_snapshot_summary(&conn, &snapshot_id1)?;
+        assert_eq!(None, find_db_objects(&conn, &snapshot_id1)?);
+
+        Ok(())
+    }
+}
@@ -2,25 +2,60 @@ use anyhow::anyhow;
 use diesel::prelude::*;
 use diesel::{RunQueryDsl, SqliteConnection};
 
+use crate::db::db_object::find_db_objects;
 use crate::db::schema;
-use crate::domain::diff::SnapshotDiff;
-use crate::domain::snapshot::SnapshotId;
+use crate::db::snapshot::find_table_snapshots;
+use crate::db::table_definition::find_table_definitions;
+use crate::domain::db_object::create_db_object_diffs;
+use crate::domain::diff::{create_snapshot_diff, SnapshotDiff};
+use crate::domain::ignore_rule::IgnoreRules;
+use crate::domain::rename::RenameRules;
+use crate::domain::snapshot::{SnapshotId, SnapshotSummary};
+use crate::domain::table_definition::create_schema_changes;
 
-pub fn find_snapshot_diff(conn: &SqliteConnection, snapshot_id1: &SnapshotId, snapshot_id2: &SnapshotId) -> anyhow::Result<Option<SnapshotDiff>> {
+// 除外ルールと名前の変更の組み合わせごとに、別の差分として保存している
+pub fn find_snapshot_diff(
+    conn: &SqliteConnection,
+    snapshot_id1: &SnapshotId,
+    snapshot_id2: &SnapshotId,
+    ignore_rules: &IgnoreRules,
+    renames: &RenameRules,
+) -> anyhow::Result<Option<SnapshotDiff>> {
     let rows: Vec<String> = schema::snapshot_diffs::table
         .select(schema::snapshot_diffs::data)
-        .filter(schema::snapshot_diffs::snapshot_id1.eq(snapshot_id1).and(schema::snapshot_diffs::snapshot_id2.eq(snapshot_id2)))
+        .filter(
+            schema::snapshot_diffs::snapshot_id1
+                .eq(snapshot_id1)
+                .and(schema::snapshot_diffs::snapshot_id2.eq(snapshot_id2))
+                .and(schema::snapshot_diffs::ignore_rules.eq(ignore_rules.as_key()))
+                .and(schema::snapshot_diffs::renames.eq(renames.as_key())),
+        )
         .load(conn)
         .map_err(|e| anyhow!(e))?;
     Ok(rows.into_iter().next().map(|data| serde_json::from_str(&data).unwrap()))
 }
 
+// 両方のスナップショットが指定したものに含まれる差分を、除外ルールの組み合わせも含めてすべて取得する
+pub fn find_snapshot_diffs(conn: &SqliteConnection, snapshot_ids: &[SnapshotId]) -> anyhow::Result<Vec<SnapshotDiff>> {
+    let rows: Vec<String> = schema::snapshot_diffs::table
+        .select(schema::snapshot_diffs::data)
+        .filter(schema::snapshot_diffs::snapshot_id1.eq_any(snapshot_ids).and(schema::snapshot_diffs::snapshot_id2.eq_any(snapshot_ids)))
+        .load(conn)
+        .map_err(|e| anyhow!(e))?;
+    Ok(rows.into_iter().map(|data| serde_json::from_str(&data).unwrap()).collect())
+}
+
 pub fn insert_snapshot_diff(conn: &SqliteConnection, snapshot_diff: &SnapshotDiff) -> anyhow::Result<()> {
+    let ignore_rules = IgnoreRules::parse(&snapshot_diff.ignore_rules)?;
+    let renames = RenameRules::parse(&snapshot_diff.renames)?;
+
     diesel::insert_into(schema::snapshot_diffs::table)
         .values((
             schema::snapshot_diffs::diff_id.eq(&snapshot_diff.diff_id),
             schema::snapshot_diffs::snapshot_id1.eq(&snapshot_diff.snapshot_id1),
             schema::snapshot_diffs::snapshot_id2.eq(&snapshot_diff.snapshot_id2),
+            schema::snapshot_diffs::ignore_rules.eq(ignore_rules.as_key()),
+            schema::snapshot_diffs::renames.eq(renames.as_key()),
             schema::snapshot_diffs::data.eq(serde_json::to_string(snapshot_diff).unwrap()),
         ))
         .execute(conn)
@@ -28,18 +63,66 @@ pub fn insert_snapshot_diff(conn: &SqliteConnection, snapshot_diff: &SnapshotDif
     Ok(())
 }
 
+// 保存済みの差分があればそれを使い、なければ差分を計算して保存する ( 差分の作り方を変えたときは、保存済みの差分を消すマイグレーションを追加する )
+pub fn find_or_insert_snapshot_diff(
+    conn: &SqliteConnection,
+    snapshot_id1: &SnapshotId,
+    snapshot_id2: &SnapshotId,
+    ignore_rules: &IgnoreRules,
+) -> anyhow::Result<SnapshotDiff> {
+    if let Some(snapshot_diff) = find_snapshot_diff(conn, snapshot_id1, snapshot_id2, ignore_rules, &RenameRules::default())? {
+        return Ok(snapshot_diff);
+    }
+
+    let table_snapshots1 = find_table_snapshots(conn, snapshot_id1)?;
+    let table_snapshots2 = find_table_snapshots(conn, snapshot_id2)?;
+    let schema_changes =
+        create_schema_changes(find_table_definitions(conn, snapshot_id1)?.as_deref(), find_table_definitions(conn, snapshot_id2)?.as_deref());
+    let db_object_diffs = create_db_object_diffs(find_db_objects(conn, snapshot_id1)?.as_deref(), find_db_objects(conn, snapshot_id2)?.as_deref());
+    let snapshot_diff = create_snapshot_diff(snapshot_id1, &table_snapshots1, snapshot_id2, &table_snapshots2, ignore_rules)
+        .with_schema_changes(schema_changes)
+        .with_db_object_diffs(db_object_diffs);
+    insert_snapshot_diff(conn, &snapshot_diff)?;
+
+    Ok(snapshot_diff)
+}
+
+// 並べたスナップショットの隣り合うもの同士の差分と、最初と最後のスナップショットの差分 ( 全体の変化 ) を返す
+pub fn find_or_insert_chained_diffs(
+    conn: &SqliteConnection,
+    snapshot_summaries: &[SnapshotSummary],
+    ignore_rules: &IgnoreRules,
+) -> anyhow::Result<(Vec<SnapshotDiff>, SnapshotDiff)> {
+    let (first, last) = match (snapshot_summaries.first(), snapshot_summaries.last()) {
+        (Some(first), Some(last)) if snapshot_summaries.len() >= 2 => (first, last),
+        _ => return Err(anyhow!("select at least 2 snapshots")),
+    };
+
+    let snapshot_diffs = snapshot_summaries
+        .windows(2)
+        .map(|pair| find_or_insert_snapshot_diff(conn, &pair[0].snapshot_id, &pair[1].snapshot_id, ignore_rules))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    // 2 つだけの場合は、隣り合うスナップショットの差分として保存したものを取得する
+    let net_diff = find_or_insert_snapshot_diff(conn, &first.snapshot_id, &last.snapshot_id, ignore_rules)?;
+
+    Ok((snapshot_diffs, net_diff))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::db::diff::{find_snapshot_diff, insert_snapshot_diff};
+    use crate::db::diff::{find_or_insert_chained_diffs, find_snapshot_diff, insert_snapshot_diff};
     use crate::db::project::insert_project;
-    use crate::db::snapshot::insert_snapshot_summary;
-    use crate::db::{create_sqlite_connection, migrate_sqlite_if_missing};
+    use crate::db::snapshot::{insert_snapshot_summary, insert_table_snapshots};
+    use crate::db::{create_sqlite_connection, migrate_sqlite};
     use crate::domain::diff::ColDiff::{Deleted, NoValue};
     use crate::domain::diff::{create_diff_id, SnapshotDiff, TableDiff};
+    use crate::domain::ignore_rule::IgnoreRules;
     use crate::domain::project::Rdbms::Mysql;
     use crate::domain::project::{create_project_id, Project};
+    use crate::domain::rename::RenameRules;
     use crate::domain::snapshot::ColValue::{SimpleNumber, SimpleString};
-    use crate::domain::snapshot::{create_snapshot_id, ColValue, PrimaryColValues, SnapshotSummary};
+    use crate::domain::snapshot::{create_snapshot_id, ColValue, PrimaryColValues, RowSnapshot, SnapshotSummary, TableSnapshot};
     use diesel::RunQueryDsl;
 
     fn n(s: &str) -> ColValue {
@@ -54,7 +137,7 @@ mod tests {
     fn snapshot_diff() -> anyhow::Result<()> {
         // setup
 
-        migrate_sqlite_if_missing()?;
+        migrate_sqlite()?;
         let conn = create_sqlite_connection()?;
         diesel::sql_query("delete from projects").execute(&conn)?;
 
@@ -73,8 +156,12 @@ mod tests {
 
         let _table_name = "items".to_string();
 
+        let no_rules = IgnoreRules::default();
+        let rules = IgnoreRules::parse(&["*.updated_at".to_string()])?;
+        let no_renames = RenameRules::default();
+
         // find
-        let table_snapshot_opt = find_snapshot_diff(&conn, &snapshot_id1, &snapshot_id2)?;
+        let table_snapshot_opt = find_snapshot_diff(&conn, &snapshot_id1, &snapshot_id2, &no_rules, &no_renames)?;
         assert_eq!(None, table_snapshot_opt);
 
         // insert
@@ -87,11 +174,87 @@ mod tests {
         table_diff.primary_col_values.push(primary_col_values2);
 
         let snapshot_diff = SnapshotDiff::new(&create_diff_id(), &snapshot_id1, &snapshot_id2, vec![table_diff]);
+        let diff_id = snapshot_diff.diff_id.clone();
         insert_snapshot_diff(&conn, &snapshot_diff)?;
 
-        let table_snapshot_opt = find_snapshot_diff(&conn, &snapshot_id1, &snapshot_id2)?;
+        let table_snapshot_opt = find_snapshot_diff(&conn, &snapshot_id1, &snapshot_id2, &no_rules, &no_renames)?;
         assert_eq!(Some(snapshot_diff), table_snapshot_opt);
 
+        // 除外ルールが異なる差分は、別に保存される
+        let table_snapshot_opt = find_snapshot_diff(&conn, &snapshot_id1, &snapshot_id2, &rules, &no_renames)?;
+        assert_eq!(None, table_snapshot_opt);
+
+        let snapshot_diff_ignored = SnapshotDiff::new(&create_diff_id(), &snapshot_id1, &snapshot_id2, vec![]).with_ignore_rules(&rules);
+        insert_snapshot_diff(&conn, &snapshot_diff_ignored)?;
+
+        let table_snapshot_opt = find_snapshot_diff(&conn, &snapshot_id1, &snapshot_id2, &rules, &no_renames)?;
+        assert_eq!(Some(snapshot_diff_ignored), table_snapshot_opt);
+
+        // 名前の変更を指定した差分も、別に保存される
+        let renames = RenameRules::parse(&["members=users".to_string()])?;
+        assert_eq!(None, find_snapshot_diff(&conn, &snapshot_id1, &snapshot_id2, &no_rules, &renames)?);
+
+        let snapshot_diff_renamed = SnapshotDiff::new(&create_diff_id(), &snapshot_id1, &snapshot_id2, vec![]).with_renames(&renames);
+        insert_snapshot_diff(&conn, &snapshot_diff_renamed)?;
+
+        assert_eq!(Some(snapshot_diff_renamed), find_snapshot_diff(&conn, &snapshot_id1, &snapshot_id2, &no_rules, &renames)?);
+        assert_eq!(
+            Some(diff_id),
+            find_snapshot_diff(&conn, &snapshot_id1, &snapshot_id2, &no_rules, &no_renames)?.map(|snapshot_diff| snapshot_diff.diff_id)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn chained_diffs() -> anyhow::Result<()> {
+        // setup
+
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+
+        let project_id = create_project_id();
+
+        let project = Project::new(&project_id, "test-project", "red", Mysql, "user", "password", "127.0.0.1", "3306", "test-db");
+        insert_project(&conn, &project)?;
+
+        let table_name = "items".to_string();
+        let mut snapshot_summaries = vec![];
+        for (i, name) in ["apple", "banana", "cherry"].iter().enumerate() {
+            let snapshot_summary =
+                SnapshotSummary::new(&create_snapshot_id(), format!("step{i}").as_str(), format!("2023-07-03 08:00:0{i}").as_str());
+            insert_snapshot_summary(&conn, &project_id, &snapshot_summary)?;
+
+            let row_snapshots = vec![RowSnapshot::new(vec![n("1")], vec![s(name)])];
+            let table_snapshot = TableSnapshot::new(&table_name, "id".to_string(), vec!["name".to_string()], row_snapshots);
+            insert_table_snapshots(&conn, &snapshot_summary.snapshot_id, vec![table_snapshot])?;
+
+            snapshot_summaries.push(snapshot_summary);
+        }
+
+        let no_rules = IgnoreRules::default();
+
+        // 隣り合うスナップショットの差分と、全体の変化
+        let (snapshot_diffs, net_diff) = find_or_insert_chained_diffs(&conn, &snapshot_summaries, &no_rules)?;
+        assert_eq!(2, snapshot_diffs.len());
+        assert_eq!(
+            vec![
+                (&snapshot_summaries[0].snapshot_id, &snapshot_summaries[1].snapshot_id),
+                (&snapshot_summaries[1].snapshot_id, &snapshot_summaries[2].snapshot_id)
+            ],
+            snapshot_diffs.iter().map(|snapshot_diff| (&snapshot_diff.snapshot_id1, &snapshot_diff.snapshot_id2)).collect::<Vec<_>>()
+        );
+        assert_eq!((&snapshot_summaries[0].snapshot_id, &snapshot_summaries[2].snapshot_id), (&net_diff.snapshot_id1, &net_diff.snapshot_id2));
+        assert_eq!(Some(&Deleted(s("apple"))), net_diff.table_diffs[0].row_diffs1.values().next().and_then(|cols| cols.get("name")));
+
+        // 保存済みの差分は計算し直さない
+        let (snapshot_diffs_cached, net_diff_cached) = find_or_insert_chained_diffs(&conn, &snapshot_summaries, &no_rules)?;
+        assert_eq!(snapshot_diffs, snapshot_diffs_cached);
+        assert_eq!(net_diff, net_diff_cached);
+
+        let act = find_or_insert_chained_diffs(&conn, &snapshot_summaries[..1], &no_rules).unwrap_err();
+        assert_eq!("select at least 2 snapshots", act.to_string());
+
         Ok(())
     }
 }
@@ -24,7 +24,10 @@ pub fn find_dump_config(conn: &SqliteConnection, snapshot_id: &SnapshotId) -> an
         .filter(schema::dump_configs::snapshot_id.eq(&snapshot_id))
         .load(conn)
         .map_err(|e| anyhow!(e))?;
-    Ok(rows.into_iter().next().map(|data| DumpConfig::sort(serde_json::from_str(&data).unwrap())).unwrap())
+    match rows.into_iter().next() {
+        Some(data) => Ok(DumpConfig::sort(serde_json::from_str(&data).unwrap())),
+        None => Err(anyhow!("dump config not found: {}", snapshot_id)),
+    }
 }
 
 pub fn insert_dump_configs(
@@ -54,7 +57,7 @@ mod tests {
     use crate::db::dump_config::{find_dump_config, find_recent_dump_configs, insert_dump_configs};
     use crate::db::project::insert_project;
     use crate::db::snapshot::insert_snapshot_summary;
-    use crate::db::{create_sqlite_connection, migrate_sqlite_if_missing};
+    use crate::db::{create_sqlite_connection, migrate_sqlite};
     use crate::domain::dump_config::DumpConfig;
     use crate::domain::project::Project;
     use crate::domain::project::Rdbms::Mysql;
@@ -64,7 +67,7 @@ mod tests {
     fn dump_config() -> anyhow::Result<()> {
         // setup
 
-        migrate_sqlite_if_missing()?;
+        migrate_sqlite()?;
         let conn = create_sqlite_connection()?;
         diesel::sql_query("delete from dump_configs").execute(&conn)?;
 
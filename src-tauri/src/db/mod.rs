@@ -1,5 +1,5 @@
-use std::fs::{create_dir_all, remove_dir_all, OpenOptions};
-use std::io::{stdout, Write};
+use std::fs::{create_dir_all, read, File};
+use std::io::{stderr, Write};
 
 use anyhow::anyhow;
 use diesel::{Connection, SqliteConnection};
@@ -8,40 +8,44 @@ use diesel_migrations::run_pending_migrations_in_directory;
 use crate::logger;
 use crate::workspace::{workspace_dir, workspace_path};
 
+pub mod db_object;
 pub mod diff;
 pub mod dump_config;
 pub mod project;
 pub mod schema;
 pub mod snapshot;
 pub mod snapshot_result;
+pub mod table_definition;
 
-pub fn migrate_sqlite_if_missing() -> anyhow::Result<()> {
+// 既存のデータベースには、まだ適用していないマイグレーションだけが適用される
+pub fn migrate_sqlite() -> anyhow::Result<()> {
     setup_dir()?;
 
     let db = workspace_path("database-v0.2.2.sqlite")?;
 
     if !db.exists() {
         logger::info("create database [ ~/.db-diff/database-v0.2.2.sqlite ]");
+    } else {
+        logger::info("found database [ ~/.db-diff/database-v0.2.2.sqlite ]");
+    }
 
-        let conn = create_sqlite_connection()?;
+    let conn = create_sqlite_connection()?;
 
-        logger::info("put ( or replace ) migration queries [ ~/.db-diff/migrations ]");
+    logger::info("put migration queries [ ~/.db-diff/migrations ]");
 
-        let migrations_dir = workspace_path("migrations")?;
+    let migrations_dir = workspace_path("migrations")?;
 
-        let _ = remove_dir_all(&migrations_dir);
+    for (name, up, down) in get_migrations() {
+        put_migrations(name, "up", up)?;
+        put_migrations(name, "down", down)?;
+    }
 
-        put_migrations("up", get_up_bytes())?;
-        put_migrations("down", get_down_bytes())?;
+    logger::info("migrate database");
 
-        logger::info("migrate database");
+    // CLI の標準出力を汚さないように、適用したマイグレーションは標準エラー出力に出す
+    run_pending_migrations_in_directory(&conn, &migrations_dir, &mut stderr())?;
 
-        run_pending_migrations_in_directory(&conn, &migrations_dir, &mut stdout())?;
-
-        logger::info("migrate ok");
-    } else {
-        logger::info("found database [ ~/.db-diff/database-v0.2.2.sqlite ]");
-    }
+    logger::info("migrate ok");
 
     Ok(())
 }
@@ -65,33 +69,57 @@ fn setup_dir() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn put_migrations(kind: &str, bytes: &[u8]) -> anyhow::Result<()> {
-    let dir = workspace_path("migrations")?.join("tables");
-    create_dir_all(&dir)?;
-
+// 起動のたびに書き換えないように、ないファイルと内容が変わったファイルだけを書き出す
+fn put_migrations(name: &str, kind: &str, bytes: &[u8]) -> anyhow::Result<()> {
+    let dir = workspace_path("migrations")?.join(name);
     let path = dir.join(format!("{kind}.sql"));
-    let mut file = OpenOptions::new().append(true).create(true).open(path)?;
+    if read(&path).is_ok_and(|current| current == bytes) {
+        return Ok(());
+    }
+
+    create_dir_all(&dir)?;
+    let mut file = File::create(path)?;
 
     file.write_all(bytes).map_err(|e| anyhow!(e))?;
     file.flush().map_err(|e| anyhow!(e))
 }
 
-#[cfg(unix)]
-fn get_up_bytes() -> &'static [u8] {
-    include_bytes!("../../migrations/tables/up.sql")
-}
+type Migration = (&'static str, &'static [u8], &'static [u8]);
 
+// include_bytes! のパスの区切り文字だけを OS ごとに切り替える
 #[cfg(unix)]
-fn get_down_bytes() -> &'static [u8] {
-    include_bytes!("../../migrations/tables/down.sql")
+macro_rules! sep {
+    () => {
+        "/"
+    };
 }
 
 #[cfg(windows)]
-fn get_up_bytes() -> &'static [u8] {
-    include_bytes!("..\\..\\migrations\\tables\\up.sql")
+macro_rules! sep {
+    () => {
+        "\\"
+    };
 }
 
-#[cfg(windows)]
-fn get_down_bytes() -> &'static [u8] {
-    include_bytes!("..\\..\\migrations\\tables\\down.sql")
+macro_rules! migration {
+    ($name:literal) => {
+        (
+            $name,
+            include_bytes!(concat!("..", sep!(), "..", sep!(), "migrations", sep!(), $name, sep!(), "up.sql")),
+            include_bytes!(concat!("..", sep!(), "..", sep!(), "migrations", sep!(), $name, sep!(), "down.sql")),
+        )
+    };
+}
+
+fn get_migrations() -> Vec<Migration> {
+    vec![
+        migration!("tables"),
+        migration!("tables-0001_snapshot_diff_ignore_rules"),
+        migration!("tables-0002_project_local"),
+        migration!("tables-0003_table_definitions"),
+        migration!("tables-0004_db_objects"),
+        migration!("tables-0005_snapshot_diff_renames"),
+        migration!("tables-0006_table_definitions_per_snapshot"),
+        migration!("tables-0007_clear_snapshot_diffs"),
+    ]
 }
@@ -3,7 +3,7 @@ use diesel::prelude::*;
 use diesel::{RunQueryDsl, SqliteConnection};
 
 use crate::db::schema;
-use crate::domain::project::Rdbms::Mysql;
+use crate::domain::project::Rdbms::{Mariadb, Mysql, Postgres, Sqlite};
 use crate::domain::project::{Project, ProjectId};
 use schema::projects as projects_table;
 
@@ -19,6 +19,7 @@ struct ProjectRecord {
     host: String,
     port: String,
     schema: String,
+    local: bool,
 }
 
 impl ProjectRecord {
@@ -29,6 +30,9 @@ impl ProjectRecord {
             color: project.color.clone(),
             rdbms: match project.rdbms {
                 Mysql => "MySQL",
+                Mariadb => "MariaDB",
+                Postgres => "PostgreSQL",
+                Sqlite => "SQLite",
             }
             .to_string(),
             user: project.user.clone(),
@@ -36,6 +40,7 @@ impl ProjectRecord {
             host: project.host.clone(),
             port: project.port.clone(),
             schema: project.schema.clone(),
+            local: project.local,
         }
     }
 
@@ -46,6 +51,9 @@ impl ProjectRecord {
             color: self.color,
             rdbms: match self.rdbms.as_ref() {
                 "MySQL" => Mysql,
+                "MariaDB" => Mariadb,
+                "PostgreSQL" => Postgres,
+                "SQLite" => Sqlite,
                 _ => unreachable!(),
             },
             user: self.user,
@@ -53,6 +61,7 @@ impl ProjectRecord {
             host: self.host,
             port: self.port,
             schema: self.schema,
+            local: self.local,
         }
     }
 }
@@ -80,6 +89,7 @@ pub fn update_project(conn: &SqliteConnection, project: &Project) -> anyhow::Res
             schema::projects::host.eq(&record.host),
             schema::projects::port.eq(&record.port),
             schema::projects::schema.eq(&record.schema),
+            schema::projects::local.eq(&record.local),
         ))
         .execute(conn)
         .map_err(|e| anyhow!(e))?;
@@ -96,7 +106,7 @@ mod tests {
     use diesel::RunQueryDsl;
 
     use crate::db::project::{all_projects, delete_project, insert_project, update_project};
-    use crate::db::{create_sqlite_connection, migrate_sqlite_if_missing};
+    use crate::db::{create_sqlite_connection, migrate_sqlite};
     use crate::domain::project::Project;
     use crate::domain::project::Rdbms::Mysql;
     use crate::domain::snapshot::create_snapshot_id;
@@ -105,7 +115,7 @@ mod tests {
     fn project() -> anyhow::Result<()> {
         // setup
 
-        migrate_sqlite_if_missing()?;
+        migrate_sqlite()?;
         let conn = create_sqlite_connection()?;
         diesel::sql_query("delete from projects").execute(&conn)?;
 
@@ -124,7 +134,8 @@ mod tests {
         assert_eq!(&project1, &projects[0]);
 
         // update
-        let project2 = Project::new(&project_id, "test-project-2", "red", Mysql, "user2", "password2", "127.0.0.2", "3307", "test-db2");
+        let project2 =
+            Project::new(&project_id, "test-project-2", "red", Mysql, "user2", "password2", "127.0.0.2", "3307", "test-db2").with_local(true);
         update_project(&conn, &project2)?;
 
         let projects = all_projects(&conn)?;
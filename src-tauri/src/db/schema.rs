@@ -11,6 +11,7 @@ diesel::table! {
         host -> Text,
         port -> Text,
         schema -> Text,
+        local -> Bool,
     }
 }
 
@@ -19,6 +20,8 @@ diesel::table! {
         diff_id -> Text,
         snapshot_id1 -> Text,
         snapshot_id2 -> Text,
+        ignore_rules -> Text,
+        renames -> Text,
         data -> Text,
     }
 }
@@ -50,6 +53,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    table_definitions (snapshot_id) {
+        snapshot_id -> Text,
+        data -> Text,
+    }
+}
+
+diesel::table! {
+    db_objects (snapshot_id) {
+        snapshot_id -> Text,
+        data -> Text,
+    }
+}
+
 diesel::table! {
     dump_configs (project_id) {
         snapshot_id -> Text,
@@ -61,7 +78,9 @@ diesel::table! {
 
 diesel::joinable!(snapshot_summaries -> projects (project_id));
 diesel::joinable!(table_snapshots -> snapshot_summaries (snapshot_id));
+diesel::joinable!(table_definitions -> snapshot_summaries (snapshot_id));
+diesel::joinable!(db_objects -> snapshot_summaries (snapshot_id));
 diesel::joinable!(dump_configs -> snapshot_summaries (snapshot_id));
 diesel::joinable!(dump_configs -> projects (project_id));
 
-diesel::allow_tables_to_appear_in_same_query!(projects, snapshot_diffs, snapshot_summaries, table_snapshots,);
+diesel::allow_tables_to_appear_in_same_query!(projects, snapshot_diffs, snapshot_summaries, table_snapshots, table_definitions, db_objects,);
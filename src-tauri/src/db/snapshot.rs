@@ -84,6 +84,14 @@ pub fn find_table_snapshots(conn: &SqliteConnection, snapshot_id: &SnapshotId) -
     Ok(rows.into_iter().map(|row| row.to()).collect())
 }
 
+pub fn find_table_snapshot(conn: &SqliteConnection, snapshot_id: &SnapshotId, table_name: &TableName) -> anyhow::Result<Option<TableSnapshot>> {
+    let rows: Vec<TableSnapshotRecord> = schema::table_snapshots::table
+        .filter(schema::table_snapshots::snapshot_id.eq(snapshot_id).and(schema::table_snapshots::table_name.eq(table_name)))
+        .load(conn)
+        .map_err(|e| anyhow!(e))?;
+    Ok(rows.into_iter().next().map(|row| row.to()))
+}
+
 pub fn insert_table_snapshots(conn: &SqliteConnection, snapshot_id: &SnapshotId, table_snapshots: Vec<TableSnapshot>) -> anyhow::Result<()> {
     let records = table_snapshots.iter().map(|table_snapshot| TableSnapshotRecord::from(table_snapshot, snapshot_id)).collect_vec();
     diesel::insert_into(schema::table_snapshots::table).values(records).execute(conn).map_err(|e| anyhow!(e))?;
@@ -96,10 +104,10 @@ mod tests {
 
     use crate::db::project::insert_project;
     use crate::db::snapshot::{
-        all_snapshot_summaries, delete_snapshot_summary, find_table_snapshots, insert_snapshot_summary, insert_table_snapshots,
+        all_snapshot_summaries, delete_snapshot_summary, find_table_snapshot, find_table_snapshots, insert_snapshot_summary, insert_table_snapshots,
         update_snapshot_summary,
     };
-    use crate::db::{create_sqlite_connection, migrate_sqlite_if_missing};
+    use crate::db::{create_sqlite_connection, migrate_sqlite};
     use crate::domain::project::Rdbms::Mysql;
     use crate::domain::project::{create_project_id, Project};
     use crate::domain::snapshot::ColValue::{SimpleNumber, SimpleString};
@@ -109,7 +117,7 @@ mod tests {
     fn snapshot_summary() -> anyhow::Result<()> {
         // setup
 
-        migrate_sqlite_if_missing()?;
+        migrate_sqlite()?;
         let conn = create_sqlite_connection()?;
         diesel::sql_query("delete from projects").execute(&conn)?;
 
@@ -161,7 +169,7 @@ mod tests {
     fn table_snapshot() -> anyhow::Result<()> {
         // setup
 
-        migrate_sqlite_if_missing()?;
+        migrate_sqlite()?;
         let conn = create_sqlite_connection()?;
         diesel::sql_query("delete from projects").execute(&conn)?;
 
@@ -196,6 +204,9 @@ mod tests {
         );
         insert_table_snapshots(&conn, &snapshot_id, vec![act])?;
 
+        assert_eq!(Some(&exp), find_table_snapshot(&conn, &snapshot_id, &table_name)?.as_ref());
+        assert_eq!(None, find_table_snapshot(&conn, &snapshot_id, &"users".to_string())?);
+
         let table_snapshots = find_table_snapshots(&conn, &snapshot_id)?;
         assert_eq!(vec![exp], table_snapshots);
 
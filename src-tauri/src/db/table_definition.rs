@@ -0,0 +1,100 @@
+use anyhow::anyhow;
+use diesel::prelude::*;
+use diesel::{RunQueryDsl, SqliteConnection};
+use itertools::Itertools;
+
+use schema::table_definitions as table_definitions_table;
+
+use crate::db::schema;
+use crate::domain::snapshot::SnapshotId;
+use crate::domain::table_definition::TableDefinition;
+
+// テーブルがないデータベースと区別できるように、スナップショットごとにテーブル名の順で 1 行に記録する
+#[derive(Queryable, Insertable)]
+#[table_name = "table_definitions_table"]
+struct TableDefinitionsRecord {
+    snapshot_id: SnapshotId,
+    data: String,
+}
+
+impl TableDefinitionsRecord {
+    fn from(table_definitions: &[TableDefinition], fk: &SnapshotId) -> Self {
+        let table_definitions = table_definitions.iter().sorted_by_key(|table_definition| &table_definition.table_name).collect_vec();
+        Self { snapshot_id: fk.clone(), data: serde_json::to_string(&table_definitions).unwrap() }
+    }
+
+    fn to(self) -> Vec<TableDefinition> {
+        serde_json::from_str(&self.data).unwrap()
+    }
+}
+
+// テーブルの定義を記録する前に作成したスナップショットでは None になる
+pub fn find_table_definitions(conn: &SqliteConnection, snapshot_id: &SnapshotId) -> anyhow::Result<Option<Vec<TableDefinition>>> {
+    let row: Option<TableDefinitionsRecord> = schema::table_definitions::table
+        .filter(schema::table_definitions::snapshot_id.eq(snapshot_id))
+        .first(conn)
+        .optional()
+        .map_err(|e| anyhow!(e))?;
+    Ok(row.map(|row| row.to()))
+}
+
+pub fn insert_table_definitions(conn: &SqliteConnection, snapshot_id: &SnapshotId, table_definitions: &[TableDefinition]) -> anyhow::Result<()> {
+    diesel::insert_into(schema::table_definitions::table)
+        .values(TableDefinitionsRecord::from(table_definitions, snapshot_id))
+        .execute(conn)
+        .map_err(|e| anyhow!(e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::project::insert_project;
+    use crate::db::snapshot::{delete_snapshot_summary, insert_snapshot_summary};
+    use crate::db::table_definition::{find_table_definitions, insert_table_definitions};
+    use crate::db::{create_sqlite_connection, migrate_sqlite};
+    use crate::domain::project::Rdbms::Mysql;
+    use crate::domain::project::{create_project_id, Project};
+    use crate::domain::snapshot::{create_snapshot_id, SnapshotSummary};
+    use crate::domain::table_definition::{ColDefinition, IndexDefinition, TableDefinition};
+
+    #[test]
+    fn table_definition() -> anyhow::Result<()> {
+        // setup
+
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+
+        let project_id = create_project_id();
+
+        let project = Project::new(&project_id, "test-project", "red", Mysql, "user", "password", "127.0.0.1", "3306", "test-db");
+        insert_project(&conn, &project)?;
+
+        let snapshot_id = create_snapshot_id();
+        insert_snapshot_summary(&conn, &project_id, &SnapshotSummary::new(&snapshot_id, "test", "2023-07-03 08:17:52"))?;
+
+        let snapshot_id2 = create_snapshot_id();
+        insert_snapshot_summary(&conn, &project_id, &SnapshotSummary::new(&snapshot_id2, "test2", "2023-07-03 08:17:53"))?;
+
+        // find
+        assert_eq!(None, find_table_definitions(&conn, &snapshot_id)?);
+
+        // insert
+        let mk = |table_name: &str| TableDefinition {
+            table_name: table_name.to_string(),
+            cols: vec![ColDefinition::new("id", "int", false), ColDefinition::new("name", "varchar(255)", true).with_default(Some("''".to_string()))],
+            indexes: vec![IndexDefinition { index_name: "PRIMARY".to_string(), col_names: vec!["id".to_string()], primary: true, unique: true }],
+            foreign_keys: vec![],
+        };
+        insert_table_definitions(&conn, &snapshot_id, &[mk("users"), mk("items")])?;
+        insert_table_definitions(&conn, &snapshot_id2, &[])?;
+
+        assert_eq!(Some(vec![mk("items"), mk("users")]), find_table_definitions(&conn, &snapshot_id)?);
+        assert_eq!(Some(vec![]), find_table_definitions(&conn, &snapshot_id2)?);
+
+        // スナップショットと一緒に削除される
+        delete_snapshot_summary(&conn, &snapshot_id)?;
+        assert_eq!(None, find_table_definitions(&conn, &snapshot_id)?);
+
+        Ok(())
+    }
+}
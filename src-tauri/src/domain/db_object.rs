@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::line_diff::diff_lines;
+use crate::domain::table_definition::SchemaStatus;
+
+// テーブル以外で、アプリケーションのロジックを持つことがあるデータベースのオブジェクト
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, PartialOrd, Ord, Debug, Clone)]
+pub enum DbObjectType {
+    View,
+    Trigger,
+    Procedure,
+    Function,
+    Event,
+}
+
+impl DbObjectType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DbObjectType::View => "view",
+            DbObjectType::Trigger => "trigger",
+            DbObjectType::Procedure => "procedure",
+            DbObjectType::Function => "function",
+            DbObjectType::Event => "event",
+        }
+    }
+}
+
+// 定義は RDBMS が返すテキストのまま持ち、行単位で比較する
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct DbObject {
+    pub object_type: DbObjectType,
+    pub object_name: String,
+    pub definition: String,
+}
+
+impl DbObject {
+    pub fn new(object_type: DbObjectType, object_name: &str, definition: &str) -> Self {
+        Self { object_type, object_name: object_name.to_string(), definition: definition.to_string() }
+    }
+
+    // 改行コードと行末の空白の違いは、変更とみなさない
+    fn lines(&self) -> Vec<&str> {
+        self.definition.trim_end().lines().map(|line| line.trim_end()).collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum LineStatus {
+    Stay,
+    Added,
+    Deleted,
+}
+
+impl LineStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            LineStatus::Stay => "stay",
+            LineStatus::Added => "added",
+            LineStatus::Deleted => "deleted",
+        }
+    }
+
+    pub fn as_sign(&self) -> &str {
+        match self {
+            LineStatus::Stay => " ",
+            LineStatus::Added => "+",
+            LineStatus::Deleted => "-",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct LineDiff {
+    pub status: LineStatus,
+    pub line: String,
+}
+
+// 変更のなかった行も含めて、定義の全体を行の差分として持つ
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct DbObjectDiff {
+    pub object_type: DbObjectType,
+    pub object_name: String,
+    pub status: SchemaStatus,
+    pub line_diffs: Vec<LineDiff>,
+}
+
+// 種類と名前で対応させ、定義が異なるものだけを種類 ( ビュー、トリガー、ルーチン、イベントの順 ) 、名前の順に並べる
+pub fn create_db_object_diffs(db_objects1: Option<&[DbObject]>, db_objects2: Option<&[DbObject]>) -> Vec<DbObjectDiff> {
+    // オブジェクトを記録する前に作成したスナップショットとは比較しない ( すべてが追加されたように見えてしまう )
+    let (db_objects1, db_objects2) = match (db_objects1, db_objects2) {
+        (Some(db_objects1), Some(db_objects2)) => (db_objects1, db_objects2),
+        _ => return vec![],
+    };
+
+    let map1: HashMap<(&DbObjectType, &String), &DbObject> =
+        db_objects1.iter().map(|db_object| ((&db_object.object_type, &db_object.object_name), db_object)).collect();
+    let map2: HashMap<(&DbObjectType, &String), &DbObject> =
+        db_objects2.iter().map(|db_object| ((&db_object.object_type, &db_object.object_name), db_object)).collect();
+
+    let keys = map1.keys().chain(map2.keys()).unique().sorted().collect_vec();
+
+    let mut db_object_diffs = vec![];
+    for key in keys {
+        let lines1 = map1.get(key).map(|db_object| db_object.lines());
+        let lines2 = map2.get(key).map(|db_object| db_object.lines());
+        if lines1 == lines2 {
+            continue;
+        }
+
+        let status = match (&lines1, &lines2) {
+            (None, Some(_)) => SchemaStatus::Added,
+            (Some(_), None) => SchemaStatus::Dropped,
+            _ => SchemaStatus::Altered,
+        };
+        let line_diffs = diff_lines(&lines1.unwrap_or_default(), &lines2.unwrap_or_default())
+            .into_iter()
+            .map(|(sign, line)| {
+                let status = match sign {
+                    '+' => LineStatus::Added,
+                    '-' => LineStatus::Deleted,
+                    _ => LineStatus::Stay,
+                };
+                LineDiff { status, line: line.to_string() }
+            })
+            .collect();
+
+        db_object_diffs.push(DbObjectDiff { object_type: key.0.clone(), object_name: key.1.clone(), status, line_diffs });
+    }
+    db_object_diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::db_object::DbObjectType::{Procedure, Trigger, View};
+    use crate::domain::db_object::LineStatus::{Added, Deleted, Stay};
+    use crate::domain::db_object::{create_db_object_diffs, DbObject};
+    use crate::domain::table_definition::SchemaStatus;
+
+    #[test]
+    fn db_object_diffs() {
+        let db_objects1 = vec![
+            DbObject::new(View, "active_users", "select * from users where active = 1"),
+            DbObject::new(Trigger, "users_bi", "begin\r\n  set new.created_at = now();\r\nend"),
+            DbObject::new(Procedure, "cleanup", "begin\n  delete from logs;\nend\n"),
+        ];
+        let db_objects2 = vec![
+            DbObject::new(View, "active_users", "select * from users where active = 1"),
+            DbObject::new(Trigger, "users_bi", "begin  \n  set new.created_at = now();\n  set new.updated_at = now();\nend"),
+            DbObject::new(Trigger, "users_bu", "begin\n  set new.updated_at = now();\nend"),
+        ];
+
+        let act = create_db_object_diffs(Some(&db_objects1), Some(&db_objects2));
+
+        assert_eq!(
+            vec![("users_bi", SchemaStatus::Altered), ("users_bu", SchemaStatus::Added), ("cleanup", SchemaStatus::Dropped)],
+            act.iter().map(|diff| (diff.object_name.as_str(), diff.status.clone())).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(Stay, "begin"), (Stay, "  set new.created_at = now();"), (Added, "  set new.updated_at = now();"), (Stay, "end"),],
+            act[0].line_diffs.iter().map(|line_diff| (line_diff.status.clone(), line_diff.line.as_str())).collect::<Vec<_>>()
+        );
+        assert!(act[2].line_diffs.iter().all(|line_diff| line_diff.status == Deleted));
+
+        assert!(create_db_object_diffs(None, Some(&db_objects2)).is_empty());
+        assert_eq!(3, create_db_object_diffs(Some(&[]), Some(&db_objects2)).len());
+    }
+}
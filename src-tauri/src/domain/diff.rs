@@ -1,14 +1,24 @@
 use std::collections::HashMap;
 
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::domain::db_object::DbObjectDiff;
 use crate::domain::diff::ColDiff::*;
+use crate::domain::ignore_rule::IgnoreRules;
+use crate::domain::inline_diff::{diff_inline, InlineSegment};
+use crate::domain::json_diff::{diff_json, JsonPathDiff};
+use crate::domain::rename::RenameRules;
 use crate::domain::schema::{ColName, Hash, PrimaryColName, PrimaryValue, TableName};
-use crate::domain::snapshot::{ColValue, PrimaryColValues, SnapshotId, TableSnapshot};
+use crate::domain::snapshot::{get_primary_col_names, ColValue, PrimaryColValues, SnapshotId, TableSnapshot};
+use crate::domain::table_definition::SchemaChange;
 
 pub type DiffId = String;
 
+// 主キーのないテーブルの差分では、連番を主キーの代わりに表示する
+const KEYLESS_PRIMARY_COL_NAME: &str = "#";
+
 pub fn create_diff_id() -> DiffId {
     Uuid::new_v4().to_string()
 }
@@ -19,11 +29,74 @@ pub struct SnapshotDiff {
     pub snapshot_id1: SnapshotId,
     pub snapshot_id2: SnapshotId,
     pub table_diffs: Vec<TableDiff>,
+    #[serde(default)]
+    pub ignore_rules: Vec<String>,
+    #[serde(default)]
+    pub schema_changes: Vec<SchemaChange>,
+    #[serde(default)]
+    pub db_object_diffs: Vec<DbObjectDiff>,
+    #[serde(default)]
+    pub renames: Vec<String>,
+    #[serde(default)]
+    pub suggested_renames: Vec<String>,
 }
 
 impl SnapshotDiff {
     pub fn new(diff_id: &DiffId, snapshot_id1: &SnapshotId, snapshot_id2: &SnapshotId, table_diffs: Vec<TableDiff>) -> Self {
-        Self { diff_id: diff_id.clone(), snapshot_id1: snapshot_id1.clone(), snapshot_id2: snapshot_id2.clone(), table_diffs }
+        Self {
+            diff_id: diff_id.clone(),
+            snapshot_id1: snapshot_id1.clone(),
+            snapshot_id2: snapshot_id2.clone(),
+            table_diffs,
+            ignore_rules: vec![],
+            schema_changes: vec![],
+            db_object_diffs: vec![],
+            renames: vec![],
+            suggested_renames: vec![],
+        }
+    }
+
+    // 差分計算時に比較から外したルール
+    pub fn with_ignore_rules(self, ignore_rules: &IgnoreRules) -> Self {
+        Self { ignore_rules: ignore_rules.as_strings(), ..self }
+    }
+
+    // 行の差分と並べて表示するスキーマの変更 ( テーブルの定義を記録していないスナップショットとの比較では空になる )
+    pub fn with_schema_changes(self, schema_changes: Vec<SchemaChange>) -> Self {
+        Self { schema_changes, ..self }
+    }
+
+    // ビュー、トリガー、ルーチン、イベントの定義の差分 ( オブジェクトを記録していないスナップショットとの比較では空になる )
+    pub fn with_db_object_diffs(self, db_object_diffs: Vec<DbObjectDiff>) -> Self {
+        Self { db_object_diffs, ..self }
+    }
+
+    // 名前を変えたとみなして比較したテーブルとカラムを、テーブルの差分にも記録する
+    pub fn with_renames(self, renames: &RenameRules) -> Self {
+        let table_diffs = self
+            .table_diffs
+            .into_iter()
+            .map(|table_diff| TableDiff {
+                renamed_from: renames.old_table_name(&table_diff.table_name),
+                renamed_col_names: renames.old_col_names(&table_diff.table_name),
+                ..table_diff
+            })
+            .collect();
+        Self { table_diffs, renames: renames.as_strings(), ..self }
+    }
+
+    // 検出した名前の変更は比較には使わず、ユーザーが確認して指定するための候補として返す
+    pub fn with_suggested_renames(self, suggested_renames: &RenameRules) -> Self {
+        Self { suggested_renames: suggested_renames.as_strings(), ..self }
+    }
+
+    // 長い文字列の変更を単語単位で比較する ( 保存する差分には含めず、表示するときに指定された場合だけ計算する )
+    pub fn with_inline_diffs(self, min_chars: usize) -> Self {
+        Self { table_diffs: self.table_diffs.into_iter().map(|table_diff| table_diff.with_inline_diffs(min_chars)).collect(), ..self }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table_diffs.is_empty() && self.schema_changes.is_empty() && self.db_object_diffs.is_empty()
     }
 }
 
@@ -35,6 +108,28 @@ pub struct TableDiff {
     pub col_names: Vec<ColName>,
     pub row_diffs1: HashMap<PrimaryValue, HashMap<ColName, ColDiff>>,
     pub row_diffs2: HashMap<PrimaryValue, HashMap<ColName, ColDiff>>,
+    #[serde(default)]
+    pub truncated: bool,
+    #[serde(default)]
+    pub excluded_col_names1: Vec<ColName>,
+    #[serde(default)]
+    pub excluded_col_names2: Vec<ColName>,
+    #[serde(default)]
+    pub ignored_col_names: Vec<ColName>,
+    #[serde(default)]
+    pub keyless: bool,
+    #[serde(default)]
+    pub row_counts: HashMap<PrimaryValue, (usize, usize)>,
+    #[serde(default)]
+    pub renamed_from: Option<TableName>,
+    #[serde(default)]
+    pub renamed_col_names: HashMap<ColName, ColName>,
+    #[serde(default)]
+    pub json_diffs: HashMap<PrimaryValue, HashMap<ColName, Vec<JsonPathDiff>>>,
+    #[serde(default)]
+    pub inline_diffs: HashMap<PrimaryValue, HashMap<ColName, Vec<InlineSegment>>>,
+    #[serde(default)]
+    pub primary_col_names: Vec<ColName>,
 }
 
 impl TableDiff {
@@ -46,7 +141,45 @@ impl TableDiff {
             col_names: col_names.into_iter().cloned().collect(),
             row_diffs1: HashMap::new(),
             row_diffs2: HashMap::new(),
+            truncated: false,
+            excluded_col_names1: vec![],
+            excluded_col_names2: vec![],
+            ignored_col_names: vec![],
+            keyless: false,
+            row_counts: HashMap::new(),
+            renamed_from: None,
+            renamed_col_names: HashMap::new(),
+            json_diffs: HashMap::new(),
+            inline_diffs: HashMap::new(),
+            primary_col_names: vec![],
+        }
+    }
+
+    pub fn get_primary_col_names(&self) -> Vec<ColName> {
+        get_primary_col_names(&self.primary_col_name, &self.primary_col_names)
+    }
+
+    fn with_inline_diffs(self, min_chars: usize) -> Self {
+        let mut inline_diffs = HashMap::new();
+        for (primary_value, cols1) in &self.row_diffs1 {
+            let cols2 = match self.row_diffs2.get(primary_value) {
+                Some(cols2) => cols2,
+                None => continue,
+            };
+            let cols: HashMap<ColName, Vec<InlineSegment>> = cols1
+                .iter()
+                .filter_map(|(col_name, col_diff1)| match (col_diff1, cols2.get(col_name)) {
+                    (Deleted(ColValue::SimpleString(value1)), Some(Added(ColValue::SimpleString(value2)))) => {
+                        diff_inline(value1, value2, min_chars).map(|segments| (col_name.clone(), segments))
+                    }
+                    _ => None,
+                })
+                .collect();
+            if !cols.is_empty() {
+                inline_diffs.insert(primary_value.clone(), cols);
+            }
         }
+        Self { inline_diffs, ..self }
     }
 
     pub fn empty(&self) -> bool {
@@ -62,22 +195,89 @@ pub enum ColDiff {
     Deleted(ColValue),
 }
 
-pub fn create_table_diff(table_snapshot1: Option<&TableSnapshot>, table_snapshot2: Option<&TableSnapshot>) -> TableDiff {
-    match (table_snapshot1, table_snapshot2) {
-        (Some(table_snapshot1), Some(table_snapshot2)) => take_table_snapshot_diff(table_snapshot1, table_snapshot2),
-        (None, Some(table_snapshot2)) => create_empty(table_snapshot2),
-        (Some(table_snapshot1), None) => create_empty(table_snapshot1),
+// 同じ名前のテーブルのスナップショット同士を比較し、差分のあるテーブルだけを残す
+pub fn create_snapshot_diff(
+    snapshot_id1: &SnapshotId,
+    table_snapshots1: &[TableSnapshot],
+    snapshot_id2: &SnapshotId,
+    table_snapshots2: &[TableSnapshot],
+    ignore_rules: &IgnoreRules,
+) -> SnapshotDiff {
+    let table_snapshots1: HashMap<&TableName, &TableSnapshot> = table_snapshots1
+        .iter()
+        .into_group_map_by(|table_snapshot| &table_snapshot.table_name)
+        .iter()
+        .map(|(&table_name, table_snapshots)| (table_name, table_snapshots[0]))
+        .collect();
+
+    let table_snapshots2: HashMap<&TableName, &TableSnapshot> = table_snapshots2
+        .iter()
+        .into_group_map_by(|table_snapshot| &table_snapshot.table_name)
+        .iter()
+        .map(|(&table_name, table_snapshots)| (table_name, table_snapshots[0]))
+        .collect();
+
+    let mut table_names1 = table_snapshots1.keys().cloned().collect_vec();
+    let mut table_names2 = table_snapshots2.keys().cloned().collect_vec();
+    table_names1.append(&mut table_names2);
+
+    SnapshotDiff::new(
+        &create_diff_id(),
+        snapshot_id1,
+        snapshot_id2,
+        table_names1
+            .into_iter()
+            .unique()
+            .map(|table_name| create_table_diff(table_snapshots1.get(table_name).copied(), table_snapshots2.get(table_name).copied(), ignore_rules))
+            .filter(|table_diff| !table_diff.empty())
+            .collect(),
+    )
+    .with_ignore_rules(ignore_rules)
+}
+
+pub fn create_table_diff(table_snapshot1: Option<&TableSnapshot>, table_snapshot2: Option<&TableSnapshot>, ignore_rules: &IgnoreRules) -> TableDiff {
+    let mut table_diff = match (table_snapshot1, table_snapshot2) {
+        (Some(table_snapshot1), Some(table_snapshot2)) if table_snapshot1.is_keyless() || table_snapshot2.is_keyless() => {
+            take_multiset_diff(table_snapshot1, table_snapshot2, ignore_rules)
+        }
+        (Some(table_snapshot1), Some(table_snapshot2)) => take_table_snapshot_diff(table_snapshot1, table_snapshot2, ignore_rules),
+        (None, Some(table_snapshot2)) => create_empty(table_snapshot2, ignore_rules),
+        (Some(table_snapshot1), None) => create_empty(table_snapshot1, ignore_rules),
         (None, None) => unreachable!(),
+    };
+
+    // どちらかの dump が行数上限で打ち切られていれば、差分が欠けている可能性がある
+    table_diff.truncated = [table_snapshot1, table_snapshot2].iter().flatten().any(|table_snapshot| table_snapshot.is_truncated());
+    table_diff.excluded_col_names1 = table_snapshot1.map(|table_snapshot| table_snapshot.excluded_col_names.clone()).unwrap_or_default();
+    table_diff.excluded_col_names2 = table_snapshot2.map(|table_snapshot| table_snapshot.excluded_col_names.clone()).unwrap_or_default();
+    if !table_diff.keyless {
+        table_diff.primary_col_names = table_snapshot1.or(table_snapshot2).map(TableSnapshot::get_primary_col_names).unwrap_or_default();
     }
+
+    table_diff
 }
 
 type Rows<'a> = HashMap<&'a PrimaryColValues, (&'a Hash, Cols<'a>)>;
 type Cols<'a> = HashMap<&'a ColName, &'a ColValue>;
 
-fn take_table_snapshot_diff(table_snapshot1: &TableSnapshot, table_snapshot2: &TableSnapshot) -> TableDiff {
-    let total_col_names = table_snapshot1.merge_col_names(table_snapshot2);
+fn take_table_snapshot_diff(table_snapshot1: &TableSnapshot, table_snapshot2: &TableSnapshot, ignore_rules: &IgnoreRules) -> TableDiff {
+    let merged_col_names = table_snapshot1.merge_col_names(table_snapshot2);
+
+    let ignored_col_names = ignore_rules.get_ignored_col_names(&table_snapshot1.table_name, &merged_col_names);
+    let total_col_names = merged_col_names.into_iter().filter(|col_name| !ignored_col_names.contains(col_name)).collect_vec();
 
     let mut table_diff = TableDiff::init(&table_snapshot1.table_name, &table_snapshot1.primary_col_name, total_col_names.clone());
+    table_diff.ignored_col_names = ignored_col_names.iter().map(|&col_name| col_name.clone()).collect();
+
+    // 比較しないカラムがある場合は Hash は使えないので、残りのカラムの値で比較する
+    // JSON を正規化する前に作成したスナップショットとは Hash が一致しないので、Hash が異なる場合も値で比較し直す
+    let is_same_row = |(hash1, cols1): &(&Hash, Cols), (hash2, cols2): &(&Hash, Cols)| {
+        (ignored_col_names.is_empty() && hash1 == hash2)
+            || total_col_names.iter().all(|&col_name| match (cols1.get(col_name), cols2.get(col_name)) {
+                (Some(col_value1), Some(col_value2)) => col_value1.is_same_value(col_value2),
+                (col_value1, col_value2) => col_value1 == col_value2,
+            })
+    };
 
     let rows1 = parse_rows(table_snapshot1);
     let rows2 = parse_rows(table_snapshot2);
@@ -87,14 +287,18 @@ fn take_table_snapshot_diff(table_snapshot1: &TableSnapshot, table_snapshot2: &T
         let row2 = rows2.get(primary_col_values);
 
         match (row1, row2) {
-            // 2 つの行の Hash が一致している場合は、スキップする
-            (Some((hash1, _)), Some((hash2, _))) if hash1 == hash2 => {}
+            // 2 つの行が一致している場合は、スキップする
+            (Some(row1), Some(row2)) if is_same_row(row1, row2) => {}
 
             // 同一の主キー値が片方にしかない場合は、片方の全列を差分として登録する
             (None, Some((_, cols2))) => {
                 table_diff.row_diffs2.insert(
                     primary_col_values.as_primary_value(),
-                    cols2.iter().map(|(&col_name, &col_value)| (col_name.clone(), Added(col_value.clone()))).collect(),
+                    cols2
+                        .iter()
+                        .filter(|(col_name, _)| !ignored_col_names.contains(col_name))
+                        .map(|(&col_name, &col_value)| (col_name.clone(), Added(col_value.clone())))
+                        .collect(),
                 );
                 table_diff.primary_col_values.push(primary_col_values.clone());
             }
@@ -103,7 +307,11 @@ fn take_table_snapshot_diff(table_snapshot1: &TableSnapshot, table_snapshot2: &T
             (Some((_, cols1)), None) => {
                 table_diff.row_diffs1.insert(
                     primary_col_values.as_primary_value(),
-                    cols1.iter().map(|(&col_name, &col_value)| (col_name.clone(), Deleted(col_value.clone()))).collect(),
+                    cols1
+                        .iter()
+                        .filter(|(col_name, _)| !ignored_col_names.contains(col_name))
+                        .map(|(&col_name, &col_value)| (col_name.clone(), Deleted(col_value.clone())))
+                        .collect(),
                 );
                 table_diff.primary_col_values.push(primary_col_values.clone());
             }
@@ -111,12 +319,12 @@ fn take_table_snapshot_diff(table_snapshot1: &TableSnapshot, table_snapshot2: &T
             // 2 つの行の Hash が一致しない場合は、列ごとに差分をとる
             (Some((_, cols1)), Some((_, cols2))) => {
                 let get_col_diff_f1 = |col_name| match (cols1.get(col_name), cols2.get(col_name)) {
-                    (Some(&col_value1), Some(&col_value2)) if col_value1 == col_value2 => Stay(col_value1.clone()),
+                    (Some(&col_value1), Some(&col_value2)) if col_value1.is_same_value(col_value2) => Stay(col_value1.clone()),
                     (Some(&col_value1), _) => Deleted(col_value1.clone()),
                     (None, _) => NoValue,
                 };
                 let get_col_diff_f2 = |col_name| match (cols1.get(col_name), cols2.get(col_name)) {
-                    (Some(&col_value1), Some(&col_value2)) if col_value1 == col_value2 => Stay(col_value2.clone()),
+                    (Some(&col_value1), Some(&col_value2)) if col_value1.is_same_value(col_value2) => Stay(col_value2.clone()),
                     (_, Some(&col_value2)) => Added(col_value2.clone()),
                     (_, None) => NoValue,
                 };
@@ -128,6 +336,21 @@ fn take_table_snapshot_diff(table_snapshot1: &TableSnapshot, table_snapshot2: &T
                     primary_col_values.as_primary_value(),
                     total_col_names.iter().map(|&col_name| (col_name.clone(), get_col_diff_f2(col_name))).collect(),
                 );
+
+                // 大きな JSON の一部だけが変わった場合に見つけやすいように、変わったパスも記録する
+                let json_diffs: HashMap<ColName, Vec<JsonPathDiff>> = total_col_names
+                    .iter()
+                    .filter_map(|&col_name| match (cols1.get(col_name), cols2.get(col_name)) {
+                        (Some(ColValue::JsonString(json1)), Some(ColValue::JsonString(json2))) => diff_json(json1, json2)
+                            .filter(|json_path_diffs| !json_path_diffs.is_empty())
+                            .map(|json_path_diffs| (col_name.clone(), json_path_diffs)),
+                        _ => None,
+                    })
+                    .collect();
+                if !json_diffs.is_empty() {
+                    table_diff.json_diffs.insert(primary_col_values.as_primary_value(), json_diffs);
+                }
+
                 table_diff.primary_col_values.push(primary_col_values.clone());
             }
 
@@ -138,6 +361,62 @@ fn take_table_snapshot_diff(table_snapshot1: &TableSnapshot, table_snapshot2: &T
     table_diff
 }
 
+// 主キーのない行は値の組み合わせごとの出現回数で比較し、増減した分を追加・削除として登録する
+fn take_multiset_diff(table_snapshot1: &TableSnapshot, table_snapshot2: &TableSnapshot, ignore_rules: &IgnoreRules) -> TableDiff {
+    let merged_col_names = table_snapshot1.merge_col_names(table_snapshot2);
+    let ignored_col_names = ignore_rules.get_ignored_col_names(&table_snapshot1.table_name, &merged_col_names);
+    let total_col_names = merged_col_names.into_iter().filter(|col_name| !ignored_col_names.contains(col_name)).collect_vec();
+
+    let mut table_diff = TableDiff::init(&table_snapshot1.table_name, &KEYLESS_PRIMARY_COL_NAME.to_string(), total_col_names.clone());
+    table_diff.ignored_col_names = ignored_col_names.iter().map(|&col_name| col_name.clone()).collect();
+    table_diff.keyless = true;
+
+    // 比較するカラムの値の組み合わせごとに、最初に出現した行と 2 つのスナップショットでの出現回数を数える
+    let mut bags: Vec<(Cols, usize, usize)> = vec![];
+    let mut indices: HashMap<Vec<Option<ColValue>>, usize> = HashMap::new();
+
+    for (n, table_snapshot) in [(1, table_snapshot1), (2, table_snapshot2)] {
+        for cols in parse_cols(table_snapshot) {
+            // Hash と同じく、JSON は正規化した値で数える
+            let key = total_col_names.iter().map(|&col_name| cols.get(col_name).map(|col_value| col_value.as_canonical())).collect_vec();
+            let i = *indices.entry(key).or_insert_with(|| {
+                bags.push((cols, 0, 0));
+                bags.len() - 1
+            });
+            if n == 1 {
+                bags[i].1 += 1;
+            } else {
+                bags[i].2 += 1;
+            }
+        }
+    }
+
+    for (cols, count1, count2) in bags.into_iter().filter(|(_, count1, count2)| count1 != count2) {
+        let primary_col_values = PrimaryColValues::new(vec![ColValue::SimpleNumber((table_diff.primary_col_values.len() + 1).to_string())]);
+        let row_diff = |col_diff: fn(ColValue) -> ColDiff| {
+            total_col_names
+                .iter()
+                .map(|&col_name| (col_name.clone(), cols.get(col_name).map_or(NoValue, |&col_value| col_diff(col_value.clone()))))
+                .collect()
+        };
+
+        if count1 > count2 {
+            table_diff.row_diffs1.insert(primary_col_values.as_primary_value(), row_diff(Deleted));
+        } else {
+            table_diff.row_diffs2.insert(primary_col_values.as_primary_value(), row_diff(Added));
+        }
+        table_diff.row_counts.insert(primary_col_values.as_primary_value(), (count1, count2));
+        table_diff.primary_col_values.push(primary_col_values);
+    }
+
+    table_diff
+}
+
+// 主キーのない行は主キーの値で区別できないので、dump した順に返す
+fn parse_cols<'a>(table_snapshot: &'a TableSnapshot) -> Vec<Cols<'a>> {
+    table_snapshot.row_snapshots.iter().map(|row_snapshot| table_snapshot.col_names.iter().zip(&row_snapshot.col_values).collect()).collect()
+}
+
 fn parse_rows<'a>(table_snapshot: &'a TableSnapshot) -> Rows<'a> {
     let mut rows: Rows = HashMap::new();
 
@@ -149,8 +428,17 @@ fn parse_rows<'a>(table_snapshot: &'a TableSnapshot) -> Rows<'a> {
     rows
 }
 
-fn create_empty(table_snapshot: &TableSnapshot) -> TableDiff {
-    TableDiff::init(&table_snapshot.table_name, &table_snapshot.primary_col_name, table_snapshot.col_names.iter().collect())
+fn create_empty(table_snapshot: &TableSnapshot, ignore_rules: &IgnoreRules) -> TableDiff {
+    let col_names = table_snapshot.col_names.iter().collect_vec();
+    let ignored_col_names = ignore_rules.get_ignored_col_names(&table_snapshot.table_name, &col_names);
+
+    let mut table_diff = TableDiff::init(
+        &table_snapshot.table_name,
+        &table_snapshot.primary_col_name,
+        col_names.iter().filter(|col_name| !ignored_col_names.contains(col_name)).copied().collect(),
+    );
+    table_diff.ignored_col_names = ignored_col_names.into_iter().cloned().collect();
+    table_diff
 }
 
 #[cfg(test)]
@@ -161,8 +449,10 @@ mod tests_create_snapshot_diff {
 
     use crate::domain::diff::ColDiff::*;
     use crate::domain::diff::{create_table_diff, ColDiff};
+    use crate::domain::ignore_rule::IgnoreRules;
+    use crate::domain::json_diff::JsonPathStatus;
     use crate::domain::schema::{ColName, PrimaryValue};
-    use crate::domain::snapshot::ColValue::{SimpleNumber, SimpleString};
+    use crate::domain::snapshot::ColValue::{JsonString, SimpleNumber, SimpleString};
     use crate::domain::snapshot::{ColValue, PrimaryColValues, RowSnapshot, TableSnapshot};
 
     fn n(s: &str) -> ColValue {
@@ -195,7 +485,7 @@ mod tests_create_snapshot_diff {
         let rows2 = vec![RowSnapshot::new(vec![n("1")], vec![s("John")])];
         let table_snapshot2 = mk_table_snapshot("user", "id", vec!["name"], rows2);
 
-        let act = create_table_diff(None, Some(&table_snapshot2));
+        let act = create_table_diff(None, Some(&table_snapshot2), &IgnoreRules::default());
 
         assert!(act.empty());
     }
@@ -205,7 +495,7 @@ mod tests_create_snapshot_diff {
         let rows1 = vec![RowSnapshot::new(vec![n("1")], vec![s("John")])];
         let table_snapshot1 = mk_table_snapshot("user", "id", vec!["name"], rows1);
 
-        let act = create_table_diff(Some(&table_snapshot1), None);
+        let act = create_table_diff(Some(&table_snapshot1), None, &IgnoreRules::default());
 
         assert!(act.empty());
     }
@@ -218,7 +508,7 @@ mod tests_create_snapshot_diff {
         let rows2 = vec![RowSnapshot::new(vec![n("1")], vec![s("Jane")])];
         let table_snapshot2 = mk_table_snapshot("user", "id", vec!["name"], rows2);
 
-        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2));
+        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2), &IgnoreRules::default());
 
         let primary_col_values1 = PrimaryColValues::new(vec![n("1")]);
 
@@ -239,7 +529,7 @@ mod tests_create_snapshot_diff {
         let rows2 = vec![RowSnapshot::new(vec![n("1")], vec![s("Jane")])];
         let table_snapshot2 = mk_table_snapshot("user", "id", vec!["name"], rows2);
 
-        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2));
+        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2), &IgnoreRules::default());
 
         let primary_col_values1 = PrimaryColValues::new(vec![n("1")]);
         let primary_col_values2 = PrimaryColValues::new(vec![n("2")]);
@@ -262,7 +552,7 @@ mod tests_create_snapshot_diff {
         let rows2 = vec![RowSnapshot::new(vec![n("1")], vec![s("John")]), RowSnapshot::new(vec![n("2")], vec![s("Jack")])];
         let table_snapshot2 = mk_table_snapshot("user", "id", vec!["name"], rows2);
 
-        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2));
+        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2), &IgnoreRules::default());
 
         let primary_col_values2 = PrimaryColValues::new(vec![n("2")]);
 
@@ -282,7 +572,7 @@ mod tests_create_snapshot_diff {
         let rows2 = vec![RowSnapshot::new(vec![n("1")], vec![n("39")])];
         let table_snapshot2 = mk_table_snapshot("user", "id", vec!["age"], rows2);
 
-        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2));
+        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2), &IgnoreRules::default());
 
         let primary_col_values1 = PrimaryColValues::new(vec![n("1")]);
 
@@ -305,7 +595,7 @@ mod tests_create_snapshot_diff {
         let rows2 = vec![RowSnapshot::new(vec![n("2")], vec![s("Jane")])];
         let table_snapshot2 = mk_table_snapshot("user", "id", vec!["name"], rows2);
 
-        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2));
+        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2), &IgnoreRules::default());
 
         let primary_col_values1 = PrimaryColValues::new(vec![n("1")]);
         let primary_col_values2 = PrimaryColValues::new(vec![n("2")]);
@@ -327,7 +617,7 @@ mod tests_create_snapshot_diff {
         let rows2 = vec![RowSnapshot::new(vec![n("123"), s("b")], vec![s("Jane")])];
         let table_snapshot2 = mk_table_snapshot("user", "id-code", vec!["name"], rows2);
 
-        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2));
+        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2), &IgnoreRules::default());
 
         let primary_col_values1 = PrimaryColValues::new(vec![n("123"), s("a")]);
         let primary_col_values2 = PrimaryColValues::new(vec![n("123"), s("b")]);
@@ -340,4 +630,153 @@ mod tests_create_snapshot_diff {
         assert_eq!(1, act.row_diffs2.len());
         assert_eq!(&Added(s("Jane")), mk_act(&act.row_diffs2, &primary_col_values2, "name"));
     }
+
+    #[test]
+    fn test_truncated() {
+        let rows1 = vec![RowSnapshot::new(vec![n("1")], vec![s("John")])];
+        let table_snapshot1 = mk_table_snapshot("user", "id", vec!["name"], rows1);
+
+        let rows2 = vec![RowSnapshot::new(vec![n("1")], vec![s("Jane")])];
+        let table_snapshot2 = mk_table_snapshot("user", "id", vec!["name"], rows2);
+
+        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2), &IgnoreRules::default());
+        assert!(!act.truncated);
+
+        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2.with_truncated(true)), &IgnoreRules::default());
+        assert!(act.truncated);
+    }
+
+    #[test]
+    fn test_ignore_rules() {
+        let rows1 = vec![
+            RowSnapshot::new(vec![n("1")], vec![s("John"), s("2023-07-03 08:17:52")]),
+            RowSnapshot::new(vec![n("2")], vec![s("Jack"), s("2023-07-03 08:17:52")]),
+        ];
+        let table_snapshot1 = mk_table_snapshot("user", "id", vec!["name", "updated_at"], rows1);
+
+        let rows2 = vec![
+            RowSnapshot::new(vec![n("1")], vec![s("John"), s("2023-07-03 08:42:35")]),
+            RowSnapshot::new(vec![n("2")], vec![s("Jane"), s("2023-07-03 08:42:35")]),
+        ];
+        let table_snapshot2 = mk_table_snapshot("user", "id", vec!["name", "updated_at"], rows2);
+
+        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2), &IgnoreRules::default());
+        assert_eq!(2, act.primary_col_values.len());
+
+        let ignore_rules = IgnoreRules::parse(&["*.updated_at".to_string()]).unwrap();
+        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2), &ignore_rules);
+
+        let primary_col_values2 = PrimaryColValues::new(vec![n("2")]);
+
+        assert_eq!(vec![&primary_col_values2], act.primary_col_values.iter().collect_vec());
+        assert_eq!(vec!["name"], act.col_names);
+        assert_eq!(vec!["updated_at"], act.ignored_col_names);
+
+        assert_eq!(&Deleted(s("Jack")), mk_act(&act.row_diffs1, &primary_col_values2, "name"));
+        assert_eq!(&Added(s("Jane")), mk_act(&act.row_diffs2, &primary_col_values2, "name"));
+        assert!(act.row_diffs2.get(&primary_col_values2.as_primary_value()).unwrap().get("updated_at").is_none());
+    }
+
+    #[test]
+    fn test_multiset() {
+        let row = |message: &str| RowSnapshot::new(vec![], vec![s("2023-07-03"), s(message)]);
+
+        let rows1 = vec![row("login"), row("login"), row("logout"), row("error")];
+        let table_snapshot1 = mk_table_snapshot("access_logs", "", vec!["at", "message"], rows1);
+
+        let rows2 = vec![row("login"), row("logout"), row("logout"), row("error"), row("retry")];
+        let table_snapshot2 = mk_table_snapshot("access_logs", "", vec!["at", "message"], rows2);
+
+        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2), &IgnoreRules::default());
+
+        let primary_col_values1 = PrimaryColValues::new(vec![n("1")]);
+        let primary_col_values2 = PrimaryColValues::new(vec![n("2")]);
+        let primary_col_values3 = PrimaryColValues::new(vec![n("3")]);
+
+        assert!(act.keyless);
+        assert_eq!("#", act.primary_col_name);
+        assert_eq!(vec![&primary_col_values1, &primary_col_values2, &primary_col_values3], act.primary_col_values.iter().collect_vec());
+
+        // 重複していた行が 1 行削除された
+        assert_eq!(&Deleted(s("login")), mk_act(&act.row_diffs1, &primary_col_values1, "message"));
+        assert_eq!(Some(&(2, 1)), act.row_counts.get(&primary_col_values1.as_primary_value()));
+
+        // 同じ行が 1 行追加されて重複した
+        assert_eq!(&Added(s("logout")), mk_act(&act.row_diffs2, &primary_col_values2, "message"));
+        assert_eq!(Some(&(1, 2)), act.row_counts.get(&primary_col_values2.as_primary_value()));
+
+        assert_eq!(&Added(s("retry")), mk_act(&act.row_diffs2, &primary_col_values3, "message"));
+        assert_eq!(Some(&(0, 1)), act.row_counts.get(&primary_col_values3.as_primary_value()));
+
+        assert_eq!(1, act.row_diffs1.len());
+        assert_eq!(2, act.row_diffs2.len());
+    }
+
+    #[test]
+    fn test_multiset_ignore_rules() {
+        let rows1 = vec![RowSnapshot::new(vec![], vec![s("2023-07-03"), s("login")])];
+        let table_snapshot1 = mk_table_snapshot("access_logs", "", vec!["at", "message"], rows1);
+
+        let rows2 = vec![RowSnapshot::new(vec![], vec![s("2023-07-04"), s("login")])];
+        let table_snapshot2 = mk_table_snapshot("access_logs", "", vec!["at", "message"], rows2);
+
+        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2), &IgnoreRules::default());
+        assert_eq!(2, act.primary_col_values.len());
+
+        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2), &IgnoreRules::parse(&["access_logs.at".to_string()]).unwrap());
+        assert!(act.empty());
+    }
+
+    #[test]
+    fn test_json() {
+        let j = |s: &str| JsonString(s.to_string());
+
+        let rows1 = vec![
+            RowSnapshot::new(vec![n("1")], vec![j(r#"{"id": 1, "name": "John"}"#)]),
+            RowSnapshot::new(vec![n("2")], vec![j(r#"{"id": 2, "tags": ["a"]}"#)]),
+        ];
+        let table_snapshot1 = mk_table_snapshot("user", "id", vec!["profile"], rows1);
+
+        let rows2 = vec![
+            RowSnapshot::new(vec![n("1")], vec![j(r#"{"name":"John","id":1}"#)]),
+            RowSnapshot::new(vec![n("2")], vec![j(r#"{"id": 2, "tags": ["a", "b"]}"#)]),
+        ];
+        let table_snapshot2 = mk_table_snapshot("user", "id", vec!["profile"], rows2);
+
+        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2), &IgnoreRules::default());
+
+        // キーの順序と空白だけが異なる行は、差分にならない
+        let primary_col_values2 = PrimaryColValues::new(vec![n("2")]);
+        assert_eq!(vec![&primary_col_values2], act.primary_col_values.iter().collect_vec());
+
+        let json_path_diffs = act.json_diffs.get(&primary_col_values2.as_primary_value()).unwrap().get("profile").unwrap();
+        assert_eq!(
+            vec![("$.tags[1]", JsonPathStatus::Added)],
+            json_path_diffs.iter().map(|diff| (diff.path.as_str(), diff.status.clone())).collect_vec()
+        );
+    }
+
+    #[test]
+    fn test_inline_diffs() {
+        let text = |s: &str| format!("<p>Welcome to our {s}, please enjoy your stay.</p>");
+
+        let rows1 = vec![RowSnapshot::new(vec![n("1")], vec![s(&text("store")), n("1")]), RowSnapshot::new(vec![n("2")], vec![s("John"), n("1")])];
+        let table_snapshot1 = mk_table_snapshot("pages", "id", vec!["body", "version"], rows1);
+
+        let rows2 = vec![RowSnapshot::new(vec![n("1")], vec![s(&text("shop")), n("2")]), RowSnapshot::new(vec![n("2")], vec![s("Jane"), n("2")])];
+        let table_snapshot2 = mk_table_snapshot("pages", "id", vec!["body", "version"], rows2);
+
+        let act = create_table_diff(Some(&table_snapshot1), Some(&table_snapshot2), &IgnoreRules::default()).with_inline_diffs(40);
+
+        // 長い文字列のカラムだけが対象になる
+        let primary_col_values1 = PrimaryColValues::new(vec![n("1")]);
+        assert_eq!(vec![&primary_col_values1.as_primary_value()], act.inline_diffs.keys().collect_vec());
+
+        let segments = act.inline_diffs.get(&primary_col_values1.as_primary_value()).unwrap();
+        assert_eq!(vec!["body"], segments.keys().collect_vec());
+        assert_eq!(
+            vec!["<p>Welcome to our ", "store", "shop", ", please enjoy your stay.</p>"],
+            segments["body"].iter().map(|segment| segment.text.as_str()).collect_vec()
+        );
+    }
 }
@@ -4,12 +4,25 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 use crate::domain::schema::{ColName, TableName};
+use crate::domain::table_mapping::TableMappings;
 
-#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+pub const DEFAULT_ROW_LIMIT: usize = 1000;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
 pub struct DumpConfig {
     pub table_name: TableName,
     pub col_names: Vec<ColName>,
     pub value: String,
+    #[serde(default)]
+    pub row_limit: Option<usize>,
+    #[serde(default)]
+    pub filter: Option<String>,
+    #[serde(default)]
+    pub excluded_col_names: Vec<ColName>,
+    #[serde(default)]
+    pub key_col_names: Vec<ColName>,
+    #[serde(default)]
+    pub multiset: bool,
 }
 
 impl DumpConfig {
@@ -25,32 +38,161 @@ impl DumpConfig {
             (None, None) => "ignore".to_string(),
         };
 
-        Self { table_name: table_name.into(), col_names, value }
+        Self {
+            table_name: table_name.into(),
+            col_names,
+            value,
+            row_limit: None,
+            filter: None,
+            excluded_col_names: vec![],
+            key_col_names: vec![],
+            multiset: false,
+        }
     }
 
     pub fn new<S: Into<String>>(table_name: S, col_names: Vec<S>, value: S) -> Self {
-        Self { table_name: table_name.into(), col_names: col_names.into_iter().map(|col_name| col_name.into()).collect(), value: value.into() }
+        Self {
+            table_name: table_name.into(),
+            col_names: col_names.into_iter().map(|col_name| col_name.into()).collect(),
+            value: value.into(),
+            row_limit: None,
+            filter: None,
+            excluded_col_names: vec![],
+            key_col_names: vec![],
+            multiset: false,
+        }
+    }
+
+    pub fn with_row_limit(self, row_limit: Option<usize>) -> Self {
+        Self { row_limit, ..self }
+    }
+
+    pub fn with_filter(self, filter: Option<String>) -> Self {
+        Self { filter, ..self }
+    }
+
+    pub fn with_excluded_col_names(self, excluded_col_names: Vec<ColName>) -> Self {
+        Self { excluded_col_names, ..self }
+    }
+
+    pub fn with_key_col_names(self, key_col_names: Vec<ColName>) -> Self {
+        Self { key_col_names, ..self }
+    }
+
+    // 主キーのないテーブルを、行の多重集合として dump する
+    pub fn with_multiset(self, multiset: bool) -> Self {
+        Self { multiset, ..self }
     }
 
     pub fn merge(def: Vec<Self>, found: Vec<Self>) -> Vec<Self> {
-        let saved: HashMap<TableName, String> = found.into_iter().map(|dump_config| (dump_config.table_name, dump_config.value)).collect();
+        let saved: HashMap<TableName, Self> = found.into_iter().map(|dump_config| (dump_config.table_name.clone(), dump_config)).collect();
+
+        def.into_iter()
+            .map(|dump_config| match saved.get(&dump_config.table_name) {
+                Some(found) => Self {
+                    value: found.value.clone(),
+                    row_limit: found.row_limit,
+                    filter: found.filter.clone(),
+                    // 削除されたカラムは引き継がない
+                    excluded_col_names: found
+                        .excluded_col_names
+                        .iter()
+                        .filter(|&col_name| dump_config.col_names.contains(col_name))
+                        .cloned()
+                        .collect(),
+                    // 一部のカラムが削除されたキーは、別の意味のキーになるので引き継がない
+                    key_col_names: if found.key_col_names.iter().all(|col_name| dump_config.col_names.contains(col_name)) {
+                        found.key_col_names.clone()
+                    } else {
+                        vec![]
+                    },
+                    multiset: found.multiset,
+                    ..dump_config
+                },
+                None => dump_config,
+            })
+            .collect_vec()
+    }
+
+    pub fn is_all(&self) -> bool {
+        self.value == "all"
+    }
 
-        def.iter().map(|dump_config| dump_config.set_value(saved.get(&dump_config.table_name).unwrap_or(&dump_config.value))).collect_vec()
+    // all は上限の指定がなければ全行を、それ以外は上限の指定がなければ 1,000 行を dump する
+    pub fn get_row_limit(&self) -> Option<usize> {
+        match self.row_limit {
+            Some(row_limit) => Some(row_limit),
+            None if self.is_all() => None,
+            None => Some(DEFAULT_ROW_LIMIT),
+        }
     }
 
-    fn set_value(&self, value: &str) -> Self {
-        Self { table_name: self.table_name.clone(), col_names: self.col_names.clone(), value: value.to_string() }
+    // 空白だけの条件は指定なしとして扱う
+    pub fn get_filter(&self) -> Option<&str> {
+        self.filter.as_deref().map(str::trim).filter(|filter| !filter.is_empty())
     }
 
     pub fn sort(mut dump_configs: Vec<Self>) -> Vec<Self> {
         dump_configs.sort_by_key(|dump_config| dump_config.table_name.clone());
         dump_configs
     }
+
+    // 別々のプロジェクトのスナップショットを比較する場合に、dump の条件の違いが差分に表れるテーブルを警告する
+    pub fn find_incompatibilities(dump_configs1: &[Self], dump_configs2: &[Self], table_mappings: &TableMappings) -> Vec<String> {
+        let dump_configs2: HashMap<TableName, &Self> =
+            dump_configs2.iter().map(|dump_config| (table_mappings.rename(&dump_config.table_name), dump_config)).collect();
+
+        let mut warnings = vec![];
+        for dump_config1 in Self::sort(dump_configs1.to_vec()) {
+            let table_name = &dump_config1.table_name;
+            let dump_config2 = match dump_configs2.get(table_name) {
+                Some(&dump_config2) => dump_config2,
+                None if dump_config1.value != "ignore" => {
+                    warnings.push(format!("{table_name}: table not found in snapshot 2"));
+                    continue;
+                }
+                None => continue,
+            };
+            if dump_config1.value == "ignore" && dump_config2.value == "ignore" {
+                continue;
+            }
+
+            let describe = |values: Vec<String>| if values.is_empty() { "none".to_string() } else { values.join(", ") };
+            let checks = [
+                ("dump method", dump_config1.value.clone(), dump_config2.value.clone()),
+                (
+                    "row limit",
+                    describe(dump_config1.get_row_limit().into_iter().map(|n| n.to_string()).collect()),
+                    describe(dump_config2.get_row_limit().into_iter().map(|n| n.to_string()).collect()),
+                ),
+                (
+                    "filter",
+                    describe(dump_config1.get_filter().into_iter().map(str::to_string).collect()),
+                    describe(dump_config2.get_filter().into_iter().map(str::to_string).collect()),
+                ),
+                (
+                    "excluded columns",
+                    describe(dump_config1.excluded_col_names.iter().sorted().cloned().collect()),
+                    describe(dump_config2.excluded_col_names.iter().sorted().cloned().collect()),
+                ),
+                ("key columns", describe(dump_config1.key_col_names.clone()), describe(dump_config2.key_col_names.clone())),
+                ("multiset", dump_config1.multiset.to_string(), dump_config2.multiset.to_string()),
+            ];
+            for (label, value1, value2) in checks {
+                if value1 != value2 {
+                    warnings.push(format!("{table_name}: different {label} ( {value1} / {value2} )"));
+                }
+            }
+        }
+
+        warnings
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::domain::dump_config::DumpConfig;
+    use crate::domain::table_mapping::TableMappings;
 
     #[test]
     fn init() {
@@ -100,4 +242,110 @@ mod tests {
         assert_eq!("limited", sut[0].value);
         assert_eq!("updated_at", sut[1].value);
     }
+
+    #[test]
+    fn merge_row_limit() {
+        let def = vec![DumpConfig::new("groups", vec!["id", "name"], "limited"), DumpConfig::new("users", vec!["id", "name"], "limited")];
+        let found = vec![DumpConfig::new("users", vec!["id", "name"], "all").with_row_limit(Some(5000))];
+
+        let sut = DumpConfig::merge(def, found);
+
+        assert_eq!(None, sut[0].row_limit);
+        assert_eq!("all", sut[1].value);
+        assert_eq!(Some(5000), sut[1].row_limit);
+    }
+
+    #[test]
+    fn merge_filter() {
+        let def = vec![DumpConfig::new("groups", vec!["id", "name"], "limited"), DumpConfig::new("users", vec!["id", "tenant_id"], "limited")];
+        let found = vec![DumpConfig::new("users", vec!["id", "tenant_id"], "limited").with_filter(Some("tenant_id = 42".to_string()))];
+
+        let sut = DumpConfig::merge(def, found);
+
+        assert_eq!(None, sut[0].filter);
+        assert_eq!(Some("tenant_id = 42".to_string()), sut[1].filter);
+    }
+
+    #[test]
+    fn merge_excluded_col_names() {
+        let def = vec![DumpConfig::new("users", vec!["id", "name", "updated_at"], "limited")];
+        let found = vec![DumpConfig::new("users", vec!["id", "name", "lock_version", "updated_at"], "limited")
+            .with_excluded_col_names(vec!["lock_version".to_string(), "updated_at".to_string()])];
+
+        let sut = DumpConfig::merge(def, found);
+
+        assert_eq!(vec!["updated_at"], sut[0].excluded_col_names);
+    }
+
+    #[test]
+    fn merge_key_col_names() {
+        let def =
+            vec![DumpConfig::new("user_groups", vec!["user_id", "group_id"], "limited"), DumpConfig::new("logs", vec!["at", "message"], "limited")];
+        let found = vec![
+            DumpConfig::new("user_groups", vec!["user_id", "group_id"], "limited")
+                .with_key_col_names(vec!["user_id".to_string(), "group_id".to_string()]),
+            DumpConfig::new("logs", vec!["id", "at", "message"], "limited").with_key_col_names(vec!["id".to_string(), "at".to_string()]),
+        ];
+
+        let sut = DumpConfig::merge(def, found);
+
+        assert_eq!(vec!["user_id", "group_id"], sut[0].key_col_names);
+        assert!(sut[1].key_col_names.is_empty());
+    }
+
+    #[test]
+    fn merge_multiset() {
+        let def = vec![DumpConfig::new("access_logs", vec!["at", "path"], "limited"), DumpConfig::new("users", vec!["id", "name"], "limited")];
+        let found = vec![DumpConfig::new("access_logs", vec!["at", "path"], "limited").with_multiset(true)];
+
+        let sut = DumpConfig::merge(def, found);
+
+        assert!(sut[0].multiset);
+        assert!(!sut[1].multiset);
+    }
+
+    #[test]
+    fn get_filter() {
+        assert_eq!(None, DumpConfig::new("users", vec!["id"], "limited").get_filter());
+        assert_eq!(None, DumpConfig::new("users", vec!["id"], "limited").with_filter(Some(" ".to_string())).get_filter());
+        assert_eq!(
+            Some("tenant_id = 42"),
+            DumpConfig::new("users", vec!["id"], "limited").with_filter(Some(" tenant_id = 42 ".to_string())).get_filter()
+        );
+    }
+
+    #[test]
+    fn find_incompatibilities() {
+        let dump_configs1 = vec![
+            DumpConfig::new("users", vec!["id"], "all").with_filter(Some("tenant_id = 1".to_string())),
+            DumpConfig::new("items", vec!["id"], "updated_at"),
+            DumpConfig::new("logs", vec!["id"], "ignore"),
+            DumpConfig::new("tags", vec!["id"], "limited"),
+        ];
+        let dump_configs2 = vec![
+            DumpConfig::new("app_users", vec!["id"], "all").with_excluded_col_names(vec!["updated_at".to_string()]),
+            DumpConfig::new("items", vec!["id"], "updated_at").with_row_limit(Some(1000)),
+            DumpConfig::new("logs", vec!["id"], "ignore"),
+        ];
+        let table_mappings = TableMappings::parse(&["users=app_users".to_string()]).unwrap();
+
+        assert_eq!(
+            vec![
+                "tags: table not found in snapshot 2",
+                "users: different filter ( tenant_id = 1 / none )",
+                "users: different excluded columns ( none / updated_at )",
+            ],
+            DumpConfig::find_incompatibilities(&dump_configs1, &dump_configs2, &table_mappings)
+        );
+    }
+
+    #[test]
+    fn get_row_limit() {
+        assert_eq!(Some(1000), DumpConfig::new("users", vec!["id"], "limited").get_row_limit());
+        assert_eq!(Some(1000), DumpConfig::new("users", vec!["id"], "updated_at").get_row_limit());
+        assert_eq!(None, DumpConfig::new("users", vec!["id"], "all").get_row_limit());
+
+        assert_eq!(Some(10), DumpConfig::new("users", vec!["id"], "limited").with_row_limit(Some(10)).get_row_limit());
+        assert_eq!(Some(10), DumpConfig::new("users", vec!["id"], "all").with_row_limit(Some(10)).get_row_limit());
+    }
 }
@@ -0,0 +1,141 @@
+use anyhow::anyhow;
+use itertools::Itertools;
+
+use crate::domain::schema::{ColName, TableName};
+
+// テーブル名とカラム名をそれぞれ glob で指定する ( 例: users.updated_at, *.updated_at, *_logs.* )
+#[derive(Eq, PartialEq, Debug)]
+pub struct IgnoreRule {
+    table_pattern: String,
+    col_pattern: String,
+}
+
+impl IgnoreRule {
+    pub fn parse(rule: &str) -> anyhow::Result<Self> {
+        match rule.trim().split_once('.') {
+            Some((table_pattern, col_pattern)) if !table_pattern.is_empty() && !col_pattern.is_empty() => {
+                Ok(Self { table_pattern: table_pattern.to_string(), col_pattern: col_pattern.to_string() })
+            }
+            _ => Err(anyhow!("invalid ignore rule, specify as table.column: {}", rule)),
+        }
+    }
+
+    pub fn matches(&self, table_name: &TableName, col_name: &ColName) -> bool {
+        glob_match(&self.table_pattern, table_name) && glob_match(&self.col_pattern, col_name)
+    }
+
+    fn as_string(&self) -> String {
+        format!("{}.{}", self.table_pattern, self.col_pattern)
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Default)]
+pub struct IgnoreRules {
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreRules {
+    // 同じルールの組み合わせが同じキャッシュキーになるように、重複を除いて並べておく
+    pub fn parse(rules: &[String]) -> anyhow::Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| rule.trim())
+            .filter(|rule| !rule.is_empty())
+            .sorted()
+            .dedup()
+            .map(IgnoreRule::parse)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self { rules })
+    }
+
+    pub fn as_strings(&self) -> Vec<String> {
+        self.rules.iter().map(IgnoreRule::as_string).collect()
+    }
+
+    pub fn as_key(&self) -> String {
+        self.as_strings().join("\n")
+    }
+
+    pub fn get_ignored_col_names<'a>(&self, table_name: &TableName, col_names: &[&'a ColName]) -> Vec<&'a ColName> {
+        col_names.iter().filter(|&&col_name| self.rules.iter().any(|rule| rule.matches(table_name, col_name))).copied().collect()
+    }
+}
+
+// * は 0 文字以上の任意の文字列に、? は任意の 1 文字にマッチする
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect_vec();
+    let text = text.chars().collect_vec();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            backtrack = Some((star_p, star_t + 1));
+            p = star_p + 1;
+            t = star_t + 1;
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::ignore_rule::{glob_match, IgnoreRule, IgnoreRules};
+
+    fn s(s: &str) -> String {
+        s.to_string()
+    }
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("updated_at", "updated_at"));
+        assert!(!glob_match("updated_at", "updated_at2"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "users"));
+        assert!(glob_match("*_at", "updated_at"));
+        assert!(glob_match("*_at", "_at"));
+        assert!(!glob_match("*_at", "updated_by"));
+        assert!(glob_match("last_*_at", "last_login_at"));
+        assert!(glob_match("*_*_at", "last_login_at"));
+        assert!(glob_match("user?", "users"));
+        assert!(!glob_match("user?", "user"));
+    }
+
+    #[test]
+    fn parse() {
+        assert!(IgnoreRule::parse("users.updated_at").is_ok());
+        assert!(IgnoreRule::parse(" *.updated_at ").is_ok());
+        assert!(IgnoreRule::parse("updated_at").is_err());
+        assert!(IgnoreRule::parse(".updated_at").is_err());
+        assert!(IgnoreRule::parse("users.").is_err());
+
+        let sut = IgnoreRules::parse(&[s("*.updated_at"), s(" "), s("users.lock_version"), s("*.updated_at ")]).unwrap();
+        assert_eq!(vec!["*.updated_at", "users.lock_version"], sut.as_strings());
+        assert_eq!("*.updated_at\nusers.lock_version", sut.as_key());
+
+        assert_eq!("", IgnoreRules::parse(&[]).unwrap().as_key());
+        assert!(IgnoreRules::parse(&[s("updated_at")]).is_err());
+    }
+
+    #[test]
+    fn get_ignored_col_names() {
+        let sut = IgnoreRules::parse(&[s("*.updated_at"), s("users.lock_*")]).unwrap();
+
+        let (id, lock_version, updated_at) = (s("id"), s("lock_version"), s("updated_at"));
+        let col_names = vec![&id, &lock_version, &updated_at];
+
+        assert_eq!(vec![&lock_version, &updated_at], sut.get_ignored_col_names(&s("users"), &col_names));
+        assert_eq!(vec![&updated_at], sut.get_ignored_col_names(&s("groups"), &col_names));
+    }
+}
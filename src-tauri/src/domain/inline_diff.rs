@@ -0,0 +1,114 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::db_object::LineStatus;
+use crate::domain::line_diff::diff_lines;
+
+// 最長共通部分列の計算時間を抑えるため、区切った数がこれを超える値は計算しない
+const MAX_TOKENS: usize = 2000;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct InlineSegment {
+    pub status: LineStatus,
+    pub text: String,
+}
+
+// 変更前後の値を単語単位で比較し、共通、削除、追加の区間に分ける ( 短い値は全体を見れば分かるので、どちらかが min_chars 以上の場合だけ計算する )
+pub fn diff_inline(value1: &str, value2: &str, min_chars: usize) -> Option<Vec<InlineSegment>> {
+    if value1.chars().count() < min_chars && value2.chars().count() < min_chars {
+        return None;
+    }
+
+    let tokens1 = tokenize(value1);
+    let tokens2 = tokenize(value2);
+    if tokens1.len() > MAX_TOKENS || tokens2.len() > MAX_TOKENS {
+        return None;
+    }
+
+    // 同じ状態の区間が続く場合は、1 つにまとめる
+    let segments = diff_lines(&tokens1, &tokens2)
+        .into_iter()
+        .group_by(|(sign, _)| *sign)
+        .into_iter()
+        .map(|(sign, edits)| {
+            let status = match sign {
+                '+' => LineStatus::Added,
+                '-' => LineStatus::Deleted,
+                _ => LineStatus::Stay,
+            };
+            InlineSegment { status, text: edits.map(|(_, token)| token).collect() }
+        })
+        .collect();
+    Some(segments)
+}
+
+// 英数字の並びと空白の並びはまとめ、それ以外 ( 記号や日本語 ) は 1 文字ずつに区切る
+fn tokenize(value: &str) -> Vec<&str> {
+    let kind = |c: char| {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            1
+        } else if c.is_whitespace() {
+            2
+        } else {
+            0
+        }
+    };
+
+    let mut tokens = vec![];
+    let mut start = 0;
+    let mut prev = None;
+    for (i, c) in value.char_indices() {
+        let k = kind(c);
+        if i != 0 && (k == 0 || Some(k) != prev) {
+            tokens.push(&value[start..i]);
+            start = i;
+        }
+        prev = Some(k);
+    }
+    if start < value.len() {
+        tokens.push(&value[start..]);
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::db_object::LineStatus::{Added, Deleted, Stay};
+    use crate::domain::inline_diff::{diff_inline, tokenize};
+
+    #[test]
+    fn tokens() {
+        assert_eq!(
+            vec!["<", "p", " ", "class", "=", "\"", "note_1", "\"", ">", "東", "京", "  ", "Tokyo"],
+            tokenize(r#"<p class="note_1">東京  Tokyo"#)
+        );
+        assert!(tokenize("").is_empty());
+    }
+
+    #[test]
+    fn inline() {
+        let act =
+            diff_inline(r#"<p class="note">Welcome to our store, John.</p>"#, r#"<p class="notice">Welcome to our shop, John!</p>"#, 40).unwrap();
+
+        assert_eq!(
+            vec![
+                (Stay, r#"<p class=""#),
+                (Deleted, "note"),
+                (Added, "notice"),
+                (Stay, r#"">Welcome to our "#),
+                (Deleted, "store"),
+                (Added, "shop"),
+                (Stay, ", John"),
+                (Deleted, "."),
+                (Added, "!"),
+                (Stay, "</p>"),
+            ],
+            act.iter().map(|segment| (segment.status.clone(), segment.text.as_str())).collect::<Vec<_>>()
+        );
+
+        // 指定した文字数より短い値と、長すぎる値は対象外
+        assert_eq!(None, diff_inline("John", "Jane", 40));
+        assert!(diff_inline("John", "Jane", 4).is_some());
+        assert_eq!(None, diff_inline(&"a ".repeat(1001), &"b ".repeat(1001), 40));
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// キーの順序と空白の違いで差分にならないように、キーを並べ替えて空白を除いた形にする ( JSON として読めない値はそのまま返す )
+pub fn canonicalize_json(json: &str) -> String {
+    match parse_json(json) {
+        Some(value) => to_canonical_string(&value),
+        None => json.to_string(),
+    }
+}
+
+// 大きな整数や桁の多い小数が f64 で丸められないように、数値は元の文字列のまま持つ
+#[derive(Eq, PartialEq, Debug)]
+enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(BTreeMap<String, JsonValue>),
+}
+
+fn to_canonical_string(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => "null".to_string(),
+        JsonValue::Bool(b) => b.to_string(),
+        JsonValue::Number(n) => n.clone(),
+        JsonValue::String(s) => Value::String(s.clone()).to_string(),
+        JsonValue::Array(values) => format!("[{}]", values.iter().map(to_canonical_string).collect_vec().join(",")),
+        JsonValue::Object(map) => {
+            let entries = map.iter().map(|(key, value)| format!("{}:{}", Value::String(key.clone()), to_canonical_string(value)));
+            format!("{{{}}}", entries.collect_vec().join(","))
+        }
+    }
+}
+
+fn parse_json(json: &str) -> Option<JsonValue> {
+    let mut parser = JsonParser { json, pos: 0 };
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    (parser.pos == json.len()).then_some(value)
+}
+
+// 文字列と数値の妥当性の確認は serde_json に任せ、値の区切りだけを読む
+struct JsonParser<'a> {
+    json: &'a str,
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.json.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn consume(&mut self, token: &str) -> Option<()> {
+        self.skip_whitespace();
+        self.json[self.pos..].starts_with(token).then(|| self.pos += token.len())
+    }
+
+    fn parse_value(&mut self) -> Option<JsonValue> {
+        self.skip_whitespace();
+        match self.peek()? {
+            b'n' => self.consume("null").map(|_| JsonValue::Null),
+            b't' => self.consume("true").map(|_| JsonValue::Bool(true)),
+            b'f' => self.consume("false").map(|_| JsonValue::Bool(false)),
+            b'"' => self.parse_string().map(JsonValue::String),
+            b'[' => self.parse_array(),
+            b'{' => self.parse_object(),
+            _ => self.parse_number(),
+        }
+    }
+
+    fn parse_string(&mut self) -> Option<String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        self.consume("\"")?;
+        loop {
+            match self.peek()? {
+                b'\\' => self.pos += 2,
+                b'"' => break,
+                _ => self.pos += 1,
+            }
+        }
+        self.pos += 1;
+        serde_json::from_str(self.json.get(start..self.pos)?).ok()
+    }
+
+    fn parse_number(&mut self) -> Option<JsonValue> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E')) {
+            self.pos += 1;
+        }
+        let number = &self.json[start..self.pos];
+        serde_json::from_str::<serde_json::Number>(number).ok().map(|_| JsonValue::Number(number.to_string()))
+    }
+
+    fn parse_array(&mut self) -> Option<JsonValue> {
+        self.consume("[")?;
+        let mut values = vec![];
+        if self.consume("]").is_some() {
+            return Some(JsonValue::Array(values));
+        }
+        loop {
+            values.push(self.parse_value()?);
+            if self.consume("]").is_some() {
+                return Some(JsonValue::Array(values));
+            }
+            self.consume(",")?;
+        }
+    }
+
+    // 同じキーが複数ある場合は、serde_json と同じく後の値を使う
+    fn parse_object(&mut self) -> Option<JsonValue> {
+        self.consume("{")?;
+        let mut map = BTreeMap::new();
+        if self.consume("}").is_some() {
+            return Some(JsonValue::Object(map));
+        }
+        loop {
+            let key = self.parse_string()?;
+            self.consume(":")?;
+            map.insert(key, self.parse_value()?);
+            if self.consume("}").is_some() {
+                return Some(JsonValue::Object(map));
+            }
+            self.consume(",")?;
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum JsonPathStatus {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl JsonPathStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            JsonPathStatus::Added => "added",
+            JsonPathStatus::Removed => "removed",
+            JsonPathStatus::Changed => "changed",
+        }
+    }
+}
+
+// 値は正規化した JSON で持つ ( 追加されたパスは value1 が、削除されたパスは value2 が None になる )
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct JsonPathDiff {
+    pub path: String,
+    pub status: JsonPathStatus,
+    pub value1: Option<String>,
+    pub value2: Option<String>,
+}
+
+// 変更のあったパスだけを、$.items[0].name の形式で返す ( どちらかが JSON として読めない場合は None )
+pub fn diff_json(json1: &str, json2: &str) -> Option<Vec<JsonPathDiff>> {
+    let value1 = parse_json(json1)?;
+    let value2 = parse_json(json2)?;
+
+    let mut json_path_diffs = vec![];
+    diff_value("$".to_string(), Some(&value1), Some(&value2), &mut json_path_diffs);
+    Some(json_path_diffs)
+}
+
+// オブジェクト同士はキーごと、配列同士は添字ごとに比較し、それ以外は値全体を比較する
+fn diff_value(path: String, value1: Option<&JsonValue>, value2: Option<&JsonValue>, json_path_diffs: &mut Vec<JsonPathDiff>) {
+    match (value1, value2) {
+        (Some(JsonValue::Object(map1)), Some(JsonValue::Object(map2))) => {
+            for key in map1.keys().chain(map2.keys()).unique().sorted() {
+                diff_value(format!("{}{}", path, as_path_part(key)), map1.get(key), map2.get(key), json_path_diffs);
+            }
+        }
+        (Some(JsonValue::Array(values1)), Some(JsonValue::Array(values2))) => {
+            for i in 0..values1.len().max(values2.len()) {
+                diff_value(format!("{}[{}]", path, i), values1.get(i), values2.get(i), json_path_diffs);
+            }
+        }
+        (Some(value1), Some(value2)) if value1 == value2 => {}
+        (value1, value2) => {
+            let status = match (value1, value2) {
+                (None, _) => JsonPathStatus::Added,
+                (_, None) => JsonPathStatus::Removed,
+                _ => JsonPathStatus::Changed,
+            };
+            json_path_diffs.push(JsonPathDiff { path, status, value1: value1.map(to_canonical_string), value2: value2.map(to_canonical_string) });
+        }
+    }
+}
+
+// 識別子として読めないキーは、$["first name"] のように括弧で囲む
+fn as_path_part(key: &str) -> String {
+    let is_identifier =
+        key.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_identifier {
+        format!(".{}", key)
+    } else {
+        format!("[{}]", Value::String(key.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::json_diff::JsonPathStatus::{Added, Changed, Removed};
+    use crate::domain::json_diff::{canonicalize_json, diff_json};
+
+    #[test]
+    fn canonicalize() {
+        assert_eq!(r#"{"a":[1,{"x":null,"y":"b c"}],"b":true}"#, canonicalize_json(r#"{ "b": true, "a": [1, {"y": "b c", "x": null}] }"#));
+        assert_eq!(canonicalize_json(r#"{"id": 1, "name": "John"}"#), canonicalize_json(r#"{"name":"John","id":1}"#));
+        assert_eq!("not json", canonicalize_json("not json"));
+
+        // 数値は丸めずに元の表記のまま比較する
+        assert_eq!(
+            r#"{"id":12345678901234567890,"rate":0.10000000000000000001}"#,
+            canonicalize_json(r#"{"rate": 0.10000000000000000001, "id": 12345678901234567890}"#)
+        );
+        assert_ne!(canonicalize_json("[12345678901234567890]"), canonicalize_json("[12345678901234567891]"));
+        assert_eq!(r#"["a\"b","あ"]"#, canonicalize_json(r#"["a\"b", "\u3042"]"#));
+        assert_eq!("{1}", canonicalize_json("{1}"));
+        assert_eq!("[1,]", canonicalize_json("[1,]"));
+        assert_eq!("01", canonicalize_json("01"));
+    }
+
+    #[test]
+    fn diff() {
+        let act = diff_json(
+            r#"{"name": "John", "tags": ["a", "b"], "option": {"id": 1, "first name": "J"}, "age": 20}"#,
+            r#"{"name": "John", "tags": ["a"], "option": {"id": 2, "first name": "J"}, "email": "john@example.com"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                ("$.age", Removed, Some("20"), None),
+                ("$.email", Added, None, Some(r#""john@example.com""#)),
+                ("$.option.id", Changed, Some("1"), Some("2")),
+                ("$.tags[1]", Removed, Some(r#""b""#), None),
+            ],
+            act.iter()
+                .map(|json_path_diff| (
+                    json_path_diff.path.as_str(),
+                    json_path_diff.status.clone(),
+                    json_path_diff.value1.as_deref(),
+                    json_path_diff.value2.as_deref()
+                ))
+                .collect::<Vec<_>>()
+        );
+
+        // 型が変わった場合は、値全体の変更とする
+        let act = diff_json(r#"{"option": {"id": 1}}"#, r#"{"option": [1]}"#).unwrap();
+        assert_eq!(
+            vec![("$.option", Some(r#"{"id":1}"#), Some("[1]"))],
+            act.iter().map(|diff| (diff.path.as_str(), diff.value1.as_deref(), diff.value2.as_deref())).collect::<Vec<_>>()
+        );
+
+        assert_eq!(r#"$["first name"]"#, diff_json(r#"{"first name": 1}"#, r#"{"first name": 2}"#).unwrap()[0].path);
+        assert_eq!(None, diff_json("not json", "{}"));
+        assert_eq!(1, diff_json(r#"{"id": 9007199254740993}"#, r#"{"id": 9007199254740992}"#).unwrap().len());
+    }
+}
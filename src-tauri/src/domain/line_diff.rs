@@ -0,0 +1,100 @@
+// 最長共通部分列で、行ごとに共通 ( ' ' ) 、削除 ( '-' ) 、追加 ( '+' ) に分ける ( 変更された区間では削除を追加より先に並べる )
+pub fn diff_lines<'a>(lines1: &[&'a str], lines2: &[&'a str]) -> Vec<(char, &'a str)> {
+    let mut edits = vec![];
+    hirschberg(lines1, lines2, &mut edits);
+
+    let mut sorted = vec![];
+    let mut added = vec![];
+    for (sign, line) in edits {
+        match sign {
+            '-' => sorted.push((sign, line)),
+            '+' => added.push((sign, line)),
+            _ => {
+                sorted.append(&mut added);
+                sorted.push((sign, line));
+            }
+        }
+    }
+    sorted.append(&mut added);
+    sorted
+}
+
+// 表全体を持たずに済むように、lines1 を半分に分けて最長共通部分列が通る lines2 の位置を求め、前後を再帰的に比較する
+fn hirschberg<'a>(lines1: &[&'a str], lines2: &[&'a str], edits: &mut Vec<(char, &'a str)>) {
+    // 先頭と末尾の共通する行は、分割せずにそのまま共通とする
+    let prefix = lines1.iter().zip(lines2).take_while(|(line1, line2)| line1 == line2).count();
+    let suffix = lines1[prefix..].iter().rev().zip(lines2[prefix..].iter().rev()).take_while(|(line1, line2)| line1 == line2).count();
+    let (body1, body2) = (&lines1[prefix..lines1.len() - suffix], &lines2[prefix..lines2.len() - suffix]);
+
+    edits.extend(lines1[..prefix].iter().map(|&line| (' ', line)));
+
+    match body1.len() {
+        _ if body2.is_empty() => edits.extend(body1.iter().map(|&line| ('-', line))),
+        0 => edits.extend(body2.iter().map(|&line| ('+', line))),
+        1 => match body2.iter().position(|&line| line == body1[0]) {
+            Some(j) => {
+                edits.extend(body2[..j].iter().map(|&line| ('+', line)));
+                edits.push((' ', body1[0]));
+                edits.extend(body2[j + 1..].iter().map(|&line| ('+', line)));
+            }
+            None => {
+                edits.push(('-', body1[0]));
+                edits.extend(body2.iter().map(|&line| ('+', line)));
+            }
+        },
+        _ => {
+            let mid = body1.len() / 2;
+            let forward = lcs_lengths(body1[..mid].iter(), body2.iter());
+            let backward = lcs_lengths(body1[mid..].iter().rev(), body2.iter().rev());
+
+            let m = body2.len();
+            let split = (0..=m).rev().max_by_key(|&j| forward[j] + backward[m - j]).unwrap_or(0);
+
+            hirschberg(&body1[..mid], &body2[..split], edits);
+            hirschberg(&body1[mid..], &body2[split..], edits);
+        }
+    }
+
+    edits.extend(lines1[lines1.len() - suffix..].iter().map(|&line| (' ', line)));
+}
+
+// lines1 と、lines2 の先頭から j 行までの最長共通部分列の長さを、1 行分の領域で計算する
+fn lcs_lengths<'a>(lines1: impl Iterator<Item = &'a &'a str>, lines2: impl Iterator<Item = &'a &'a str> + Clone) -> Vec<usize> {
+    let mut row = vec![0; lines2.clone().count() + 1];
+    for line1 in lines1 {
+        let mut diagonal = 0;
+        for (j, line2) in lines2.clone().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if line1 == line2 { diagonal + 1 } else { above.max(row[j]) };
+            diagonal = above;
+        }
+    }
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::line_diff::diff_lines;
+
+    #[test]
+    fn lines() {
+        assert_eq!(vec![(' ', "a"), ('-', "b"), ('+', "x"), (' ', "c"), ('+', "d")], diff_lines(&["a", "b", "c"], &["a", "x", "c", "d"]));
+        assert_eq!(vec![('-', "a")], diff_lines(&["a"], &[]));
+        assert_eq!(Vec::<(char, &str)>::new(), diff_lines(&[], &[]));
+    }
+
+    #[test]
+    fn lines_long() {
+        // 長い入力でも、共通部分を最大にしたまま削除を追加より先に並べる
+        let lines1 = (0..3000).map(|i| if i % 3 == 0 { "x" } else { "a" }).collect::<Vec<_>>();
+        let lines2 = (0..3000).map(|i| if i % 5 == 0 { "y" } else { "a" }).collect::<Vec<_>>();
+
+        let act = diff_lines(&lines1, &lines2);
+
+        let stay = act.iter().filter(|(sign, _)| *sign == ' ').count();
+        assert_eq!(lines1.iter().filter(|&&line| line == "a").count().min(lines2.iter().filter(|&&line| line == "a").count()), stay);
+        assert_eq!(lines1, act.iter().filter(|(sign, _)| *sign != '+').map(|(_, line)| *line).collect::<Vec<_>>());
+        assert_eq!(lines2, act.iter().filter(|(sign, _)| *sign != '-').map(|(_, line)| *line).collect::<Vec<_>>());
+        assert!(act.windows(2).all(|edits| !(edits[0].0 == '+' && edits[1].0 == '-')));
+    }
+}
@@ -1,6 +1,15 @@
+pub mod db_object;
 pub mod diff;
 pub mod dump_config;
+pub mod ignore_rule;
+pub mod inline_diff;
+pub mod json_diff;
+pub mod line_diff;
 pub mod project;
+pub mod rename;
 pub mod schema;
 pub mod snapshot;
 pub mod snapshot_result;
+pub mod table_definition;
+pub mod table_mapping;
+pub mod timeline;
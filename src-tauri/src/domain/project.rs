@@ -1,7 +1,12 @@
+use std::path::Path;
+
 use anyhow::anyhow;
+use diesel::{Connection, SqliteConnection};
 use mysql::{Conn, Opts, OptsBuilder};
+use postgres::{Client, NoTls};
 use r2d2::ManageConnection;
 use r2d2_mysql::MysqlConnectionManager;
+use serde::{Deserialize, Serialize};
 use urlencoding::encode;
 
 pub type ProjectId = String;
@@ -11,9 +16,12 @@ pub fn create_project_id() -> ProjectId {
     uuid::Uuid::new_v4().to_string()
 }
 
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
 pub enum Rdbms {
     Mysql,
+    Mariadb,
+    Postgres,
+    Sqlite,
 }
 
 #[derive(Eq, PartialEq, Debug)]
@@ -27,6 +35,8 @@ pub struct Project {
     pub host: String,
     pub port: String,
     pub schema: String,
+    // ローカルや開発用の環境は、確認なしでリストアできる
+    pub local: bool,
 }
 
 impl Project {
@@ -42,6 +52,19 @@ impl Project {
             host: host.into(),
             port: port.into(),
             schema: schema.into(),
+            local: false,
+        }
+    }
+
+    pub fn with_local(self, local: bool) -> Self {
+        Self { local, ..self }
+    }
+
+    pub fn test_connection(&self) -> anyhow::Result<()> {
+        match self.rdbms {
+            Rdbms::Mysql | Rdbms::Mariadb => self.create_connection().map(|_| ()),
+            Rdbms::Postgres => self.create_client().map(|_| ()),
+            Rdbms::Sqlite => self.create_sqlite_file_connection().map(|_| ()),
         }
     }
 
@@ -53,7 +76,33 @@ impl Project {
         manager.connect().map_err(|e| anyhow!(e))
     }
 
+    pub fn create_client(&self) -> anyhow::Result<Client> {
+        let url = self.create_url();
+        Client::connect(&url, NoTls).map_err(|e| anyhow!(e))
+    }
+
+    // SQLite のプロジェクトは Database にファイルパスを保持する
+    pub fn create_sqlite_file_connection(&self) -> anyhow::Result<SqliteConnection> {
+        if !Path::new(&self.schema).is_file() {
+            return Err(anyhow!("file not found: {}", &self.schema));
+        }
+        SqliteConnection::establish(&self.schema).map_err(|e| anyhow!(e))
+    }
+
     pub fn create_url(&self) -> String {
-        format!("mysql://{}:{}@{}:{}/{}", encode(&self.user), encode(&self.password), encode(&self.host), encode(&self.port), encode(&self.schema))
+        let scheme = match self.rdbms {
+            Rdbms::Mysql | Rdbms::Mariadb => "mysql",
+            Rdbms::Postgres => "postgres",
+            Rdbms::Sqlite => return format!("sqlite://{}", self.schema),
+        };
+        format!(
+            "{}://{}:{}@{}:{}/{}",
+            scheme,
+            encode(&self.user),
+            encode(&self.password),
+            encode(&self.host),
+            encode(&self.port),
+            encode(&self.schema)
+        )
     }
 }
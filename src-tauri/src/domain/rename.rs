@@ -0,0 +1,411 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::anyhow;
+use itertools::Itertools;
+
+use crate::domain::schema::{ColName, Hash, TableName};
+use crate::domain::snapshot::ColValue::Null;
+use crate::domain::snapshot::{ColValue, PrimaryColValues, TableSnapshot};
+use crate::domain::table_definition::TableDefinition;
+
+// 名前を変えたとみなすのに必要な、内容が一致する行 ( カラムの場合は値 ) の割合
+const SIMILARITY: f64 = 0.9;
+
+// スナップショット 1 のテーブルやカラムを、スナップショット 2 の名前に読み替える ( 例: members=users, users.name=full_name )
+#[derive(Eq, PartialEq, Debug)]
+pub struct RenameRule {
+    table_name: TableName,
+    col_name: Option<ColName>,
+    new_name: String,
+}
+
+impl RenameRule {
+    pub fn parse(rule: &str) -> anyhow::Result<Self> {
+        let invalid = || anyhow!("invalid rename, specify as table=new_table or table.column=new_column: {}", rule);
+
+        let (name, new_name) = rule.trim().split_once('=').ok_or_else(invalid)?;
+        let (name, new_name) = (name.trim(), new_name.trim());
+        if name.is_empty() || new_name.is_empty() {
+            return Err(invalid());
+        }
+
+        match name.split_once('.') {
+            Some((table_name, col_name)) if !table_name.is_empty() && !col_name.is_empty() => {
+                Ok(Self { table_name: table_name.to_string(), col_name: Some(col_name.to_string()), new_name: new_name.to_string() })
+            }
+            Some(_) => Err(invalid()),
+            None => Ok(Self { table_name: name.to_string(), col_name: None, new_name: new_name.to_string() }),
+        }
+    }
+
+    fn as_string(&self) -> String {
+        format!("{}={}", self.old_key(), self.new_name)
+    }
+
+    fn old_key(&self) -> String {
+        match &self.col_name {
+            Some(col_name) => format!("{}.{}", self.table_name, col_name),
+            None => self.table_name.clone(),
+        }
+    }
+
+    fn new_key(&self) -> String {
+        match &self.col_name {
+            Some(_) => format!("{}.{}", self.table_name, self.new_name),
+            None => self.new_name.clone(),
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Default)]
+pub struct RenameRules {
+    rules: Vec<RenameRule>,
+}
+
+impl RenameRules {
+    // 除外ルールと同じく、同じ組み合わせが同じキャッシュキーになるように並べておく
+    pub fn parse(rules: &[String]) -> anyhow::Result<Self> {
+        let rules =
+            rules.iter().map(|rule| rule.trim()).filter(|rule| !rule.is_empty()).map(RenameRule::parse).collect::<anyhow::Result<Vec<_>>>()?;
+
+        Self::new(rules)
+    }
+
+    fn new(rules: Vec<RenameRule>) -> anyhow::Result<Self> {
+        let rules = rules.into_iter().sorted_by_key(RenameRule::as_string).dedup().collect_vec();
+
+        // 1 つのテーブルやカラムを複数の名前に読み替えることも、複数のテーブルやカラムを同じ名前に読み替えることもできない
+        let duplicates = rules.iter().map(RenameRule::old_key).duplicates().join(", ");
+        if !duplicates.is_empty() {
+            return Err(anyhow!("renamed more than once: {}", duplicates));
+        }
+        let duplicates = rules.iter().map(RenameRule::new_key).duplicates().join(", ");
+        if !duplicates.is_empty() {
+            return Err(anyhow!("renamed to the same name more than once: {}", duplicates));
+        }
+
+        Ok(Self { rules })
+    }
+
+    // 検出した名前の変更のうち、元の名前か変更後の名前がほかの候補と重なるものは、どれが正しいか判断できないので外す
+    fn without_conflicts(rules: Vec<RenameRule>) -> Self {
+        let old_keys = rules.iter().map(RenameRule::old_key).counts();
+        let new_keys = rules.iter().map(RenameRule::new_key).counts();
+        let rules = rules
+            .into_iter()
+            .filter(|rule| old_keys[&rule.old_key()] == 1 && new_keys[&rule.new_key()] == 1)
+            .sorted_by_key(RenameRule::as_string)
+            .collect();
+        Self { rules }
+    }
+
+    pub fn as_strings(&self) -> Vec<String> {
+        self.rules.iter().map(RenameRule::as_string).collect()
+    }
+
+    pub fn as_key(&self) -> String {
+        self.as_strings().join("\n")
+    }
+
+    fn new_table_name(&self, table_name: &TableName) -> TableName {
+        self.rules
+            .iter()
+            .find(|rule| &rule.table_name == table_name && rule.col_name.is_none())
+            .map_or(table_name.clone(), |rule| rule.new_name.clone())
+    }
+
+    fn new_col_name(&self, table_name: &TableName, col_name: &ColName) -> ColName {
+        self.rules
+            .iter()
+            .find(|rule| &rule.table_name == table_name && rule.col_name.as_ref() == Some(col_name))
+            .map_or(col_name.clone(), |rule| rule.new_name.clone())
+    }
+
+    // 読み替えたテーブルと同じ名前のテーブルがスナップショット 1 にもある場合は、比較の対象から外す
+    fn is_shadowed(&self, table_name: &TableName) -> bool {
+        self.rules.iter().any(|rule| rule.col_name.is_none() && &rule.new_name == table_name && &rule.table_name != table_name)
+    }
+
+    // スナップショット 2 の名前で比較できるように、スナップショット 1 のテーブル名とカラム名を読み替える
+    pub fn apply(&self, table_snapshots1: Vec<TableSnapshot>) -> Vec<TableSnapshot> {
+        table_snapshots1
+            .into_iter()
+            .filter(|table_snapshot| !self.is_shadowed(&table_snapshot.table_name))
+            .map(|table_snapshot| {
+                let table_name = &table_snapshot.table_name;
+                TableSnapshot {
+                    col_names: table_snapshot.col_names.iter().map(|col_name| self.new_col_name(table_name, col_name)).collect(),
+                    excluded_col_names: table_snapshot.excluded_col_names.iter().map(|col_name| self.new_col_name(table_name, col_name)).collect(),
+                    table_name: self.new_table_name(table_name),
+                    ..table_snapshot
+                }
+            })
+            .collect()
+    }
+
+    // テーブルの定義も、行と同じように読み替える
+    pub fn apply_definitions(&self, table_definitions1: Vec<TableDefinition>) -> Vec<TableDefinition> {
+        table_definitions1
+            .into_iter()
+            .filter(|table_definition| !self.is_shadowed(&table_definition.table_name))
+            .map(|mut table_definition| {
+                for col in table_definition.cols.iter_mut() {
+                    col.col_name = self.new_col_name(&table_definition.table_name, &col.col_name);
+                }
+                TableDefinition { table_name: self.new_table_name(&table_definition.table_name), ..table_definition }
+            })
+            .collect()
+    }
+
+    // 差分の表示用に、スナップショット 2 のテーブル名から元のテーブル名を引く
+    pub fn old_table_name(&self, new_table_name: &TableName) -> Option<TableName> {
+        self.rules.iter().find(|rule| rule.col_name.is_none() && &rule.new_name == new_table_name).map(|rule| rule.table_name.clone())
+    }
+
+    // 差分の表示用に、スナップショット 2 のカラム名から元のカラム名を引く
+    pub fn old_col_names(&self, new_table_name: &TableName) -> HashMap<ColName, ColName> {
+        let table_name = self.old_table_name(new_table_name).unwrap_or_else(|| new_table_name.clone());
+        self.rules
+            .iter()
+            .filter(|rule| rule.table_name == table_name)
+            .filter_map(|rule| rule.col_name.as_ref().map(|col_name| (rule.new_name.clone(), col_name.clone())))
+            .collect()
+    }
+}
+
+// 片方にしかないテーブルとカラムから、名前を変えただけとみられるものを探す
+pub fn detect_renames(table_snapshots1: &[TableSnapshot], table_snapshots2: &[TableSnapshot]) -> RenameRules {
+    let table_names1: HashSet<&TableName> = table_snapshots1.iter().map(|table_snapshot| &table_snapshot.table_name).collect();
+    let table_snapshots2: HashMap<&TableName, &TableSnapshot> =
+        table_snapshots2.iter().map(|table_snapshot| (&table_snapshot.table_name, table_snapshot)).collect();
+
+    let mut table_rules = vec![];
+    let mut pairs = vec![];
+
+    for table_snapshot1 in table_snapshots1.iter().sorted_by_key(|table_snapshot| &table_snapshot.table_name) {
+        if let Some(&table_snapshot2) = table_snapshots2.get(&table_snapshot1.table_name) {
+            pairs.push((table_snapshot1, table_snapshot2));
+            continue;
+        }
+
+        // 同じ内容のテーブルが複数ある場合は、どれに変えたのか判断できない
+        let candidates = table_snapshots2
+            .values()
+            .filter(|table_snapshot2| !table_names1.contains(&table_snapshot2.table_name))
+            .filter(|table_snapshot2| is_renamed_table(table_snapshot1, table_snapshot2))
+            .collect_vec();
+        if let [&table_snapshot2] = candidates[..] {
+            table_rules.push(RenameRule {
+                table_name: table_snapshot1.table_name.clone(),
+                col_name: None,
+                new_name: table_snapshot2.table_name.clone(),
+            });
+            pairs.push((table_snapshot1, table_snapshot2));
+        }
+    }
+
+    // 複数のテーブルが同じテーブルに対応した場合は、それらだけを外す ( 外したテーブルのカラムも対応させない )
+    let table_rules = RenameRules::without_conflicts(table_rules);
+    let mut rules = vec![];
+    for (table_snapshot1, table_snapshot2) in pairs {
+        if table_rules.new_table_name(&table_snapshot1.table_name) != table_snapshot2.table_name {
+            continue;
+        }
+        if !table_snapshot1.is_keyless() && !table_snapshot2.is_keyless() {
+            rules.extend(detect_col_renames(table_snapshot1, table_snapshot2));
+        }
+    }
+
+    // カラムも同じく、複数のカラムが同じカラムに対応した場合は、それらだけを外す
+    rules.extend(table_rules.rules);
+    RenameRules::without_conflicts(rules)
+}
+
+// 主キーの値の集合が同じで、ほとんどの行の内容が一致するテーブル ( 主キーのないテーブルは、すべての行が一致するテーブル )
+fn is_renamed_table(table_snapshot1: &TableSnapshot, table_snapshot2: &TableSnapshot) -> bool {
+    if table_snapshot1.primary_col_name != table_snapshot2.primary_col_name
+        || table_snapshot1.row_snapshots.is_empty()
+        || table_snapshot1.row_snapshots.len() != table_snapshot2.row_snapshots.len()
+    {
+        return false;
+    }
+
+    if table_snapshot1.is_keyless() {
+        let hashes =
+            |table_snapshot: &TableSnapshot| table_snapshot.row_snapshots.iter().map(|row_snapshot| row_snapshot.hash.clone()).sorted().collect_vec();
+        return hashes(table_snapshot1) == hashes(table_snapshot2);
+    }
+
+    let hashes2: HashMap<&PrimaryColValues, &Hash> =
+        table_snapshot2.row_snapshots.iter().map(|row_snapshot| (&row_snapshot.primary_col_values, &row_snapshot.hash)).collect();
+
+    let mut same = 0;
+    for row_snapshot1 in &table_snapshot1.row_snapshots {
+        match hashes2.get(&row_snapshot1.primary_col_values) {
+            Some(&hash2) if hash2 == &row_snapshot1.hash => same += 1,
+            Some(_) => {}
+            None => return false,
+        }
+    }
+    same as f64 >= table_snapshot1.row_snapshots.len() as f64 * SIMILARITY
+}
+
+// 両方にある行で、ほとんどの値が一致するカラム ( 値の一致が同数の候補が複数ある場合は、判断できないので対応させない )
+// 1 つのカラムに複数のカラムが対応した場合は、呼び出し元で外す
+// 両方が NULL の行は一致の根拠にならないので数えない ( すべて NULL のカラム同士は対応させない )
+fn detect_col_renames(table_snapshot1: &TableSnapshot, table_snapshot2: &TableSnapshot) -> Vec<RenameRule> {
+    let col_names1 = table_snapshot1.col_names.iter().enumerate().filter(|(_, col_name)| !table_snapshot2.col_names.contains(col_name)).collect_vec();
+    let col_names2 = table_snapshot2.col_names.iter().enumerate().filter(|(_, col_name)| !table_snapshot1.col_names.contains(col_name)).collect_vec();
+    if col_names1.is_empty() || col_names2.is_empty() {
+        return vec![];
+    }
+
+    let rows2: HashMap<&PrimaryColValues, &Vec<ColValue>> =
+        table_snapshot2.row_snapshots.iter().map(|row_snapshot| (&row_snapshot.primary_col_values, &row_snapshot.col_values)).collect();
+    let rows = table_snapshot1
+        .row_snapshots
+        .iter()
+        .filter_map(|row_snapshot1| rows2.get(&row_snapshot1.primary_col_values).map(|&col_values2| (&row_snapshot1.col_values, col_values2)))
+        .collect_vec();
+    if rows.is_empty() {
+        return vec![];
+    }
+
+    let mut rules = vec![];
+
+    for (i, col_name1) in col_names1 {
+        let scores = col_names2
+            .iter()
+            .map(|&(j, col_name2)| {
+                let values =
+                    rows.iter().map(|(col_values1, col_values2)| (&col_values1[i], &col_values2[j])).filter(|values| values != &(&Null, &Null));
+                let (count, same) = values.fold((0, 0), |(count, same), (value1, value2)| (count + 1, same + value1.is_same_value(value2) as usize));
+                (col_name2, count, same)
+            })
+            .collect_vec();
+        let best = scores.iter().map(|(_, _, same)| *same).max().unwrap_or_default();
+        let candidates = scores.iter().filter(|(_, _, same)| *same == best).collect_vec();
+
+        if let [&(col_name2, count, _)] = candidates[..] {
+            if count != 0 && best as f64 >= count as f64 * SIMILARITY {
+                rules.push(RenameRule {
+                    table_name: table_snapshot1.table_name.clone(),
+                    col_name: Some(col_name1.clone()),
+                    new_name: col_name2.clone(),
+                });
+            }
+        }
+    }
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::domain::rename::{detect_renames, RenameRules};
+    use crate::domain::snapshot::ColValue::{Null, SimpleNumber, SimpleString};
+    use crate::domain::snapshot::{ColValue, RowSnapshot, TableSnapshot};
+
+    fn n(s: &str) -> ColValue {
+        SimpleNumber(s.to_string())
+    }
+
+    fn s(s: &str) -> ColValue {
+        SimpleString(s.to_string())
+    }
+
+    fn mk(table_name: &str, col_names: Vec<&str>, rows: Vec<Vec<ColValue>>) -> TableSnapshot {
+        let row_snapshots = rows.into_iter().enumerate().map(|(i, col_values)| RowSnapshot::new(vec![n(&(i + 1).to_string())], col_values)).collect();
+        TableSnapshot::new(&table_name.to_string(), "id".to_string(), col_names.into_iter().map(String::from).collect(), row_snapshots)
+    }
+
+    #[test]
+    fn parse() {
+        let act = RenameRules::parse(&["users.name=full_name".to_string(), " members = users ".to_string(), "".to_string()]).unwrap();
+        assert_eq!(vec!["members=users", "users.name=full_name"], act.as_strings());
+
+        assert_eq!(
+            "invalid rename, specify as table=new_table or table.column=new_column: users.name",
+            RenameRules::parse(&["users.name".to_string()]).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "invalid rename, specify as table=new_table or table.column=new_column: .name=x",
+            RenameRules::parse(&[".name=x".to_string()]).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "renamed more than once: users.name",
+            RenameRules::parse(&["users.name=a".to_string(), "users.name=b".to_string()]).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "renamed to the same name more than once: users",
+            RenameRules::parse(&["members=users".to_string(), "accounts=users".to_string()]).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn apply() {
+        let renames = RenameRules::parse(&["members=users".to_string(), "members.name=full_name".to_string()]).unwrap();
+        let table_snapshots1 = vec![mk("members", vec!["name", "age"], vec![]), mk("users", vec!["name"], vec![]), mk("items", vec!["name"], vec![])];
+
+        let act = renames.apply(table_snapshots1);
+
+        // 読み替えた名前と同じ名前のテーブルは比較しない
+        assert_eq!(
+            vec![("users", vec!["full_name", "age"]), ("items", vec!["name"])],
+            act.iter()
+                .map(|table_snapshot| (table_snapshot.table_name.as_str(), table_snapshot.col_names.iter().map(String::as_str).collect_vec()))
+                .collect_vec()
+        );
+
+        assert_eq!(Some("members".to_string()), renames.old_table_name(&"users".to_string()));
+        assert_eq!(Some(&"name".to_string()), renames.old_col_names(&"users".to_string()).get("full_name"));
+        assert!(renames.old_col_names(&"items".to_string()).is_empty());
+    }
+
+    #[test]
+    fn detect() {
+        let table_snapshots1 = vec![
+            mk(
+                "users",
+                vec!["name", "age", "memo", "deleted_at"],
+                vec![vec![s("John"), n("20"), s("x"), Null], vec![s("Jane"), n("30"), s("x"), Null]],
+            ),
+            mk("members", vec!["code"], vec![vec![s("a")], vec![s("b")]]),
+            mk("logs", vec!["message"], vec![vec![s("x")]]),
+        ];
+        let table_snapshots2 = vec![
+            mk(
+                "users",
+                vec!["full_name", "age", "note", "remark", "archived_at"],
+                vec![vec![s("John"), n("21"), s("x"), s("x"), Null], vec![s("Jane"), n("30"), s("x"), s("x"), Null]],
+            ),
+            mk("app_members", vec!["code"], vec![vec![s("a")], vec![s("b")]]),
+            mk("events", vec!["message"], vec![vec![s("y")]]),
+        ];
+
+        let act = detect_renames(&table_snapshots1, &table_snapshots2);
+
+        // memo は note と remark のどちらとも値が一致するので対応させない、すべて NULL の deleted_at と archived_at は対応させない
+        // logs は内容が異なるので対応させない
+        assert_eq!(vec!["members=app_members", "users.name=full_name"], act.as_strings());
+    }
+
+    #[test]
+    fn detect_conflicts() {
+        let table_snapshots1 = vec![
+            mk("members", vec!["code"], vec![vec![s("a")], vec![s("b")]]),
+            mk("members_copy", vec!["code"], vec![vec![s("a")], vec![s("b")]]),
+            mk("users", vec!["name", "nickname", "age"], vec![vec![s("John"), s("John"), n("20")], vec![s("Jane"), s("Jane"), n("30")]]),
+        ];
+        let table_snapshots2 = vec![
+            mk("app_members", vec!["code"], vec![vec![s("a")], vec![s("b")]]),
+            mk("users", vec!["full_name", "years"], vec![vec![s("John"), n("20")], vec![s("Jane"), n("30")]]),
+        ];
+
+        let act = detect_renames(&table_snapshots1, &table_snapshots2);
+
+        // 2 つのテーブルと 2 つのカラムが同じものに対応するので、それらだけを外す
+        assert_eq!(vec!["users.age=years"], act.as_strings());
+    }
+}
@@ -1,3 +1,4 @@
+use anyhow::anyhow;
 use itertools::Itertools;
 
 pub type TableName = String;
@@ -37,6 +38,33 @@ impl TableSchema {
         cols
     }
 
+    // 指定したカラムを主キーとして扱う ( 元の主キーは通常のカラムとして扱う )
+    pub fn with_key_cols(self, key_col_names: &[ColName]) -> anyhow::Result<Self> {
+        if key_col_names.is_empty() {
+            return Ok(self);
+        }
+
+        let all_cols = self.primary_cols.into_iter().chain(self.cols).collect_vec();
+
+        let primary_cols = key_col_names
+            .iter()
+            .map(|key_col_name| {
+                all_cols
+                    .iter()
+                    .find(|col| &col.col_name == key_col_name)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("key column not found: {}.{}", self.table_name, key_col_name))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let cols = all_cols.into_iter().filter(|col| !key_col_names.contains(&col.col_name)).collect();
+
+        Ok(Self { table_name: self.table_name, primary_cols, cols })
+    }
+
+    pub fn exclude_cols(self, col_names: &[ColName]) -> Self {
+        Self { cols: self.cols.into_iter().filter(|col| !col_names.contains(&col.col_name)).collect(), ..self }
+    }
+
     pub fn get_col_indices(&self) -> Vec<(usize, bool)> {
         let mut result = vec![];
         for (i, _) in self.primary_cols.iter().enumerate() {
@@ -54,3 +82,36 @@ pub struct ColSchema {
     pub col_name: ColName,
     pub data_type: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::schema::{ColSchema, TableSchema};
+
+    fn col(col_name: &str) -> ColSchema {
+        ColSchema { col_name: col_name.to_string(), data_type: "int".to_string() }
+    }
+
+    fn col_names(cols: &[ColSchema]) -> Vec<&str> {
+        cols.iter().map(|col| col.col_name.as_str()).collect()
+    }
+
+    #[test]
+    fn with_key_cols() -> anyhow::Result<()> {
+        let sut = TableSchema::new(&"user_groups".to_string(), &[], &[col("user_id"), col("group_id"), col("note")]);
+        let sut = sut.with_key_cols(&["group_id".to_string(), "user_id".to_string()])?;
+
+        assert_eq!(vec!["group_id", "user_id"], col_names(&sut.primary_cols));
+        assert_eq!(vec!["note"], col_names(&sut.cols));
+
+        let sut = TableSchema::new(&"users".to_string(), &[col("id")], &[col("code"), col("name")]);
+        let sut = sut.with_key_cols(&["code".to_string()])?;
+
+        assert_eq!(vec!["code"], col_names(&sut.primary_cols));
+        assert_eq!(vec!["id", "name"], col_names(&sut.cols));
+
+        let sut = TableSchema::new(&"users".to_string(), &[col("id")], &[col("name")]);
+        assert!(sut.with_key_cols(&["code".to_string()]).is_err());
+
+        Ok(())
+    }
+}
@@ -5,6 +5,7 @@ use chrono::Local;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
+use crate::domain::json_diff::canonicalize_json;
 use crate::domain::schema::{ColName, Hash, PrimaryColName, PrimaryValue, TableName};
 use crate::domain::snapshot::ColValue::*;
 
@@ -12,7 +13,9 @@ pub type SnapshotId = String;
 
 pub type SnapshotName = String;
 
-#[cfg(test)]
+// 行数上限を設定できるようになる前は、常にこの行数で dump を打ち切っていた
+const LEGACY_ROW_LIMIT: usize = 1000;
+
 pub fn create_snapshot_id() -> SnapshotId {
     uuid::Uuid::new_v4().to_string()
 }
@@ -35,6 +38,15 @@ impl SnapshotSummary {
     }
 }
 
+// 一覧を記録する前のスナップショットと差分は、- でつないだ名前から分ける
+pub fn get_primary_col_names(primary_col_name: &PrimaryColName, primary_col_names: &[ColName]) -> Vec<ColName> {
+    if primary_col_names.is_empty() && !primary_col_name.is_empty() {
+        primary_col_name.split('-').map(String::from).collect()
+    } else {
+        primary_col_names.to_vec()
+    }
+}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
 pub struct TableSnapshot {
     pub table_name: TableName,
@@ -42,13 +54,58 @@ pub struct TableSnapshot {
     pub col_names: Vec<ColName>,
     pub hash: Hash,
     pub row_snapshots: Vec<RowSnapshot>,
+    // 打ち切ったかを記録する前のスナップショットでは None になる
+    #[serde(default)]
+    pub truncated: Option<bool>,
+    #[serde(default)]
+    pub excluded_col_names: Vec<ColName>,
+    #[serde(default)]
+    pub primary_col_names: Vec<ColName>,
 }
 
 impl TableSnapshot {
     pub fn new(table_name: &TableName, primary_col_name: PrimaryColName, col_names: Vec<ColName>, row_snapshots: Vec<RowSnapshot>) -> Self {
         let row_hashes = row_snapshots.iter().map(|row_snapshot| &row_snapshot.hash).join("");
         let hash = format!("{:?}", md5::compute(format!("{}{}{}", primary_col_name, col_names.join(""), row_hashes)));
-        Self { table_name: table_name.clone(), primary_col_name, col_names, hash, row_snapshots }
+        Self {
+            table_name: table_name.clone(),
+            primary_col_name,
+            col_names,
+            hash,
+            row_snapshots,
+            truncated: Some(false),
+            excluded_col_names: vec![],
+            primary_col_names: vec![],
+        }
+    }
+
+    // 行数上限により dump を打ち切ったか
+    pub fn with_truncated(self, truncated: bool) -> Self {
+        Self { truncated: Some(truncated), ..self }
+    }
+
+    // 打ち切ったかを記録する前のスナップショットは、当時の上限の行数があれば打ち切った可能性があるとみなす
+    pub fn is_truncated(&self) -> bool {
+        self.truncated.unwrap_or(self.row_snapshots.len() >= LEGACY_ROW_LIMIT)
+    }
+
+    // dump 設定により取得しなかったカラム
+    pub fn with_excluded_col_names(self, excluded_col_names: Vec<ColName>) -> Self {
+        Self { excluded_col_names, ..self }
+    }
+
+    // - を含むカラム名があっても分けられるように、主キーのカラム名を一覧でも記録する
+    pub fn with_primary_col_names(self, primary_col_names: Vec<ColName>) -> Self {
+        Self { primary_col_names, ..self }
+    }
+
+    pub fn get_primary_col_names(&self) -> Vec<ColName> {
+        get_primary_col_names(&self.primary_col_name, &self.primary_col_names)
+    }
+
+    // 主キーのないテーブルを多重集合として dump したか
+    pub fn is_keyless(&self) -> bool {
+        self.primary_col_name.is_empty()
     }
 
     pub fn merge_primary_col_values<'a>(&'a self, other: &'a Self) -> Vec<&'a PrimaryColValues> {
@@ -140,6 +197,22 @@ impl ColValue {
         }
     }
 
+    // JSON はキーの順序や空白が異なっても、同じ値とみなす
+    pub fn is_same_value(&self, other: &ColValue) -> bool {
+        match (self, other) {
+            (JsonString(v1), JsonString(v2)) => v1 == v2 || canonicalize_json(v1) == canonicalize_json(v2),
+            _ => self == other,
+        }
+    }
+
+    // 主キーのないテーブルで、同じ値の行を数えるときに使う
+    pub fn as_canonical(&self) -> ColValue {
+        match self {
+            JsonString(v) => JsonString(canonicalize_json(v)),
+            _ => self.clone(),
+        }
+    }
+
     fn as_hash_parts(&self) -> String {
         match self {
             SimpleNumber(v) => v.to_string(),
@@ -147,9 +220,43 @@ impl ColValue {
             SimpleString(v) => v.to_string(),
             DateString(v) => v.to_string(),
             BinaryString(v) => format!("{:?}", md5::compute(v)),
-            JsonString(v) => v.to_string(),
+            JsonString(v) => canonicalize_json(v),
             Null => format!("{:?}", md5::compute("<null>")),
             ParseError => "parse error".to_string(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::domain::snapshot::TableSnapshot;
+
+    fn baseline_json(row_count: usize) -> String {
+        let rows = (0..row_count)
+            .map(|i| format!(r#"{{"primary_col_values":{{"col_values":[{{"SimpleNumber":"{i}"}}]}},"col_values":[],"hash":"{i}"}}"#))
+            .join(",");
+        format!(r#"{{"table_name":"users","primary_col_name":"id","col_names":["id"],"hash":"hash","row_snapshots":[{rows}]}}"#)
+    }
+
+    #[test]
+    fn is_truncated() {
+        // 打ち切ったかを記録する前のスナップショットは、上限の行数があれば打ち切った可能性がある
+        let act: TableSnapshot = serde_json::from_str(&baseline_json(1000)).unwrap();
+        assert_eq!(None, act.truncated);
+        assert!(act.is_truncated());
+
+        let act: TableSnapshot = serde_json::from_str(&baseline_json(999)).unwrap();
+        assert!(!act.is_truncated());
+
+        // 記録したスナップショットは、行数によらず記録のとおり
+        let act: TableSnapshot =
+            serde_json::from_str(&baseline_json(1000).replace(r#""row_snapshots""#, r#""truncated":false,"row_snapshots""#)).unwrap();
+        assert!(!act.is_truncated());
+
+        let act: TableSnapshot =
+            serde_json::from_str(&baseline_json(1).replace(r#""row_snapshots""#, r#""truncated":true,"row_snapshots""#)).unwrap();
+        assert!(act.is_truncated());
+    }
+}
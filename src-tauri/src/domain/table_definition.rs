@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::schema::{ColName, TableName};
+
+// 行の比較に使う TableSchema とは別に、スキーマの変更を比較するためにテーブルの定義をすべて記録する
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct TableDefinition {
+    pub table_name: TableName,
+    pub cols: Vec<ColDefinition>,
+    pub indexes: Vec<IndexDefinition>,
+    pub foreign_keys: Vec<ForeignKeyDefinition>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ColDefinition {
+    pub col_name: ColName,
+    pub col_type: String,
+    pub nullable: bool,
+    pub default: Option<String>,
+    pub charset: Option<String>,
+    pub collation: Option<String>,
+}
+
+impl ColDefinition {
+    pub fn new(col_name: &str, col_type: &str, nullable: bool) -> Self {
+        Self { col_name: col_name.to_string(), col_type: col_type.to_string(), nullable, default: None, charset: None, collation: None }
+    }
+
+    pub fn with_default(self, default: Option<String>) -> Self {
+        Self { default, ..self }
+    }
+
+    pub fn with_collation(self, charset: Option<String>, collation: Option<String>) -> Self {
+        Self { charset, collation, ..self }
+    }
+
+    // 例: varchar(255) not null default 'guest' charset utf8mb4 collate utf8mb4_bin
+    pub fn as_ddl(&self) -> String {
+        let mut ddl = vec![self.col_type.clone()];
+        if !self.nullable {
+            ddl.push("not null".to_string());
+        }
+        if let Some(default) = &self.default {
+            ddl.push(format!("default {default}"));
+        }
+        if let Some(charset) = &self.charset {
+            ddl.push(format!("charset {charset}"));
+        }
+        if let Some(collation) = &self.collation {
+            ddl.push(format!("collate {collation}"));
+        }
+        ddl.join(" ")
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct IndexDefinition {
+    pub index_name: String,
+    pub col_names: Vec<ColName>,
+    pub primary: bool,
+    pub unique: bool,
+}
+
+impl IndexDefinition {
+    pub fn as_ddl(&self) -> String {
+        let kind = if self.primary {
+            "primary key"
+        } else if self.unique {
+            "unique"
+        } else {
+            "index"
+        };
+        format!("{} ({})", kind, self.col_names.join(", "))
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ForeignKeyDefinition {
+    pub constraint_name: String,
+    pub col_names: Vec<ColName>,
+    pub ref_table_name: TableName,
+    pub ref_col_names: Vec<ColName>,
+}
+
+impl ForeignKeyDefinition {
+    pub fn as_ddl(&self) -> String {
+        format!("foreign key ({}) references {} ({})", self.col_names.join(", "), self.ref_table_name, self.ref_col_names.join(", "))
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum SchemaObject {
+    Table,
+    Col,
+    Index,
+    ForeignKey,
+}
+
+impl SchemaObject {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SchemaObject::Table => "table",
+            SchemaObject::Col => "column",
+            SchemaObject::Index => "index",
+            SchemaObject::ForeignKey => "foreign key",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum SchemaStatus {
+    Added,
+    Dropped,
+    Altered,
+}
+
+impl SchemaStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SchemaStatus::Added => "added",
+            SchemaStatus::Dropped => "dropped",
+            SchemaStatus::Altered => "altered",
+        }
+    }
+}
+
+// テーブル、カラム、インデックス、外部キーの単位で、追加・削除・変更を 1 つずつ記録する ( 定義は DDL の断片で持つ )
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct SchemaChange {
+    pub table_name: TableName,
+    pub object: SchemaObject,
+    pub name: String,
+    pub status: SchemaStatus,
+    pub definition1: Option<String>,
+    pub definition2: Option<String>,
+}
+
+impl SchemaChange {
+    fn new(table_name: &TableName, object: SchemaObject, name: &str, definition1: Option<String>, definition2: Option<String>) -> Self {
+        let status = match (&definition1, &definition2) {
+            (None, Some(_)) => SchemaStatus::Added,
+            (Some(_), None) => SchemaStatus::Dropped,
+            _ => SchemaStatus::Altered,
+        };
+        Self { table_name: table_name.clone(), object, name: name.to_string(), status, definition1, definition2 }
+    }
+}
+
+// 追加・削除されたテーブルはテーブル単位で、両方にあるテーブルはカラムなどの単位で比較する
+pub fn create_schema_changes(table_definitions1: Option<&[TableDefinition]>, table_definitions2: Option<&[TableDefinition]>) -> Vec<SchemaChange> {
+    // テーブルの定義を記録する前に作成したスナップショットとは比較しない ( すべてのテーブルが追加されたように見えてしまう )
+    let (table_definitions1, table_definitions2) = match (table_definitions1, table_definitions2) {
+        (Some(table_definitions1), Some(table_definitions2)) => (table_definitions1, table_definitions2),
+        _ => return vec![],
+    };
+
+    let table_definitions1: HashMap<&TableName, &TableDefinition> =
+        table_definitions1.iter().map(|definition| (&definition.table_name, definition)).collect();
+    let table_definitions2: HashMap<&TableName, &TableDefinition> =
+        table_definitions2.iter().map(|definition| (&definition.table_name, definition)).collect();
+
+    let table_names = table_definitions1.keys().chain(table_definitions2.keys()).unique().sorted().collect_vec();
+
+    let mut schema_changes = vec![];
+    for table_name in table_names {
+        match (table_definitions1.get(table_name), table_definitions2.get(table_name)) {
+            (Some(definition1), Some(definition2)) => {
+                let cols1 = definition1.cols.iter().map(|col| (col.col_name.clone(), col.as_ddl())).collect_vec();
+                let cols2 = definition2.cols.iter().map(|col| (col.col_name.clone(), col.as_ddl())).collect_vec();
+                schema_changes.extend(compare(table_name, SchemaObject::Col, cols1, cols2));
+
+                let indexes1 = definition1.indexes.iter().map(|index| (index.index_name.clone(), index.as_ddl())).collect_vec();
+                let indexes2 = definition2.indexes.iter().map(|index| (index.index_name.clone(), index.as_ddl())).collect_vec();
+                schema_changes.extend(compare(table_name, SchemaObject::Index, indexes1, indexes2));
+
+                let foreign_keys1 =
+                    definition1.foreign_keys.iter().map(|foreign_key| (foreign_key.constraint_name.clone(), foreign_key.as_ddl())).collect_vec();
+                let foreign_keys2 =
+                    definition2.foreign_keys.iter().map(|foreign_key| (foreign_key.constraint_name.clone(), foreign_key.as_ddl())).collect_vec();
+                schema_changes.extend(compare(table_name, SchemaObject::ForeignKey, foreign_keys1, foreign_keys2));
+            }
+            (definition1, definition2) => {
+                let as_ddl =
+                    |definition: &&TableDefinition| definition.cols.iter().map(|col| format!("{} {}", col.col_name, col.as_ddl())).join(", ");
+                schema_changes.push(SchemaChange::new(table_name, SchemaObject::Table, table_name, definition1.map(as_ddl), definition2.map(as_ddl)));
+            }
+        }
+    }
+    schema_changes
+}
+
+// 名前で対応させ、定義が異なるものだけを残す ( カラムは定義の順、それ以外は名前順に並べる )
+fn compare(
+    table_name: &TableName,
+    object: SchemaObject,
+    definitions1: Vec<(String, String)>,
+    definitions2: Vec<(String, String)>,
+) -> Vec<SchemaChange> {
+    let map1: HashMap<&String, &String> = definitions1.iter().map(|(name, ddl)| (name, ddl)).collect();
+    let map2: HashMap<&String, &String> = definitions2.iter().map(|(name, ddl)| (name, ddl)).collect();
+
+    let mut names = definitions2.iter().chain(definitions1.iter()).map(|(name, _)| name).unique().collect_vec();
+    if object != SchemaObject::Col {
+        names.sort();
+    }
+
+    names
+        .into_iter()
+        .filter(|name| map1.get(name) != map2.get(name))
+        .map(|name| {
+            SchemaChange::new(table_name, object.clone(), name, map1.get(name).map(|ddl| ddl.to_string()), map2.get(name).map(|ddl| ddl.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::table_definition::SchemaObject::{Col, ForeignKey, Index, Table};
+    use crate::domain::table_definition::SchemaStatus::{Added, Altered, Dropped};
+    use crate::domain::table_definition::{create_schema_changes, ColDefinition, ForeignKeyDefinition, IndexDefinition, TableDefinition};
+
+    fn index(index_name: &str, col_names: Vec<&str>, primary: bool, unique: bool) -> IndexDefinition {
+        IndexDefinition { index_name: index_name.to_string(), col_names: col_names.into_iter().map(String::from).collect(), primary, unique }
+    }
+
+    fn users(cols: Vec<ColDefinition>, indexes: Vec<IndexDefinition>, foreign_keys: Vec<ForeignKeyDefinition>) -> TableDefinition {
+        TableDefinition { table_name: "users".to_string(), cols, indexes, foreign_keys }
+    }
+
+    #[test]
+    fn as_ddl() {
+        let col = ColDefinition::new("name", "varchar(255)", false)
+            .with_default(Some("'guest'".to_string()))
+            .with_collation(Some("utf8mb4".to_string()), Some("utf8mb4_bin".to_string()));
+        assert_eq!("varchar(255) not null default 'guest' charset utf8mb4 collate utf8mb4_bin", col.as_ddl());
+        assert_eq!("int", ColDefinition::new("age", "int", true).as_ddl());
+
+        assert_eq!("primary key (id)", index("PRIMARY", vec!["id"], true, true).as_ddl());
+        assert_eq!("unique (tenant_id, code)", index("uq_code", vec!["tenant_id", "code"], false, true).as_ddl());
+        assert_eq!("index (name)", index("idx_name", vec!["name"], false, false).as_ddl());
+
+        let foreign_key = ForeignKeyDefinition {
+            constraint_name: "fk_group".to_string(),
+            col_names: vec!["group_id".to_string()],
+            ref_table_name: "groups".to_string(),
+            ref_col_names: vec!["id".to_string()],
+        };
+        assert_eq!("foreign key (group_id) references groups (id)", foreign_key.as_ddl());
+    }
+
+    #[test]
+    fn schema_changes() {
+        let id = ColDefinition::new("id", "int", false);
+        let foreign_key = ForeignKeyDefinition {
+            constraint_name: "fk_group".to_string(),
+            col_names: vec!["group_id".to_string()],
+            ref_table_name: "groups".to_string(),
+            ref_col_names: vec!["id".to_string()],
+        };
+
+        let definitions1 = vec![
+            users(
+                vec![id.clone(), ColDefinition::new("name", "varchar(100)", false), ColDefinition::new("group_id", "int", true)],
+                vec![index("PRIMARY", vec!["id"], true, true), index("idx_name", vec!["name"], false, false)],
+                vec![foreign_key.clone()],
+            ),
+            TableDefinition { table_name: "logs".to_string(), cols: vec![id.clone()], indexes: vec![], foreign_keys: vec![] },
+        ];
+        let definitions2 = vec![
+            users(
+                vec![id.clone(), ColDefinition::new("name", "varchar(255)", false), ColDefinition::new("email", "varchar(255)", true)],
+                vec![index("PRIMARY", vec!["id"], true, true), index("idx_name", vec!["name"], false, true)],
+                vec![],
+            ),
+            TableDefinition { table_name: "groups".to_string(), cols: vec![id.clone()], indexes: vec![], foreign_keys: vec![] },
+        ];
+
+        let act = create_schema_changes(Some(&definitions1), Some(&definitions2));
+        let act = act.iter().map(|change| (change.table_name.as_str(), &change.object, change.name.as_str(), &change.status)).collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                ("groups", &Table, "groups", &Added),
+                ("logs", &Table, "logs", &Dropped),
+                ("users", &Col, "name", &Altered),
+                ("users", &Col, "email", &Added),
+                ("users", &Col, "group_id", &Dropped),
+                ("users", &Index, "idx_name", &Altered),
+                ("users", &ForeignKey, "fk_group", &Dropped),
+            ],
+            act
+        );
+
+        let act = create_schema_changes(Some(&definitions1), Some(&definitions2));
+        assert_eq!(
+            (Some("varchar(100) not null".to_string()), Some("varchar(255) not null".to_string())),
+            (act[2].definition1.clone(), act[2].definition2.clone())
+        );
+        assert_eq!(Some("id int not null".to_string()), act[0].definition2);
+
+        assert!(create_schema_changes(Some(&definitions1), Some(&definitions1)).is_empty());
+        assert!(create_schema_changes(None, Some(&definitions2)).is_empty());
+        assert_eq!(2, create_schema_changes(Some(&[]), Some(&definitions2)).len());
+    }
+}
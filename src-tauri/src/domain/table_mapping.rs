@@ -0,0 +1,137 @@
+use anyhow::anyhow;
+use itertools::Itertools;
+
+use crate::domain::schema::TableName;
+use crate::domain::snapshot::TableSnapshot;
+use crate::domain::table_definition::TableDefinition;
+
+// スナップショット 1 のテーブルと、スナップショット 2 の名前の異なるテーブルを対応させる ( 例: users=app_users )
+#[derive(Eq, PartialEq, Debug)]
+pub struct TableMapping {
+    table_name1: TableName,
+    table_name2: TableName,
+}
+
+impl TableMapping {
+    pub fn parse(mapping: &str) -> anyhow::Result<Self> {
+        match mapping.trim().split_once('=') {
+            Some((table_name1, table_name2)) if !table_name1.trim().is_empty() && !table_name2.trim().is_empty() => {
+                Ok(Self { table_name1: table_name1.trim().to_string(), table_name2: table_name2.trim().to_string() })
+            }
+            _ => Err(anyhow!("invalid table mapping, specify as table1=table2: {}", mapping)),
+        }
+    }
+
+    fn as_string(&self) -> String {
+        format!("{}={}", self.table_name1, self.table_name2)
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Default)]
+pub struct TableMappings {
+    mappings: Vec<TableMapping>,
+}
+
+impl TableMappings {
+    pub fn parse(mappings: &[String]) -> anyhow::Result<Self> {
+        let mappings = mappings
+            .iter()
+            .map(|mapping| mapping.trim())
+            .filter(|mapping| !mapping.is_empty())
+            .sorted()
+            .dedup()
+            .map(TableMapping::parse)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        // 1 つのテーブルを複数のテーブルに対応させることはできない
+        let duplicates = mappings
+            .iter()
+            .map(|mapping| &mapping.table_name1)
+            .duplicates()
+            .chain(mappings.iter().map(|mapping| &mapping.table_name2).duplicates())
+            .join(", ");
+        if !duplicates.is_empty() {
+            return Err(anyhow!("table mapped more than once: {}", duplicates));
+        }
+
+        Ok(Self { mappings })
+    }
+
+    pub fn as_strings(&self) -> Vec<String> {
+        self.mappings.iter().map(TableMapping::as_string).collect()
+    }
+
+    // スナップショット 2 のテーブル名を、比較するスナップショット 1 のテーブル名に読み替える
+    pub fn rename(&self, table_name2: &TableName) -> TableName {
+        self.mappings.iter().find(|mapping| &mapping.table_name2 == table_name2).map_or(table_name2.clone(), |mapping| mapping.table_name1.clone())
+    }
+
+    // 対応させたテーブルと同じ名前のテーブルがスナップショット 2 にもある場合は、比較の対象から外す
+    pub fn apply(&self, table_snapshots2: Vec<TableSnapshot>) -> Vec<TableSnapshot> {
+        self.apply_by(
+            table_snapshots2,
+            |table_snapshot| &table_snapshot.table_name,
+            |table_snapshot, table_name| TableSnapshot { table_name, ..table_snapshot },
+        )
+    }
+
+    // テーブルの定義も、行と同じようにテーブル名を読み替える
+    pub fn apply_definitions(&self, table_definitions2: Vec<TableDefinition>) -> Vec<TableDefinition> {
+        self.apply_by(
+            table_definitions2,
+            |table_definition| &table_definition.table_name,
+            |table_definition, table_name| TableDefinition { table_name, ..table_definition },
+        )
+    }
+
+    fn apply_by<T>(&self, items: Vec<T>, table_name: fn(&T) -> &TableName, with_table_name: fn(T, TableName) -> T) -> Vec<T> {
+        items
+            .into_iter()
+            .filter(|item| {
+                let mapped = self.mappings.iter().any(|mapping| &mapping.table_name2 == table_name(item));
+                let shadowed = self.mappings.iter().any(|mapping| &mapping.table_name1 == table_name(item));
+                mapped || !shadowed
+            })
+            .map(|item| {
+                let renamed = self.rename(table_name(&item));
+                with_table_name(item, renamed)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::domain::snapshot::TableSnapshot;
+    use crate::domain::table_mapping::{TableMapping, TableMappings};
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            TableMapping { table_name1: "users".to_string(), table_name2: "app_users".to_string() },
+            TableMapping::parse(" users = app_users ").unwrap()
+        );
+        assert_eq!("invalid table mapping, specify as table1=table2: users", TableMapping::parse("users").unwrap_err().to_string());
+        assert_eq!("invalid table mapping, specify as table1=table2: =users", TableMapping::parse("=users").unwrap_err().to_string());
+
+        let act = TableMappings::parse(&["users=app_users".to_string(), "".to_string(), "items=app_items".to_string()]).unwrap();
+        assert_eq!(vec!["items=app_items", "users=app_users"], act.as_strings());
+
+        let act = TableMappings::parse(&["users=app_users".to_string(), "members=app_users".to_string()]).unwrap_err();
+        assert_eq!("table mapped more than once: app_users", act.to_string());
+    }
+
+    #[test]
+    fn apply() {
+        let mk = |table_name: &str| TableSnapshot::new(&table_name.to_string(), "id".to_string(), vec![], vec![]);
+        let sut = TableMappings::parse(&["users=app_users".to_string()]).unwrap();
+
+        assert_eq!("users", sut.rename(&"app_users".to_string()));
+        assert_eq!("items", sut.rename(&"items".to_string()));
+
+        let act = sut.apply(vec![mk("app_users"), mk("users"), mk("items")]);
+        assert_eq!(vec!["users", "items"], act.iter().map(|table_snapshot| &table_snapshot.table_name).collect_vec());
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::domain::schema::{ColName, PrimaryColName, PrimaryValue, TableName};
+use crate::domain::snapshot::{ColValue, PrimaryColValues, SnapshotSummary, TableSnapshot};
+
+#[derive(Eq, PartialEq, Debug)]
+pub enum RowState {
+    Present,
+    Absent,
+    // テーブルを dump していないか、上限で打ち切られていて行があるかわからない
+    Unknown,
+}
+
+#[derive(Eq, PartialEq, Debug)]
+pub struct RowPoint {
+    pub snapshot_summary: SnapshotSummary,
+    pub state: RowState,
+    pub col_values: HashMap<ColName, ColValue>,
+    pub changed_col_names: Vec<ColName>,
+}
+
+#[derive(Eq, PartialEq, Debug)]
+pub struct RowTimeline {
+    pub table_name: TableName,
+    pub primary_col_name: PrimaryColName,
+    pub primary_value: PrimaryValue,
+    pub col_names: Vec<ColName>,
+    pub points: Vec<RowPoint>,
+}
+
+// 1 つの行を、スナップショットの作成順に並べ、直前に行があったスナップショットから変更されたカラムを記録する
+pub fn create_row_timeline(table_name: &TableName, primary_value: &str, snapshots: Vec<(SnapshotSummary, Option<TableSnapshot>)>) -> RowTimeline {
+    let snapshots = snapshots.into_iter().sorted_by(|(s1, _), (s2, _)| s1.create_at.cmp(&s2.create_at)).collect_vec();
+
+    let mut primary_col_name = String::new();
+    let mut col_names: Vec<ColName> = vec![];
+    let mut points: Vec<RowPoint> = vec![];
+
+    for (snapshot_summary, table_snapshot) in snapshots {
+        let (state, col_values) = match &table_snapshot {
+            Some(table_snapshot) => {
+                primary_col_name = table_snapshot.primary_col_name.clone();
+                for col_name in &table_snapshot.col_names {
+                    if !col_names.contains(col_name) {
+                        col_names.push(col_name.clone());
+                    }
+                }
+
+                match table_snapshot.row_snapshots.iter().find(|row_snapshot| matches(&row_snapshot.primary_col_values, primary_value)) {
+                    Some(row_snapshot) => {
+                        (RowState::Present, table_snapshot.col_names.iter().cloned().zip(row_snapshot.col_values.iter().cloned()).collect())
+                    }
+                    None if table_snapshot.is_truncated() => (RowState::Unknown, HashMap::new()),
+                    None => (RowState::Absent, HashMap::new()),
+                }
+            }
+            None => (RowState::Unknown, HashMap::new()),
+        };
+
+        // 最初のスナップショットと、行の有無がわからないスナップショットは比較しない ( 行が消えたスナップショットでは直前のカラムがすべて変更される )
+        let changed_col_names = match points.iter().rev().find(|point| point.state != RowState::Unknown) {
+            Some(last) if state != RowState::Unknown => {
+                let mut changed_col_names = col_values.keys().chain(last.col_values.keys()).unique().cloned().collect_vec();
+                changed_col_names.retain(|col_name| col_values.get(col_name) != last.col_values.get(col_name));
+                changed_col_names.sort_by_key(|col_name| col_names.iter().position(|c| c == col_name));
+                changed_col_names
+            }
+            _ => vec![],
+        };
+
+        points.push(RowPoint { snapshot_summary, state, col_values, changed_col_names });
+    }
+
+    RowTimeline { table_name: table_name.clone(), primary_col_name, primary_value: primary_value.to_string(), col_names, points }
+}
+
+// 文字列の主キーは、差分表示と同じ引用符つきの値でも引用符なしの値でも指定できる
+fn matches(primary_col_values: &PrimaryColValues, primary_value: &str) -> bool {
+    let unquoted = primary_col_values.col_values.iter().map(|col_value| col_value.as_display_value().trim_matches('"').to_string()).join("-");
+
+    primary_col_values.as_primary_value() == primary_value || unquoted == primary_value
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::snapshot::ColValue::{SimpleNumber, SimpleString};
+    use crate::domain::snapshot::{RowSnapshot, SnapshotSummary, TableSnapshot};
+    use crate::domain::timeline::{create_row_timeline, RowState};
+
+    fn summary(i: usize) -> SnapshotSummary {
+        SnapshotSummary::new(&format!("id-{i}"), format!("step {i}").as_str(), format!("2023-01-01 00:00:0{i}").as_str())
+    }
+
+    fn users(rows: Vec<(&str, &str, &str)>) -> Option<TableSnapshot> {
+        let s = |s: &str| SimpleString(s.to_string());
+        let row_snapshots =
+            rows.into_iter().map(|(id, name, status)| RowSnapshot::new(vec![s(id)], vec![s(name), SimpleNumber(status.to_string())])).collect();
+        Some(TableSnapshot::new(&"users".to_string(), "id".to_string(), vec!["name".to_string(), "status".to_string()], row_snapshots))
+    }
+
+    #[test]
+    fn row_timeline() {
+        let snapshots = vec![
+            (summary(3), users(vec![("u1", "John", "1"), ("u2", "Jack", "0")])),
+            (summary(1), users(vec![("u2", "Jack", "0")])),
+            (summary(2), users(vec![("u1", "John", "0")])),
+            (summary(4), None),
+            (summary(5), users(vec![("u1", "Johnny", "2")])),
+            (summary(6), users(vec![])),
+        ];
+
+        let sut = create_row_timeline(&"users".to_string(), "u1", snapshots);
+
+        assert_eq!("id", sut.primary_col_name);
+        assert_eq!(vec!["name", "status"], sut.col_names);
+        assert_eq!(
+            vec!["step 1", "step 2", "step 3", "step 4", "step 5", "step 6"],
+            sut.points.iter().map(|p| &p.snapshot_summary.snapshot_name).collect::<Vec<_>>()
+        );
+
+        let states = sut.points.iter().map(|p| &p.state).collect::<Vec<_>>();
+        assert_eq!(
+            vec![&RowState::Absent, &RowState::Present, &RowState::Present, &RowState::Unknown, &RowState::Present, &RowState::Absent],
+            states
+        );
+
+        // 行が現れたスナップショットと消えたスナップショットではすべてのカラムが、更新されたスナップショットでは更新されたカラムが変更されたとみなす
+        let changes = sut.points.iter().map(|p| p.changed_col_names.join(", ")).collect::<Vec<_>>();
+        assert_eq!(vec!["", "name, status", "status", "", "name, status", "name, status"], changes);
+
+        assert_eq!(Some(&SimpleString("Johnny".to_string())), sut.points[4].col_values.get("name"));
+    }
+
+    #[test]
+    fn quoted_primary_value() {
+        let sut = create_row_timeline(&"users".to_string(), r#""u2""#, vec![(summary(1), users(vec![("u2", "Jack", "0")]))]);
+
+        assert_eq!(RowState::Present, sut.points[0].state);
+    }
+}
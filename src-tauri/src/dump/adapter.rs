@@ -1,11 +1,165 @@
+use anyhow::anyhow;
+use itertools::Itertools;
+
+use crate::domain::db_object::DbObject;
 use crate::domain::dump_config::DumpConfig;
-use crate::domain::schema::TableSchema;
-use crate::domain::snapshot::RowSnapshot;
+use crate::domain::schema::{ColSchema, TableName, TableSchema};
+use crate::domain::snapshot::ColValue::*;
+use crate::domain::snapshot::{ColValue, PrimaryColValues, RowSnapshot};
+use crate::domain::table_definition::TableDefinition;
 
 pub trait TargetDbAdapter {
     fn get_dump_configs(&mut self) -> anyhow::Result<Vec<DumpConfig>>;
 
     fn get_table_schemata(&mut self) -> anyhow::Result<Vec<TableSchema>>;
 
-    fn get_row_snapshots(&mut self, table_schema: &TableSchema, dump_config_value: &str) -> anyhow::Result<Vec<RowSnapshot>>;
+    // 行の dump とは関係なく、すべてのテーブルのカラム、インデックス、外部キーの定義を取得する
+    fn get_table_definitions(&mut self) -> anyhow::Result<Vec<TableDefinition>>;
+
+    // ビュー、トリガー、ルーチン、イベントの定義を取得する ( RDBMS にない種類は含まれない )
+    fn get_db_objects(&mut self) -> anyhow::Result<Vec<DbObject>>;
+
+    // 0 行を取得するクエリを実行して、対象データベースで解釈できる条件か確認する
+    fn validate_filter(&mut self, table_name: &TableName, filter: &str) -> anyhow::Result<()>;
+
+    fn get_row_snapshots(&mut self, table_schema: &TableSchema, dump_config: &DumpConfig, limit: usize) -> anyhow::Result<Vec<RowSnapshot>>;
+
+    // 主キーの昇順で after より後ろの行を取得する
+    fn get_row_snapshots_after(
+        &mut self,
+        table_schema: &TableSchema,
+        dump_config: &DumpConfig,
+        after: Option<&PrimaryColValues>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<RowSnapshot>>;
+
+    // dump 時の条件に関係なく、主キーの値が一致する行を取得する
+    fn get_row_snapshots_by_keys(&mut self, table_schema: &TableSchema, keys: &[PrimaryColValues]) -> anyhow::Result<Vec<RowSnapshot>>;
+
+    // 1 つのトランザクションで実行し、途中で失敗した場合はすべて取り消す
+    fn execute_statements(&mut self, statements: &[String]) -> anyhow::Result<()>;
+}
+
+// 条件をそれぞれ括弧でくくって and でつなぐ
+pub fn create_where_clause(conditions: Vec<Option<String>>) -> String {
+    let conditions = conditions.into_iter().flatten().collect_vec();
+    if conditions.is_empty() {
+        "".to_string()
+    } else {
+        format!("where {}", conditions.iter().map(|condition| format!("({condition})")).join(" and "))
+    }
+}
+
+// (a, b) > (1, 'x') を a > 1 or (a = 1 and b > 'x') に展開する
+pub fn create_keyset_condition(
+    primary_cols: &[ColSchema],
+    after: &PrimaryColValues,
+    quote_col_name: fn(&str) -> String,
+    escape_backslash: bool,
+) -> anyhow::Result<String> {
+    let literals = after.col_values.iter().map(|col_value| as_literal(col_value, escape_backslash)).collect::<anyhow::Result<Vec<_>>>()?;
+    let col_names = primary_cols.iter().map(|col| quote_col_name(&col.col_name)).collect_vec();
+
+    Ok((0..col_names.len())
+        .map(|i| {
+            let mut conditions = (0..i).map(|j| format!("{} = {}", col_names[j], literals[j])).collect_vec();
+            conditions.push(format!("{} > {}", col_names[i], literals[i]));
+            format!("({})", conditions.join(" and "))
+        })
+        .join(" or "))
+}
+
+// 主キーの値ごとに (a = 1 and b = 'x') を作り、or でつなぐ
+pub fn create_key_condition(
+    primary_cols: &[ColSchema],
+    keys: &[PrimaryColValues],
+    quote_col_name: fn(&str) -> String,
+    escape_backslash: bool,
+) -> anyhow::Result<String> {
+    let col_names = primary_cols.iter().map(|col| quote_col_name(&col.col_name)).collect_vec();
+
+    Ok(keys
+        .iter()
+        .map(|key| {
+            let conditions = col_names
+                .iter()
+                .zip(&key.col_values)
+                .map(|(col_name, col_value)| Ok(format!("{} = {}", col_name, as_literal(col_value, escape_backslash)?)))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            Ok(format!("({})", conditions.join(" and ")))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?
+        .join(" or "))
+}
+
+fn as_literal(col_value: &ColValue, escape_backslash: bool) -> anyhow::Result<String> {
+    match col_value {
+        SimpleNumber(v) => Ok(v.clone()),
+        SimpleString(v) | DateString(v) => {
+            let v = if escape_backslash { v.replace('\\', r"\\") } else { v.clone() };
+            Ok(format!("'{}'", v.replace('\'', "''")))
+        }
+        _ => Err(anyhow!("primary key value is not supported for dumping all rows: {}", col_value.as_display_value())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::schema::ColSchema;
+    use crate::domain::snapshot::ColValue::*;
+    use crate::domain::snapshot::PrimaryColValues;
+    use crate::dump::adapter::{create_key_condition, create_keyset_condition, create_where_clause};
+
+    fn col(col_name: &str) -> ColSchema {
+        ColSchema { col_name: col_name.to_string(), data_type: "".to_string() }
+    }
+
+    fn quote(col_name: &str) -> String {
+        format!("`{col_name}`")
+    }
+
+    #[test]
+    fn keyset_condition() {
+        let after = PrimaryColValues::new(vec![SimpleNumber("1".to_string())]);
+        assert_eq!("(`id` > 1)", create_keyset_condition(&[col("id")], &after, quote, true).unwrap());
+
+        let after = PrimaryColValues::new(vec![SimpleNumber("1".to_string()), SimpleString("a'b".to_string()), DateString("2023-09-01".to_string())]);
+        assert_eq!(
+            "(`id` > 1) or (`id` = 1 and `code` > 'a''b') or (`id` = 1 and `code` = 'a''b' and `date` > '2023-09-01')",
+            create_keyset_condition(&[col("id"), col("code"), col("date")], &after, quote, true).unwrap()
+        );
+
+        let after = PrimaryColValues::new(vec![SimpleString(r"a\b".to_string())]);
+        assert_eq!(r"(`code` > 'a\\b')", create_keyset_condition(&[col("code")], &after, quote, true).unwrap());
+        assert_eq!(r"(`code` > 'a\b')", create_keyset_condition(&[col("code")], &after, quote, false).unwrap());
+
+        let after = PrimaryColValues::new(vec![BinaryString("abc".to_string())]);
+        assert!(create_keyset_condition(&[col("code")], &after, quote, true).is_err());
+    }
+
+    #[test]
+    fn key_condition() {
+        let keys = vec![
+            PrimaryColValues::new(vec![SimpleNumber("1".to_string()), SimpleString("a'b".to_string())]),
+            PrimaryColValues::new(vec![SimpleNumber("2".to_string()), SimpleString(r"c\d".to_string())]),
+        ];
+        assert_eq!(
+            r"(`id` = 1 and `code` = 'a''b') or (`id` = 2 and `code` = 'c\\d')",
+            create_key_condition(&[col("id"), col("code")], &keys, quote, true).unwrap()
+        );
+
+        let keys = vec![PrimaryColValues::new(vec![BinaryString("abc".to_string())])];
+        assert!(create_key_condition(&[col("code")], &keys, quote, true).is_err());
+    }
+
+    #[test]
+    fn where_clause() {
+        assert_eq!("", create_where_clause(vec![]));
+        assert_eq!("", create_where_clause(vec![None, None]));
+        assert_eq!("where (tenant_id = 42)", create_where_clause(vec![Some("tenant_id = 42".to_string()), None]));
+        assert_eq!(
+            "where (a = 1 or b = 2) and ((`id` > 1))",
+            create_where_clause(vec![Some("a = 1 or b = 2".to_string()), Some("(`id` > 1)".to_string())])
+        );
+    }
 }
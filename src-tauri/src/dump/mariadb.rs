@@ -0,0 +1,358 @@
+use std::collections::HashSet;
+
+use anyhow::anyhow;
+use mysql::{from_row, Conn};
+
+use crate::domain::db_object::DbObject;
+use crate::domain::dump_config::DumpConfig;
+use crate::domain::project::Project;
+use crate::domain::schema::{ColName, ColSchema, TableName, TableSchema};
+use crate::domain::snapshot::ColValue::SimpleString;
+use crate::domain::snapshot::{ColValue, PrimaryColValues, RowSnapshot};
+use crate::domain::table_definition::TableDefinition;
+use crate::dump::adapter::TargetDbAdapter;
+use crate::dump::{mysql_definition, mysql_protocol};
+use crate::logger;
+
+pub struct TargetDbMariadb {
+    conn: Conn,
+    schema: String,
+}
+
+impl TargetDbMariadb {
+    pub fn new(project: &Project) -> anyhow::Result<Self> {
+        let conn = project.create_connection()?;
+        let schema = project.schema.clone();
+
+        Ok(Self { conn, schema })
+    }
+
+    // MariaDB の json は longtext の別名で、json_valid の検査制約でしか見分けられない
+    fn get_json_cols(&mut self) -> anyhow::Result<HashSet<(TableName, ColName)>> {
+        let query = format!(
+            "select table_name, check_clause from information_schema.check_constraints where constraint_schema = '{}' and check_clause like 'json_valid(%'",
+            self.schema
+        );
+
+        logger::info(format!("query: {}", &query));
+
+        let result = self.conn.query(query).map_err(|e| anyhow!(e))?;
+
+        Ok(result
+            .map(|x| x.unwrap())
+            .filter_map(|row| {
+                let (table_name, check_clause) = from_row::<(String, String)>(row);
+                parse_json_valid_col_name(&check_clause).map(|col_name| (table_name, col_name))
+            })
+            .collect())
+    }
+}
+
+impl TargetDbAdapter for TargetDbMariadb {
+    fn get_dump_configs(&mut self) -> anyhow::Result<Vec<DumpConfig>> {
+        mysql_protocol::get_dump_configs(&mut self.conn, &self.schema)
+    }
+
+    fn get_table_schemata(&mut self) -> anyhow::Result<Vec<TableSchema>> {
+        let json_cols = self.get_json_cols()?;
+
+        // システムバージョニングの期間カラムは更新のたびに変わるので対象外にする ( 履歴行は select で返らないので現在の行だけが対象になる )
+        let query = format!(
+            "select c.table_name, c.column_name, c.data_type, c.column_key from information_schema.columns c join information_schema.tables t on c.table_schema = t.table_schema and c.table_name = t.table_name where c.table_schema = '{}' and t.table_type in ('BASE TABLE', 'SYSTEM VERSIONED') and c.extra not like '%INVISIBLE%' and c.extra not like '%ROW START%' and c.extra not like '%ROW END%' order by c.table_name, c.ordinal_position",
+            self.schema
+        );
+
+        mysql_protocol::select_table_schemata(&mut self.conn, &query, &json_cols)
+    }
+
+    fn get_table_definitions(&mut self) -> anyhow::Result<Vec<TableDefinition>> {
+        mysql_definition::get_table_definitions(&mut self.conn, &self.schema, &["BASE TABLE", "SYSTEM VERSIONED"])
+    }
+
+    fn get_db_objects(&mut self) -> anyhow::Result<Vec<DbObject>> {
+        mysql_definition::get_db_objects(&mut self.conn, &self.schema)
+    }
+
+    fn validate_filter(&mut self, table_name: &TableName, filter: &str) -> anyhow::Result<()> {
+        mysql_protocol::validate_filter(&mut self.conn, table_name, filter)
+    }
+
+    fn get_row_snapshots(&mut self, table_schema: &TableSchema, dump_config: &DumpConfig, limit: usize) -> anyhow::Result<Vec<RowSnapshot>> {
+        mysql_protocol::get_row_snapshots(&mut self.conn, table_schema, dump_config, limit, &parse_col_value)
+    }
+
+    fn get_row_snapshots_after(
+        &mut self,
+        table_schema: &TableSchema,
+        dump_config: &DumpConfig,
+        after: Option<&PrimaryColValues>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<RowSnapshot>> {
+        mysql_protocol::get_row_snapshots_after(&mut self.conn, table_schema, dump_config, after, limit, &parse_col_value)
+    }
+
+    fn get_row_snapshots_by_keys(&mut self, table_schema: &TableSchema, keys: &[PrimaryColValues]) -> anyhow::Result<Vec<RowSnapshot>> {
+        mysql_protocol::get_row_snapshots_by_keys(&mut self.conn, table_schema, keys, &parse_col_value)
+    }
+
+    fn execute_statements(&mut self, statements: &[String]) -> anyhow::Result<()> {
+        mysql_protocol::execute_statements(&mut self.conn, statements)
+    }
+}
+
+fn parse_json_valid_col_name(check_clause: &str) -> Option<ColName> {
+    check_clause.strip_prefix("json_valid(")?.strip_suffix(')').map(|col_name| col_name.trim_matches('`').to_string())
+}
+
+// MySQL にない uuid、inet4、inet6 型は文字列として扱う
+fn parse_col_value(col_schema: &ColSchema, value: String) -> ColValue {
+    match col_schema.data_type.as_str() {
+        "uuid" | "inet4" | "inet6" => SimpleString(value),
+        _ => mysql_protocol::parse_col_value(col_schema, value),
+    }
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod adapter_tests {
+    use itertools::Itertools;
+
+    use crate::db::{create_sqlite_connection, migrate_sqlite};
+    use crate::db::project::insert_project;
+    use crate::db::snapshot::find_table_snapshots;
+    use crate::domain::dump_config::DumpConfig;
+    use crate::domain::project::{create_project_id, Project};
+    use crate::domain::project::Rdbms::Mariadb;
+    use crate::domain::snapshot::{ColValue, create_snapshot_id, TableSnapshot};
+    use crate::domain::snapshot::ColValue::*;
+    use crate::dump::adapter::TargetDbAdapter;
+    use crate::dump::dump;
+    use crate::dump::mariadb::TargetDbMariadb;
+
+    fn s(s: &str) -> String {
+        s.to_string()
+    }
+
+    #[test]
+    fn get_dump_configs() -> anyhow::Result<()> {
+        let project = Project::new(&create_project_id(), "test-project", "red", Mariadb, "user","password","127.0.0.1","19005","testdata");
+
+        let mut adapter = TargetDbMariadb::new(&project)?;
+
+        // drop all
+        for table_schema in adapter.get_table_schemata()? {
+            adapter.conn.prep_exec(format!("drop table {}", table_schema.table_name), ())?;
+        }
+
+        adapter.conn.prep_exec("create table 01_number_01_signed ( id int auto_increment, col_tinyint tinyint, col_smallint smallint, col_mediumint mediumint, col_int int, col_bigint bigint, updated_at datetime, primary key (id) )", ())?;
+        adapter.conn.prep_exec("create table 11_string_01_char ( id int auto_increment, col_char char(3), col_varchar varchar(3), updated_at datetime, primary key (id) )", ())?;
+
+        let sut = DumpConfig::sort(adapter.get_dump_configs()?);
+
+        assert_eq!("01_number_01_signed", sut[0].table_name);
+        assert_eq!("id, col_tinyint, col_smallint, col_mediumint, col_int, col_bigint, updated_at", sut[0].col_names.join(", "));
+        assert_eq!("updated_at", sut[0].value);
+
+        assert_eq!("11_string_01_char", sut[1].table_name);
+        assert_eq!("id, col_char, col_varchar, updated_at", sut[1].col_names.join(", "));
+        assert_eq!("updated_at", sut[1].value);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump_all() -> anyhow::Result<()> {
+        let project = Project::new(&create_project_id(), "test-project", "red", Mariadb, "user","password","127.0.0.1","19005","testdata");
+
+        let mut adapter = TargetDbMariadb::new(&project)?;
+
+        // drop all
+        for table_schema in adapter.get_table_schemata()? {
+            adapter.conn.prep_exec(format!("drop table {}", table_schema.table_name), ())?;
+        }
+
+        adapter.conn.prep_exec("create table 01_number_01_signed ( id int auto_increment, col_tinyint tinyint, col_int int, col_bigint bigint, updated_at datetime, primary key (id) )", ())?;
+        adapter.conn.prep_exec("insert into 01_number_01_signed values (1, 127, 2147483647, 9223372036854775807, '20230901123456'), (2, -128, -2147483648, -9223372036854775808, '20230901123456')", ())?;
+
+        adapter.conn.prep_exec("create table 02_number_02_bit ( id int auto_increment, col_bit bit(10), updated_at datetime, primary key (id) )", ())?;
+        adapter.conn.prep_exec("insert into 02_number_02_bit values (1, b'1000000000', '20230901123456'), (2, b'0', '20230901123456')", ())?;
+
+        adapter.conn.prep_exec("create table 03_string_01_char ( id int auto_increment, col_varchar varchar(3), col_longtext longtext, updated_at datetime, primary key (id) )", ())?;
+        adapter.conn.prep_exec("insert into 03_string_01_char values (1, 'abc', 'abc', '20230901123456'), (2, null, null, '20230901123456')", ())?;
+
+        adapter.conn.prep_exec("create table 04_string_02_json ( id int auto_increment, col_json json, updated_at datetime, primary key (id) )", ())?;
+        adapter.conn.prep_exec(r#"insert into 04_string_02_json values (1, '{"id": 1, "name": "John"}', '20230901123456')"#, ())?;
+
+        adapter.conn.prep_exec("create table 05_string_03_uuid ( id int auto_increment, col_uuid uuid, col_inet4 inet4, col_inet6 inet6, updated_at datetime, primary key (id) )", ())?;
+        adapter.conn.prep_exec("insert into 05_string_03_uuid values (1, 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11', '192.168.0.1', '2001:db8::1', '20230901123456')", ())?;
+
+        adapter.conn.prep_exec("create table 06_versioning_01_system ( id int auto_increment, name varchar(10), primary key (id) ) with system versioning", ())?;
+        adapter.conn.prep_exec("insert into 06_versioning_01_system values (1, 'John')", ())?;
+        adapter.conn.prep_exec("update 06_versioning_01_system set name = 'Jane' where id = 1", ())?;
+
+        adapter.conn.prep_exec("create table 07_key_01_unique_not_null ( code int not null, updated_at datetime, unique (code) )", ())?;
+
+        adapter.conn.prep_exec("create table 08_key_02_nothing ( code int )", ())?;
+
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+
+        let project_id = create_project_id();
+        let project = Project::new(&project_id, "testdata-mariadb1011", "red", Mariadb, "user", "password", "localhost", "19005", "testdata");
+        insert_project(&conn, &project)?;
+
+        let snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &snapshot_id, "test dump".to_string(), &adapter.get_dump_configs()?)?;
+
+        let act = find_table_snapshots(&conn, &snapshot_id)?;
+
+        fn assert(act: &TableSnapshot, table_name: &str, primary_col_name: &str, col_names: Vec<&str>, col_values: Vec<Vec<ColValue>>) {
+            assert_eq!(table_name, act.table_name);
+            assert_eq!(primary_col_name, act.primary_col_name);
+            assert_eq!(col_names.into_iter().map(String::from).collect_vec(), act.col_names);
+            assert_eq!(col_values.len(), act.row_snapshots.len());
+            for (i, col_value) in col_values.into_iter().enumerate() {
+                assert_eq!(col_value, act.row_snapshots[i].col_values);
+            }
+        }
+
+        assert(
+            &act[0],
+            "01_number_01_signed",
+            "id",
+            vec!["col_tinyint", "col_int", "col_bigint", "updated_at"],
+            vec![
+                vec![SimpleNumber(s("127")),  SimpleNumber(s("2147483647")),  SimpleNumber(s("9223372036854775807")),  DateString(s("2023-09-01 12:34:56"))],
+                vec![SimpleNumber(s("-128")), SimpleNumber(s("-2147483648")), SimpleNumber(s("-9223372036854775808")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[1],
+            "02_number_02_bit",
+            "id",
+            vec!["col_bit", "updated_at"],
+            vec![
+                vec![BitNumber(s("1000000000")), DateString(s("2023-09-01 12:34:56"))],
+                vec![BitNumber(s("0")),          DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[2],
+            "03_string_01_char",
+            "id",
+            vec!["col_varchar", "col_longtext", "updated_at"],
+            vec![
+                vec![SimpleString(s("abc")), SimpleString(s("abc")), DateString(s("2023-09-01 12:34:56"))],
+                vec![Null,                   Null,                   DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[3],
+            "04_string_02_json",
+            "id",
+            vec!["col_json", "updated_at"],
+            vec![
+                vec![JsonString(s(r#"{"id": 1, "name": "John"}"#)), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[4],
+            "05_string_03_uuid",
+            "id",
+            vec!["col_uuid", "col_inet4", "col_inet6", "updated_at"],
+            vec![
+                vec![SimpleString(s("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11")), SimpleString(s("192.168.0.1")), SimpleString(s("2001:db8::1")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[5],
+            "06_versioning_01_system",
+            "id",
+            vec!["name"],
+            vec![
+                vec![SimpleString(s("Jane"))],
+            ]
+        );
+
+        assert(
+            &act[6],
+            "07_key_01_unique_not_null",
+            "code",
+            vec!["updated_at"],
+            vec![]
+        );
+
+        // 08_key_02_nothing
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod parse_json_valid_col_name_tests {
+    use crate::dump::mariadb::parse_json_valid_col_name;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Some("col_json".to_string()), parse_json_valid_col_name("json_valid(`col_json`)"));
+        assert_eq!(None, parse_json_valid_col_name("`price` > 0"));
+    }
+}
+
+#[cfg(test)]
+mod parse_col_value_tests {
+    use crate::domain::schema::ColSchema;
+    use crate::domain::snapshot::ColValue;
+    use crate::dump::mariadb::parse_col_value;
+
+    fn sut(data_type: &str, value: &str) -> ColValue {
+        parse_col_value(&ColSchema { col_name: "col_test".to_string(), data_type: data_type.to_string() }, value.to_string())
+    }
+
+    #[test]
+    fn parse_int() {
+        let exp = "42";
+        assert_eq!(exp, sut("int", "42").as_display_value());
+    }
+
+    #[test]
+    fn parse_bit() {
+        let exp = "bit(101)";
+        assert_eq!(exp, sut("bit", "101").as_display_value());
+    }
+
+    #[test]
+    fn parse_longtext() {
+        let exp = r#""abc""#;
+        assert_eq!(exp, sut("longtext", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_uuid() {
+        let exp = r#""a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11""#;
+        assert_eq!(exp, sut("uuid", "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11").as_display_value());
+    }
+
+    #[test]
+    fn parse_inet4() {
+        let exp = r#""192.168.0.1""#;
+        assert_eq!(exp, sut("inet4", "192.168.0.1").as_display_value());
+    }
+
+    #[test]
+    fn parse_inet6() {
+        let exp = r#""2001:db8::1""#;
+        assert_eq!(exp, sut("inet6", "2001:db8::1").as_display_value());
+    }
+
+    #[test]
+    fn parse_json() {
+        let exp = r#"{"id": 1, "name": "John"}"#;
+        assert_eq!(exp, sut("json", r#"{"id": 1, "name": "John"}"#).as_display_value());
+    }
+}
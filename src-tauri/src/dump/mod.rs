@@ -1,26 +1,73 @@
-use std::collections::HashMap;
+use std::cmp::min;
+use std::collections::{HashMap, HashSet};
 
+use anyhow::anyhow;
 use diesel::SqliteConnection;
+use itertools::Itertools;
 
-use crate::db::snapshot::{insert_snapshot_summary, insert_table_snapshots};
+use crate::db::db_object::insert_db_objects;
+use crate::db::dump_config::{find_dump_config, insert_dump_configs};
+use crate::db::snapshot::{find_table_snapshots, insert_snapshot_summary, insert_table_snapshots};
 use crate::db::snapshot_result::{insert_snapshot_result, update_snapshot_result};
+use crate::db::table_definition::insert_table_definitions;
+use crate::domain::db_object::DbObject;
+use crate::domain::diff::create_table_diff;
 use crate::domain::dump_config::DumpConfig;
+use crate::domain::ignore_rule::IgnoreRules;
 use crate::domain::project::Project;
-use crate::domain::project::Rdbms::Mysql;
-use crate::domain::schema::TableName;
-use crate::domain::snapshot::{SnapshotId, SnapshotName, SnapshotSummary, TableSnapshot};
+use crate::domain::project::Rdbms::{Mariadb, Mysql, Postgres, Sqlite};
+use crate::domain::schema::{TableName, TableSchema};
+use crate::domain::snapshot::{PrimaryColValues, RowSnapshot, SnapshotId, SnapshotName, SnapshotSummary, TableSnapshot};
 use crate::domain::snapshot_result::SnapshotResult;
+use crate::domain::table_definition::TableDefinition;
 use crate::dump::adapter::TargetDbAdapter;
+use crate::dump::mariadb::TargetDbMariadb;
+use crate::dump::mysql57::TargetDbMysql57;
 use crate::dump::mysql80::TargetDbMysql80;
+use crate::dump::mysql_version::MysqlVersion;
+use crate::dump::postgres::TargetDbPostgres;
+use crate::dump::sqlite::TargetDbSqlite;
 use crate::logger;
+use crate::script::create_ordered_statements;
 
 mod adapter;
+mod mariadb;
+mod mysql57;
 mod mysql80;
+mod mysql_definition;
+mod mysql_protocol;
+mod mysql_version;
+mod postgres;
+mod sqlite;
+
+// dump_live で取得した、保存していない現在の状態
+pub struct LiveSnapshot {
+    pub dump_configs: Vec<DumpConfig>,
+    pub table_definitions: Vec<TableDefinition>,
+    pub db_objects: Option<Vec<DbObject>>,
+    pub table_snapshots: Vec<TableSnapshot>,
+}
+
+// all で 1 回のクエリで取得する行数
+const BATCH_SIZE: usize = 1000;
+
+// 主キーのないテーブルを all で dump する場合の取得行数 ( 事実上の無制限 )
+const UNLIMITED: usize = i64::MAX as usize;
+
+fn create_adapter(project: &Project) -> anyhow::Result<Box<dyn TargetDbAdapter>> {
+    Ok(match &project.rdbms {
+        Mysql => match MysqlVersion::detect(project)? {
+            MysqlVersion::V80 => Box::new(TargetDbMysql80::new(project)?),
+            version => Box::new(TargetDbMysql57::new(project, version)?),
+        },
+        Mariadb => Box::new(TargetDbMariadb::new(project)?),
+        Postgres => Box::new(TargetDbPostgres::new(project)?),
+        Sqlite => Box::new(TargetDbSqlite::new(project)?),
+    })
+}
 
 pub fn get_dump_configs(project: &Project) -> anyhow::Result<Vec<DumpConfig>> {
-    let mut adapter = match &project.rdbms {
-        Mysql => TargetDbMysql80::new(project),
-    }?;
+    let mut adapter = create_adapter(project)?;
 
     adapter.get_dump_configs()
 }
@@ -32,16 +79,22 @@ pub fn dump(
     snapshot_name: SnapshotName,
     dump_configs: &[DumpConfig],
 ) -> anyhow::Result<SnapshotResult> {
-    let mut adapter = match &project.rdbms {
-        Mysql => TargetDbMysql80::new(project),
-    }?;
+    let mut adapter = create_adapter(project)?;
 
-    let snapshot_summary = SnapshotSummary::create(snapshot_id, &snapshot_name);
-    insert_snapshot_summary(conn, &project.project_id, &snapshot_summary)?;
+    validate_filters(adapter.as_mut(), dump_configs)?;
 
     let dump_configs: HashMap<&TableName, &DumpConfig> = dump_configs.iter().map(|dump_config| (&dump_config.table_name, dump_config)).collect();
 
-    let table_schemata = adapter.get_table_schemata()?;
+    let table_schemata = apply_key_cols(adapter.get_table_schemata()?, &dump_configs)?;
+
+    let snapshot_summary = SnapshotSummary::create(snapshot_id, &snapshot_name);
+    insert_snapshot_summary(conn, &project.project_id, &snapshot_summary)?;
+
+    // dump しないテーブルも含めて、すべてのテーブルの定義を記録する
+    insert_table_definitions(conn, snapshot_id, &adapter.get_table_definitions()?)?;
+    if let Some(db_objects) = get_db_objects(adapter.as_mut()) {
+        insert_db_objects(conn, snapshot_id, &db_objects)?;
+    }
 
     let mut last_percent = 0;
     let mut snapshot_result = SnapshotResult::init(snapshot_id, table_schemata.len());
@@ -61,15 +114,11 @@ pub fn dump(
             continue;
         }
 
-        let mut table_snapshots = vec![];
-
-        if table_schema.has_any_primary_cols() {
-            let row_snapshots = adapter.get_row_snapshots(&table_schema, &dump_config.value)?;
+        // 主キーのないテーブルは、多重集合として比較する指定がなければ dump しない
+        if table_schema.has_any_primary_cols() || dump_config.multiset {
+            let table_snapshot = take_table_snapshot(adapter.as_mut(), table_schema, dump_config)?;
 
-            let (primary_col_name, col_names) = table_schema.get_all_col_names();
-            table_snapshots.push(TableSnapshot::new(&table_schema.table_name, primary_col_name, col_names, row_snapshots));
-
-            insert_table_snapshots(conn, snapshot_id, table_snapshots)?;
+            insert_table_snapshots(conn, snapshot_id, vec![table_snapshot])?;
 
             snapshot_result.increment();
             update_snapshot_result(conn, &snapshot_result)?;
@@ -85,3 +134,271 @@ pub fn dump(
 
     Ok(snapshot_result)
 }
+
+// 保存したスナップショットと同じ dump 設定で、現在の状態をスナップショットとして保存せずに dump する
+pub fn dump_live(conn: &SqliteConnection, project: &Project, snapshot_id: &SnapshotId) -> anyhow::Result<LiveSnapshot> {
+    let mut adapter = create_adapter(project)?;
+
+    let dump_configs = find_dump_config(conn, snapshot_id)?;
+    validate_filters(adapter.as_mut(), &dump_configs)?;
+
+    let dump_config_map: HashMap<&TableName, &DumpConfig> = dump_configs.iter().map(|dump_config| (&dump_config.table_name, dump_config)).collect();
+
+    let mut table_snapshots = vec![];
+    for table_schema in apply_key_cols(adapter.get_table_schemata()?, &dump_config_map)? {
+        // スナップショットの作成後に追加されたテーブルは dump 設定がないので比較しない
+        match dump_config_map.get(&table_schema.table_name) {
+            Some(&dump_config) if dump_config.value != "ignore" && (table_schema.has_any_primary_cols() || dump_config.multiset) => {
+                table_snapshots.push(take_table_snapshot(adapter.as_mut(), table_schema, dump_config)?);
+            }
+            _ => logger::info(format!("ignore: {}", &table_schema.table_name)),
+        }
+    }
+
+    Ok(LiveSnapshot {
+        dump_configs,
+        table_definitions: adapter.get_table_definitions()?,
+        db_objects: get_db_objects(adapter.as_mut()),
+        table_snapshots,
+    })
+}
+
+// 権限が足りずに取得できない場合もあるので、失敗しても dump は続ける ( オブジェクトは比較の対象外になる )
+fn get_db_objects(adapter: &mut dyn TargetDbAdapter) -> Option<Vec<DbObject>> {
+    match adapter.get_db_objects() {
+        Ok(db_objects) => Some(db_objects),
+        Err(e) => {
+            logger::error(format!("cannot get db objects: {}", e));
+            None
+        }
+    }
+}
+
+// dump_live で取得した状態を、通常の dump と同じくスナップショットとして保存する
+pub fn keep_live(
+    conn: &SqliteConnection,
+    project: &Project,
+    snapshot_id: &SnapshotId,
+    snapshot_name: SnapshotName,
+    live_snapshot: LiveSnapshot,
+) -> anyhow::Result<()> {
+    insert_snapshot_summary(conn, &project.project_id, &SnapshotSummary::create(snapshot_id, &snapshot_name))?;
+
+    let mut snapshot_result = SnapshotResult::init(snapshot_id, live_snapshot.table_snapshots.len());
+    snapshot_result.complete();
+    insert_snapshot_result(conn, &snapshot_result)?;
+
+    insert_table_definitions(conn, snapshot_id, &live_snapshot.table_definitions)?;
+    if let Some(db_objects) = &live_snapshot.db_objects {
+        insert_db_objects(conn, snapshot_id, db_objects)?;
+    }
+    insert_table_snapshots(conn, snapshot_id, live_snapshot.table_snapshots)?;
+    insert_dump_configs(conn, &project.project_id, snapshot_id, &live_snapshot.dump_configs)
+}
+
+// 対象データベースの行を、保存したスナップショットの状態に戻すための SQL をまとめて実行する
+pub fn restore(conn: &SqliteConnection, project: &Project, snapshot_id: &SnapshotId) -> anyhow::Result<usize> {
+    let stored_table_snapshots = find_table_snapshots(conn, snapshot_id)?;
+
+    // 上限を超えた行はスナップショットにないので、戻すと削除されてしまう
+    let truncated_table_names = stored_table_snapshots
+        .iter()
+        .filter(|table_snapshot| table_snapshot.is_truncated())
+        .map(|table_snapshot| &table_snapshot.table_name)
+        .join(", ");
+    if !truncated_table_names.is_empty() {
+        return Err(anyhow!("cannot restore a snapshot truncated by row limit: {}", truncated_table_names));
+    }
+
+    let mut adapter = create_adapter(project)?;
+
+    let dump_configs = find_dump_config(conn, snapshot_id)?;
+    let dump_configs: HashMap<&TableName, &DumpConfig> = dump_configs.iter().map(|dump_config| (&dump_config.table_name, dump_config)).collect();
+
+    let mut table_schemata: HashMap<TableName, TableSchema> = apply_key_cols(adapter.get_table_schemata()?, &dump_configs)?
+        .into_iter()
+        .map(|table_schema| (table_schema.table_name.clone(), table_schema))
+        .collect();
+
+    let mut table_diffs = vec![];
+
+    for stored_table_snapshot in &stored_table_snapshots {
+        let table_name = &stored_table_snapshot.table_name;
+        let table_schema = match table_schemata.remove(table_name) {
+            Some(table_schema) => table_schema,
+            None => return Err(anyhow!("table not found: {}", table_name)),
+        };
+        let dump_config = match dump_configs.get(table_name) {
+            Some(&dump_config) => dump_config,
+            None => return Err(anyhow!("dump config not found: {}", table_name)),
+        };
+
+        // 条件や除外カラムは dump 時と同じにして、対象となる行をすべて取得する
+        let dump_config = DumpConfig { value: "all".to_string(), row_limit: None, ..dump_config.clone() };
+        let key_schema = TableSchema::new(&table_schema.table_name, &table_schema.primary_cols, &table_schema.cols);
+        let mut live_table_snapshot = take_table_snapshot(adapter.as_mut(), table_schema, &dump_config)?;
+
+        // 条件に合わなくなった行は取得されないので、スナップショットの主キーで取得して更新の対象にする
+        if dump_config.get_filter().is_some() && key_schema.has_any_primary_cols() {
+            let key_schema = key_schema.exclude_cols(&live_table_snapshot.excluded_col_names);
+            let moved_row_snapshots = get_moved_row_snapshots(adapter.as_mut(), &key_schema, &live_table_snapshot, stored_table_snapshot)?;
+            if !moved_row_snapshots.is_empty() {
+                live_table_snapshot = TableSnapshot::new(
+                    table_name,
+                    live_table_snapshot.primary_col_name,
+                    live_table_snapshot.col_names,
+                    live_table_snapshot.row_snapshots.into_iter().chain(moved_row_snapshots).collect(),
+                )
+                .with_primary_col_names(live_table_snapshot.primary_col_names)
+                .with_excluded_col_names(live_table_snapshot.excluded_col_names);
+            }
+        }
+
+        table_diffs.push(create_table_diff(Some(&live_table_snapshot), Some(stored_table_snapshot), &IgnoreRules::default()));
+    }
+
+    // PostgreSQL は遅延できない外部キーを無効にできないので、外部キーの順に実行する
+    let table_definitions = match project.rdbms {
+        Postgres => adapter.get_table_definitions()?,
+        _ => vec![],
+    };
+    let statements = create_ordered_statements(&project.rdbms, &table_diffs, &table_definitions)?;
+    if !statements.is_empty() {
+        adapter.execute_statements(&statements)?;
+    }
+
+    Ok(statements.len())
+}
+
+// スナップショットにあって今の条件では取得されない行を、主キーの値を分割して取得する
+fn get_moved_row_snapshots(
+    adapter: &mut dyn TargetDbAdapter,
+    table_schema: &TableSchema,
+    live_table_snapshot: &TableSnapshot,
+    stored_table_snapshot: &TableSnapshot,
+) -> anyhow::Result<Vec<RowSnapshot>> {
+    let live_keys: HashSet<&PrimaryColValues> =
+        live_table_snapshot.row_snapshots.iter().map(|row_snapshot| &row_snapshot.primary_col_values).collect();
+    let keys = stored_table_snapshot
+        .row_snapshots
+        .iter()
+        .map(|row_snapshot| &row_snapshot.primary_col_values)
+        .filter(|&primary_col_values| !live_keys.contains(primary_col_values))
+        .cloned()
+        .collect_vec();
+
+    let mut row_snapshots = vec![];
+    for keys in keys.chunks(BATCH_SIZE) {
+        row_snapshots.extend(adapter.get_row_snapshots_by_keys(table_schema, keys)?);
+    }
+    Ok(row_snapshots)
+}
+
+fn take_table_snapshot(adapter: &mut dyn TargetDbAdapter, table_schema: TableSchema, dump_config: &DumpConfig) -> anyhow::Result<TableSnapshot> {
+    // 主キーは除外できない
+    let excluded_col_names = table_schema
+        .cols
+        .iter()
+        .map(|col| &col.col_name)
+        .filter(|&col_name| dump_config.excluded_col_names.contains(col_name))
+        .cloned()
+        .collect_vec();
+    let table_schema = table_schema.exclude_cols(&excluded_col_names);
+
+    let (row_snapshots, truncated) = get_row_snapshots(adapter, &table_schema, dump_config)?;
+    if !dump_config.key_col_names.is_empty() {
+        validate_unique_keys(&table_schema, &row_snapshots)?;
+    }
+    if truncated {
+        logger::info(format!("truncated: {}", &table_schema.table_name));
+    }
+
+    let (primary_col_name, col_names) = table_schema.get_all_col_names();
+    let primary_col_names = table_schema.primary_cols.iter().map(|col| col.col_name.clone()).collect();
+    Ok(TableSnapshot::new(&table_schema.table_name, primary_col_name, col_names, row_snapshots)
+        .with_primary_col_names(primary_col_names)
+        .with_truncated(truncated)
+        .with_excluded_col_names(excluded_col_names))
+}
+
+// 条件の誤りで dump が途中で失敗しないように、dump を始める前にすべての条件を確認する
+fn validate_filters(adapter: &mut dyn TargetDbAdapter, dump_configs: &[DumpConfig]) -> anyhow::Result<()> {
+    let errors = dump_configs
+        .iter()
+        .filter(|dump_config| dump_config.value != "ignore")
+        .filter_map(|dump_config| dump_config.get_filter().map(|filter| (&dump_config.table_name, filter)))
+        .filter_map(|(table_name, filter)| adapter.validate_filter(table_name, filter).err().map(|e| format!("{table_name}: {e}")))
+        .collect_vec();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("invalid filter: {}", errors.join(", ")))
+    }
+}
+
+// 論理キーが指定されたテーブルは、主キーの代わりに論理キーで行を識別する
+fn apply_key_cols(table_schemata: Vec<TableSchema>, dump_configs: &HashMap<&TableName, &DumpConfig>) -> anyhow::Result<Vec<TableSchema>> {
+    table_schemata
+        .into_iter()
+        .map(|table_schema| match dump_configs.get(&table_schema.table_name) {
+            Some(dump_config) if dump_config.value != "ignore" => table_schema.with_key_cols(&dump_config.key_col_names),
+            _ => Ok(table_schema),
+        })
+        .collect()
+}
+
+// 論理キーは一意であることが保証されないので、dump した行で重複がないか確認する
+fn validate_unique_keys(table_schema: &TableSchema, row_snapshots: &[RowSnapshot]) -> anyhow::Result<()> {
+    let duplicates = row_snapshots
+        .iter()
+        .map(|row_snapshot| &row_snapshot.primary_col_values)
+        .duplicates()
+        .map(|primary_col_values| primary_col_values.as_primary_value())
+        .collect_vec();
+
+    if duplicates.is_empty() {
+        Ok(())
+    } else {
+        let (primary_col_name, _) = table_schema.get_all_col_names();
+        Err(anyhow!("duplicate key values: {} ( {} ): {}", table_schema.table_name, primary_col_name, duplicates.join(", ")))
+    }
+}
+
+// 上限を超えたかを判定するため、上限より 1 行多く取得する
+fn get_row_snapshots(
+    adapter: &mut dyn TargetDbAdapter,
+    table_schema: &TableSchema,
+    dump_config: &DumpConfig,
+) -> anyhow::Result<(Vec<RowSnapshot>, bool)> {
+    let row_limit = dump_config.get_row_limit();
+
+    // 主キーのないテーブルは keyset で分割できないので、1 回のクエリで取得する
+    let mut row_snapshots = if dump_config.is_all() && table_schema.has_any_primary_cols() {
+        let mut row_snapshots: Vec<RowSnapshot> = vec![];
+        loop {
+            let limit = match row_limit {
+                Some(row_limit) => min(BATCH_SIZE, row_limit + 1 - row_snapshots.len()),
+                None => BATCH_SIZE,
+            };
+            let page = adapter.get_row_snapshots_after(table_schema, dump_config, row_snapshots.last().map(|row| &row.primary_col_values), limit)?;
+            let last = page.len() < limit;
+            row_snapshots.extend(page);
+
+            if last || row_limit.is_some_and(|row_limit| row_limit < row_snapshots.len()) {
+                break;
+            }
+        }
+        row_snapshots
+    } else {
+        adapter.get_row_snapshots(table_schema, dump_config, row_limit.map_or(UNLIMITED, |row_limit| row_limit + 1))?
+    };
+
+    match row_limit {
+        Some(row_limit) if row_limit < row_snapshots.len() => {
+            row_snapshots.truncate(row_limit);
+            Ok((row_snapshots, true))
+        }
+        _ => Ok((row_snapshots, false)),
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::HashSet;
+
+use mysql::Conn;
+
+use crate::domain::db_object::DbObject;
+use crate::domain::dump_config::DumpConfig;
+use crate::domain::project::Project;
+use crate::domain::schema::{ColSchema, TableName, TableSchema};
+use crate::domain::snapshot::ColValue::ParseError;
+use crate::domain::snapshot::{ColValue, PrimaryColValues, RowSnapshot};
+use crate::domain::table_definition::TableDefinition;
+use crate::dump::adapter::TargetDbAdapter;
+use crate::dump::mysql_version::MysqlVersion;
+use crate::dump::mysql_version::MysqlVersion::V56;
+use crate::dump::{mysql_definition, mysql_protocol};
+
+// 5.7 と 5.6 の差異は json 型の有無くらいなので、1 つのアダプタで扱う
+pub struct TargetDbMysql57 {
+    conn: Conn,
+    schema: String,
+    version: MysqlVersion,
+}
+
+impl TargetDbMysql57 {
+    pub fn new(project: &Project, version: MysqlVersion) -> anyhow::Result<Self> {
+        let conn = project.create_connection()?;
+        let schema = project.schema.clone();
+
+        Ok(Self { conn, schema, version })
+    }
+}
+
+impl TargetDbAdapter for TargetDbMysql57 {
+    fn get_dump_configs(&mut self) -> anyhow::Result<Vec<DumpConfig>> {
+        mysql_protocol::get_dump_configs(&mut self.conn, &self.schema)
+    }
+
+    fn get_table_schemata(&mut self) -> anyhow::Result<Vec<TableSchema>> {
+        // 5.x の information_schema.columns はビューも返すので、実テーブルに限定する
+        let query = format!(
+            "select c.table_name, c.column_name, c.data_type, c.column_key from information_schema.columns c join information_schema.tables t on c.table_schema = t.table_schema and c.table_name = t.table_name where c.table_schema = '{}' and t.table_type = 'BASE TABLE' order by c.table_name, c.ordinal_position",
+            self.schema
+        );
+
+        mysql_protocol::select_table_schemata(&mut self.conn, &query, &HashSet::new())
+    }
+
+    fn get_table_definitions(&mut self) -> anyhow::Result<Vec<TableDefinition>> {
+        mysql_definition::get_table_definitions(&mut self.conn, &self.schema, &["BASE TABLE"])
+    }
+
+    fn get_db_objects(&mut self) -> anyhow::Result<Vec<DbObject>> {
+        mysql_definition::get_db_objects(&mut self.conn, &self.schema)
+    }
+
+    fn validate_filter(&mut self, table_name: &TableName, filter: &str) -> anyhow::Result<()> {
+        mysql_protocol::validate_filter(&mut self.conn, table_name, filter)
+    }
+
+    fn get_row_snapshots(&mut self, table_schema: &TableSchema, dump_config: &DumpConfig, limit: usize) -> anyhow::Result<Vec<RowSnapshot>> {
+        let version = &self.version;
+        mysql_protocol::get_row_snapshots(&mut self.conn, table_schema, dump_config, limit, &|col, value| parse_col_value(version, col, value))
+    }
+
+    fn get_row_snapshots_after(
+        &mut self,
+        table_schema: &TableSchema,
+        dump_config: &DumpConfig,
+        after: Option<&PrimaryColValues>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<RowSnapshot>> {
+        let version = &self.version;
+        mysql_protocol::get_row_snapshots_after(&mut self.conn, table_schema, dump_config, after, limit, &|col, value| {
+            parse_col_value(version, col, value)
+        })
+    }
+
+    fn get_row_snapshots_by_keys(&mut self, table_schema: &TableSchema, keys: &[PrimaryColValues]) -> anyhow::Result<Vec<RowSnapshot>> {
+        let version = &self.version;
+        mysql_protocol::get_row_snapshots_by_keys(&mut self.conn, table_schema, keys, &|col, value| parse_col_value(version, col, value))
+    }
+
+    fn execute_statements(&mut self, statements: &[String]) -> anyhow::Result<()> {
+        mysql_protocol::execute_statements(&mut self.conn, statements)
+    }
+}
+
+// 5.x は sql_mode によって 0000-00-00 のようなゼロ日付を保持できるが、そのまま文字列として扱う ( 5.6 には json 型がない )
+fn parse_col_value(version: &MysqlVersion, col_schema: &ColSchema, value: String) -> ColValue {
+    match col_schema.data_type.as_str() {
+        "json" if version == &V56 => ParseError,
+        _ => mysql_protocol::parse_col_value(col_schema, value),
+    }
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod adapter_tests {
+    use itertools::Itertools;
+
+    use crate::db::{create_sqlite_connection, migrate_sqlite};
+    use crate::db::project::insert_project;
+    use crate::db::snapshot::find_table_snapshots;
+    use crate::domain::dump_config::DumpConfig;
+    use crate::domain::project::{create_project_id, Project};
+    use crate::domain::project::Rdbms::Mysql;
+    use crate::domain::snapshot::{ColValue, create_snapshot_id, TableSnapshot};
+    use crate::domain::snapshot::ColValue::*;
+    use crate::dump::adapter::TargetDbAdapter;
+    use crate::dump::dump;
+    use crate::dump::mysql57::TargetDbMysql57;
+    use crate::dump::mysql_version::MysqlVersion;
+    use crate::dump::mysql_version::MysqlVersion::{V56, V57};
+
+    fn s(s: &str) -> String {
+        s.to_string()
+    }
+
+    fn setup(port: &str, version: MysqlVersion) -> anyhow::Result<(Project, TargetDbMysql57)> {
+        let project = Project::new(&create_project_id(), "test-project", "red", Mysql, "user","password","127.0.0.1",port,"testdata");
+
+        assert_eq!(version, MysqlVersion::detect(&project)?);
+
+        let mut adapter = TargetDbMysql57::new(&project, version)?;
+
+        // drop all
+        for table_schema in adapter.get_table_schemata()? {
+            adapter.conn.prep_exec(format!("drop table {}", table_schema.table_name), ())?;
+        }
+
+        Ok((project, adapter))
+    }
+
+    #[test]
+    fn get_dump_configs() -> anyhow::Result<()> {
+        for (port, version) in [("19003", V57), ("19004", V56)] {
+            let (_, mut adapter) = setup(port, version)?;
+
+            adapter.conn.prep_exec("create table 01_number_01_signed ( id int auto_increment, col_tinyint tinyint, col_smallint smallint, col_mediumint mediumint, col_int int, col_bigint bigint, updated_at datetime, primary key (id) )", ())?;
+            adapter.conn.prep_exec("create table 11_string_01_char ( id int auto_increment, col_char char(3), col_varchar varchar(3), updated_at datetime, primary key (id) )", ())?;
+
+            let sut = DumpConfig::sort(adapter.get_dump_configs()?);
+
+            assert_eq!("01_number_01_signed", sut[0].table_name);
+            assert_eq!("id, col_tinyint, col_smallint, col_mediumint, col_int, col_bigint, updated_at", sut[0].col_names.join(", "));
+            assert_eq!("updated_at", sut[0].value);
+
+            assert_eq!("11_string_01_char", sut[1].table_name);
+            assert_eq!("id, col_char, col_varchar, updated_at", sut[1].col_names.join(", "));
+            assert_eq!("updated_at", sut[1].value);
+        }
+
+        Ok(())
+    }
+
+    fn assert(act: &TableSnapshot, table_name: &str, primary_col_name: &str, col_names: Vec<&str>, col_values: Vec<Vec<ColValue>>) {
+        assert_eq!(table_name, act.table_name);
+        assert_eq!(primary_col_name, act.primary_col_name);
+        assert_eq!(col_names.into_iter().map(String::from).collect_vec(), act.col_names);
+        assert_eq!(col_values.len(), act.row_snapshots.len());
+        for (i, col_value) in col_values.into_iter().enumerate() {
+            assert_eq!(col_value, act.row_snapshots[i].col_values);
+        }
+    }
+
+    fn dump_common(port: &str, version: MysqlVersion) -> anyhow::Result<Vec<TableSnapshot>> {
+        let (project, mut adapter) = setup(port, version)?;
+
+        adapter.conn.prep_exec("set session sql_mode = ''", ())?;
+
+        adapter.conn.prep_exec("create table 01_number_01_signed ( id int auto_increment, col_tinyint tinyint, col_int int, col_bigint bigint, updated_at datetime, primary key (id) )", ())?;
+        adapter.conn.prep_exec("insert into 01_number_01_signed values (1, 127, 2147483647, 9223372036854775807, '20230901123456'), (2, -128, -2147483648, -9223372036854775808, '20230901123456')", ())?;
+
+        adapter.conn.prep_exec("create table 02_number_02_float ( id int auto_increment, col_decimal decimal(5, 2), col_float float(5, 2), col_double double(5, 2), updated_at datetime, primary key (id) )", ())?;
+        adapter.conn.prep_exec("insert into 02_number_02_float values (1, 999.99, 999.99, 999.99, '20230901123456')", ())?;
+
+        adapter.conn.prep_exec("create table 03_number_03_bit ( id int auto_increment, col_bit bit(10), updated_at datetime, primary key (id) )", ())?;
+        adapter.conn.prep_exec("insert into 03_number_03_bit values (1, b'1000000000', '20230901123456'), (2, b'0', '20230901123456')", ())?;
+
+        adapter.conn.prep_exec("create table 04_date_01_zero ( id int auto_increment, col_date date, col_datetime datetime, updated_at datetime, primary key (id) )", ())?;
+        adapter.conn.prep_exec("insert into 04_date_01_zero values (1, '2020-01-01', '2020-01-01 00:00:00', '20230901123456'), (2, '0000-00-00', '0000-00-00 00:00:00', '20230901123456')", ())?;
+
+        adapter.conn.prep_exec("create table 05_string_01_char ( id int auto_increment, col_varchar varchar(3), col_text text, col_blob blob, col_enum enum ('active', 'inactive'), updated_at datetime, primary key (id) )", ())?;
+        adapter.conn.prep_exec("insert into 05_string_01_char values (1, 'abc', 'abc', 'abc', 'active', '20230901123456'), (2, null, null, null, null, '20230901123456')", ())?;
+
+        adapter.conn.prep_exec("create table 06_key_01_unique_not_null ( code int not null, updated_at datetime, unique (code) )", ())?;
+
+        adapter.conn.prep_exec("create table 07_key_02_multi_unique_not_null_unique_not_null ( code1 int not null, code2 int not null, updated_at datetime, unique (code1, code2) )", ())?;
+
+        adapter.conn.prep_exec("create table 08_key_03_nothing ( code int )", ())?;
+
+        adapter.conn.prep_exec("create or replace view 09_view as select id from 01_number_01_signed", ())?;
+
+        if adapter.version == V57 {
+            adapter.conn.prep_exec("create table 10_string_02_json ( id int auto_increment, col_json json, updated_at datetime, primary key (id) )", ())?;
+            adapter.conn.prep_exec(r#"insert into 10_string_02_json values (1, '{"id": 1, "name": "John"}', '20230901123456')"#, ())?;
+        }
+
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+
+        insert_project(&conn, &project)?;
+
+        let snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &snapshot_id, "test dump".to_string(), &adapter.get_dump_configs()?)?;
+
+        let act = find_table_snapshots(&conn, &snapshot_id)?;
+
+        assert(
+            &act[0],
+            "01_number_01_signed",
+            "id",
+            vec!["col_tinyint", "col_int", "col_bigint", "updated_at"],
+            vec![
+                vec![SimpleNumber(s("127")),  SimpleNumber(s("2147483647")),  SimpleNumber(s("9223372036854775807")),  DateString(s("2023-09-01 12:34:56"))],
+                vec![SimpleNumber(s("-128")), SimpleNumber(s("-2147483648")), SimpleNumber(s("-9223372036854775808")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[1],
+            "02_number_02_float",
+            "id",
+            vec!["col_decimal", "col_float", "col_double", "updated_at"],
+            vec![
+                vec![SimpleNumber(s("999.99")), SimpleNumber(s("999.99")), SimpleNumber(s("999.99")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[2],
+            "03_number_03_bit",
+            "id",
+            vec!["col_bit", "updated_at"],
+            vec![
+                vec![BitNumber(s("1000000000")), DateString(s("2023-09-01 12:34:56"))],
+                vec![BitNumber(s("0")),          DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[3],
+            "04_date_01_zero",
+            "id",
+            vec!["col_date", "col_datetime", "updated_at"],
+            vec![
+                vec![DateString(s("2020-01-01")), DateString(s("2020-01-01 00:00:00")), DateString(s("2023-09-01 12:34:56"))],
+                vec![DateString(s("0000-00-00")), DateString(s("0000-00-00 00:00:00")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[4],
+            "05_string_01_char",
+            "id",
+            vec!["col_varchar", "col_text", "col_blob", "col_enum", "updated_at"],
+            vec![
+                vec![SimpleString(s("abc")), SimpleString(s("abc")), BinaryString(s("abc")), SimpleString(s("active")), DateString(s("2023-09-01 12:34:56"))],
+                vec![Null,                   Null,                   Null,                   Null,                      DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[5],
+            "06_key_01_unique_not_null",
+            "code",
+            vec!["updated_at"],
+            vec![]
+        );
+
+        assert(
+            &act[6],
+            "07_key_02_multi_unique_not_null_unique_not_null",
+            "code1-code2",
+            vec!["updated_at"],
+            vec![]
+        );
+
+        // 08_key_03_nothing
+
+        // 09_view
+
+        Ok(act)
+    }
+
+    #[test]
+    fn dump_all_57() -> anyhow::Result<()> {
+        let act = dump_common("19003", V57)?;
+
+        assert_eq!(8, act.len());
+
+        assert(
+            &act[7],
+            "10_string_02_json",
+            "id",
+            vec!["col_json", "updated_at"],
+            vec![
+                vec![JsonString(s(r#"{"id": 1, "name": "John"}"#)), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump_all_56() -> anyhow::Result<()> {
+        let act = dump_common("19004", V56)?;
+
+        assert_eq!(7, act.len());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod parse_col_value_tests {
+    use crate::domain::schema::ColSchema;
+    use crate::domain::snapshot::ColValue;
+    use crate::dump::mysql57::parse_col_value;
+    use crate::dump::mysql_version::MysqlVersion;
+    use crate::dump::mysql_version::MysqlVersion::{V56, V57};
+
+    fn sut(version: MysqlVersion, data_type: &str, value: &str) -> ColValue {
+        parse_col_value(&version, &ColSchema { col_name: "col_test".to_string(), data_type: data_type.to_string() }, value.to_string())
+    }
+
+    #[test]
+    fn parse_int() {
+        let exp = "42";
+        assert_eq!(exp, sut(V57, "int", "42").as_display_value());
+        assert_eq!(exp, sut(V56, "int", "42").as_display_value());
+    }
+
+    #[test]
+    fn parse_bit() {
+        let exp = "bit(101)";
+        assert_eq!(exp, sut(V57, "bit", "101").as_display_value());
+        assert_eq!(exp, sut(V56, "bit", "101").as_display_value());
+    }
+
+    #[test]
+    fn parse_zero_date() {
+        let exp = r#""0000-00-00""#;
+        assert_eq!(exp, sut(V57, "date", "0000-00-00").as_display_value());
+        assert_eq!(exp, sut(V56, "date", "0000-00-00").as_display_value());
+    }
+
+    #[test]
+    fn parse_zero_datetime() {
+        let exp = r#""0000-00-00 00:00:00""#;
+        assert_eq!(exp, sut(V57, "datetime", "0000-00-00 00:00:00").as_display_value());
+        assert_eq!(exp, sut(V56, "datetime", "0000-00-00 00:00:00").as_display_value());
+    }
+
+    #[test]
+    fn parse_varchar() {
+        let exp = r#""abc""#;
+        assert_eq!(exp, sut(V57, "varchar", "abc").as_display_value());
+        assert_eq!(exp, sut(V56, "varchar", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_blob() {
+        let exp = "binary";
+        assert_eq!(exp, sut(V57, "blob", "abc").as_display_value());
+        assert_eq!(exp, sut(V56, "blob", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_json() {
+        let exp = r#"{"id": 1, "name": "John"}"#;
+        assert_eq!(exp, sut(V57, "json", r#"{"id": 1, "name": "John"}"#).as_display_value());
+
+        let exp = "parse error";
+        assert_eq!(exp, sut(V56, "json", r#"{"id": 1, "name": "John"}"#).as_display_value());
+    }
+}
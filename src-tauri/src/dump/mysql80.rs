@@ -1,17 +1,16 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
-use anyhow::anyhow;
-use itertools::Itertools;
-use mysql::Value::NULL;
-use mysql::{from_row, from_value, Conn, Value};
+use mysql::Conn;
 
+use crate::domain::db_object::DbObject;
 use crate::domain::dump_config::DumpConfig;
 use crate::domain::project::Project;
-use crate::domain::schema::{ColName, ColSchema, TableName, TableSchema};
-use crate::domain::snapshot::ColValue::*;
-use crate::domain::snapshot::{ColValue, RowSnapshot};
+use crate::domain::schema::{TableName, TableSchema};
+use crate::domain::snapshot::{PrimaryColValues, RowSnapshot};
+use crate::domain::table_definition::TableDefinition;
 use crate::dump::adapter::TargetDbAdapter;
-use crate::logger;
+use crate::dump::mysql_protocol::parse_col_value;
+use crate::dump::{mysql_definition, mysql_protocol};
 
 pub struct TargetDbMysql80 {
     conn: Conn,
@@ -29,111 +28,47 @@ impl TargetDbMysql80 {
 
 impl TargetDbAdapter for TargetDbMysql80 {
     fn get_dump_configs(&mut self) -> anyhow::Result<Vec<DumpConfig>> {
-        let mut map: HashMap<TableName, Vec<ColName>> = HashMap::new();
-
-        let query = format!(
-            "select table_name, column_name from information_schema.columns where table_schema = '{}' order by table_name, ordinal_position",
-            self.schema
-        );
-        logger::info(format!("query: {}", &query));
-
-        let result = self.conn.query(query).map_err(|e| anyhow!(e))?;
-
-        for row in result.map(|x| x.unwrap()) {
-            let (table_name, col_name) = from_row::<(TableName, ColName)>(row);
-            map.entry(table_name).or_default().push(col_name);
-        }
-
-        Ok(map.into_iter().map(|(k, v)| DumpConfig::init(k, v)).collect_vec())
+        mysql_protocol::get_dump_configs(&mut self.conn, &self.schema)
     }
 
     fn get_table_schemata(&mut self) -> anyhow::Result<Vec<TableSchema>> {
         let query = format!("select table_name, column_name, data_type, column_key from information_schema.columns where table_schema = '{}' order by table_name, ordinal_position", self.schema);
 
-        logger::info(format!("query: {}", &query));
-
-        let mut table_names = HashSet::new();
-        let mut map1: HashMap<TableName, Vec<ColSchema>> = HashMap::new();
-        let mut map2: HashMap<TableName, Vec<ColSchema>> = HashMap::new();
-
-        let result = self.conn.query(query).map_err(|e| anyhow!(e))?;
+        mysql_protocol::select_table_schemata(&mut self.conn, &query, &HashSet::new())
+    }
 
-        for row in result.map(|x| x.unwrap()) {
-            let (table_name, col_name, data_type, col_key) = from_row::<(String, String, String, String)>(row);
+    fn get_table_definitions(&mut self) -> anyhow::Result<Vec<TableDefinition>> {
+        mysql_definition::get_table_definitions(&mut self.conn, &self.schema, &["BASE TABLE"])
+    }
 
-            table_names.insert(table_name.clone());
+    fn get_db_objects(&mut self) -> anyhow::Result<Vec<DbObject>> {
+        mysql_definition::get_db_objects(&mut self.conn, &self.schema)
+    }
 
-            if &col_key == "PRI" {
-                map1.entry(table_name).or_default().push(ColSchema { col_name, data_type });
-            } else {
-                map2.entry(table_name).or_default().push(ColSchema { col_name, data_type });
-            }
-        }
+    fn validate_filter(&mut self, table_name: &TableName, filter: &str) -> anyhow::Result<()> {
+        mysql_protocol::validate_filter(&mut self.conn, table_name, filter)
+    }
 
-        let mut table_names = table_names.into_iter().collect_vec();
-        table_names.sort();
-        Ok(table_names
-            .iter()
-            .map(|table_name| TableSchema::new(table_name, map1.get(table_name).unwrap_or(&vec![]), map2.get(table_name).unwrap_or(&vec![])))
-            .collect())
+    fn get_row_snapshots(&mut self, table_schema: &TableSchema, dump_config: &DumpConfig, limit: usize) -> anyhow::Result<Vec<RowSnapshot>> {
+        mysql_protocol::get_row_snapshots(&mut self.conn, table_schema, dump_config, limit, &parse_col_value)
     }
 
-    fn get_row_snapshots(&mut self, table_schema: &TableSchema, dump_config_value: &str) -> anyhow::Result<Vec<RowSnapshot>> {
-        let all_cols = table_schema.get_all_col_refs();
-
-        let col_names = all_cols.iter().map(|col| as_select_col(col)).join(",");
-        let order_by = if dump_config_value == "limited" { "".to_string() } else { format!("order by {dump_config_value}") };
-        let query = format!("select {} from `{}` {} limit 1000", col_names, table_schema.table_name, order_by);
-
-        logger::info(format!("query: {}", &query));
-
-        self.conn
-            .query(query)
-            .map(|result| {
-                result
-                    .map(|x| x.unwrap())
-                    .map(|row| {
-                        let mut primary_cols = vec![];
-                        let mut cols = vec![];
-
-                        for (i, is_primary) in table_schema.get_col_indices() {
-                            let value: Value = row.get(i).unwrap();
-                            let col = if value == NULL { Null } else { parse_col_value(all_cols[i], from_value(value)) };
-                            if is_primary {
-                                primary_cols.push(col);
-                            } else {
-                                cols.push(col);
-                            }
-                        }
-
-                        RowSnapshot::new(primary_cols, cols)
-                    })
-                    .collect()
-            })
-            .map_err(|e| anyhow!(e))
+    fn get_row_snapshots_after(
+        &mut self,
+        table_schema: &TableSchema,
+        dump_config: &DumpConfig,
+        after: Option<&PrimaryColValues>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<RowSnapshot>> {
+        mysql_protocol::get_row_snapshots_after(&mut self.conn, table_schema, dump_config, after, limit, &parse_col_value)
     }
-}
 
-fn as_select_col(col: &ColSchema) -> String {
-    match col.data_type.as_str() {
-        "bit" => format!("bin(`{}`)", col.col_name),
-        _ => format!("`{}`", col.col_name),
+    fn get_row_snapshots_by_keys(&mut self, table_schema: &TableSchema, keys: &[PrimaryColValues]) -> anyhow::Result<Vec<RowSnapshot>> {
+        mysql_protocol::get_row_snapshots_by_keys(&mut self.conn, table_schema, keys, &parse_col_value)
     }
-}
 
-fn parse_col_value(col_schema: &ColSchema, value: String) -> ColValue {
-    match col_schema.data_type.as_str() {
-        "tinyint" | "smallint" | "mediumint" | "int" | "bigint" => SimpleNumber(value),
-        "decimal" | "float" | "double" => SimpleNumber(value),
-        "bit" => BitNumber(value),
-        "date" | "time" | "datetime" | "timestamp" | "year" => DateString(value),
-        "char" | "varchar" => SimpleString(value),
-        "binary" | "varbinary" => BinaryString(value),
-        "tinyblob" | "mediumblob" | "blob" | "longblob" => BinaryString(value),
-        "tinytext" | "mediumtext" | "text" | "longtext" => SimpleString(value),
-        "enum" | "set" => SimpleString(value),
-        "json" => JsonString(value),
-        _ => ParseError,
+    fn execute_statements(&mut self, statements: &[String]) -> anyhow::Result<()> {
+        mysql_protocol::execute_statements(&mut self.conn, statements)
     }
 }
 
@@ -142,7 +77,7 @@ fn parse_col_value(col_schema: &ColSchema, value: String) -> ColValue {
 mod adapter_tests {
     use itertools::Itertools;
 
-    use crate::db::{create_sqlite_connection, migrate_sqlite_if_missing};
+    use crate::db::{create_sqlite_connection, migrate_sqlite};
     use crate::db::project::insert_project;
     use crate::db::snapshot::find_table_snapshots;
     use crate::domain::dump_config::DumpConfig;
@@ -275,7 +210,7 @@ mod adapter_tests {
 
         adapter.conn.prep_exec("create table 30_key_13_nothing ( code int )", ())?;
 
-        migrate_sqlite_if_missing()?;
+        migrate_sqlite()?;
         let conn = create_sqlite_connection()?;
         
         let project_id = create_project_id();
@@ -559,218 +494,3 @@ mod adapter_tests {
         Ok(())
     }
 }
-
-#[cfg(test)]
-mod parse_col_value_tests {
-    use crate::domain::schema::ColSchema;
-    use crate::domain::snapshot::ColValue;
-    use crate::dump::mysql80::parse_col_value;
-
-    fn sut(data_type: &str, value: &str) -> ColValue {
-        parse_col_value(&ColSchema { col_name: "col_test".to_string(), data_type: data_type.to_string() }, value.to_string())
-    }
-
-    #[test]
-    fn parse_i_tinyint() {
-        let exp = "42";
-        assert_eq!(exp, sut("tinyint", "42").as_display_value());
-    }
-
-    #[test]
-    fn parse_u_tinyint() {
-        let exp = "42";
-        assert_eq!(exp, sut("tinyint", "42").as_display_value());
-    }
-
-    #[test]
-    fn parse_i_smallint() {
-        let exp = "42";
-        assert_eq!(exp, sut("smallint", "42").as_display_value());
-    }
-
-    #[test]
-    fn parse_u_smallint() {
-        let exp = "42";
-        assert_eq!(exp, sut("smallint", "42").as_display_value());
-    }
-
-    #[test]
-    fn parse_i_mediumint() {
-        let exp = "42";
-        assert_eq!(exp, sut("mediumint", "42").as_display_value());
-    }
-
-    #[test]
-    fn parse_u_mediumint() {
-        let exp = "42";
-        assert_eq!(exp, sut("mediumint", "42").as_display_value());
-    }
-
-    #[test]
-    fn parse_i_int() {
-        let exp = "42";
-        assert_eq!(exp, sut("int", "42").as_display_value());
-    }
-
-    #[test]
-    fn parse_u_int() {
-        let exp = "42";
-        assert_eq!(exp, sut("int", "42").as_display_value());
-    }
-
-    #[test]
-    fn parse_i_bigint() {
-        let exp = "42";
-        assert_eq!(exp, sut("bigint", "42").as_display_value());
-    }
-
-    #[test]
-    fn parse_u_bigint() {
-        let exp = "42";
-        assert_eq!(exp, sut("bigint", "42").as_display_value());
-    }
-
-    #[test]
-    fn parse_decimal() {
-        let exp = "42.0";
-        assert_eq!(exp, sut("decimal", "42.0").as_display_value());
-    }
-
-    #[test]
-    fn parse_float() {
-        let exp = "42.0";
-        assert_eq!(exp, sut("float", "42.0").as_display_value());
-    }
-
-    #[test]
-    fn parse_double() {
-        let exp = "42.0";
-        assert_eq!(exp, sut("double", "42.0").as_display_value());
-    }
-
-    #[test]
-    fn parse_bit() {
-        let exp = "bit(111)";
-        assert_eq!(exp, sut("bit", "111").as_display_value());
-    }
-
-    #[test]
-    fn parse_date() {
-        let exp = r#""2020-01-01""#;
-        assert_eq!(exp, sut("date", "2020-01-01").as_display_value());
-    }
-
-    #[test]
-    fn parse_time() {
-        let exp = r#""12:34:56""#;
-        assert_eq!(exp, sut("time", "12:34:56").as_display_value());
-    }
-
-    #[test]
-    fn parse_datetime() {
-        let exp = r#""2020-01-01 12:34:56""#;
-        assert_eq!(exp, sut("datetime", "2020-01-01 12:34:56").as_display_value());
-    }
-
-    #[test]
-    fn parse_timestamp() {
-        let exp = r#""2020-01-01 12:34:56""#;
-        assert_eq!(exp, sut("timestamp", "2020-01-01 12:34:56").as_display_value());
-    }
-
-    #[test]
-    fn parse_year() {
-        let exp = r#""2020""#;
-        assert_eq!(exp, sut("year", "2020").as_display_value());
-    }
-
-    #[test]
-    fn parse_char() {
-        let exp = r#""abc""#;
-        assert_eq!(exp, sut("char", "abc").as_display_value());
-    }
-
-    #[test]
-    fn parse_varchar() {
-        let exp = r#""abc""#;
-        assert_eq!(exp, sut("varchar", "abc").as_display_value());
-    }
-
-    #[test]
-    fn parse_binary() {
-        let exp = "binary";
-        assert_eq!(exp, sut("binary", "abc").as_display_value());
-    }
-
-    #[test]
-    fn parse_varbinary() {
-        let exp = "binary";
-        assert_eq!(exp, sut("varbinary", "abc").as_display_value());
-    }
-
-    #[test]
-    fn parse_tinyblob() {
-        let exp = "binary";
-        assert_eq!(exp, sut("tinyblob", "abc").as_display_value());
-    }
-
-    #[test]
-    fn parse_blob() {
-        let exp = "binary";
-        assert_eq!(exp, sut("blob", "abc").as_display_value());
-    }
-
-    #[test]
-    fn parse_mediumblob() {
-        let exp = "binary";
-        assert_eq!(exp, sut("mediumblob", "abc").as_display_value());
-    }
-
-    #[test]
-    fn parse_longblob() {
-        let exp = "binary";
-        assert_eq!(exp, sut("longblob", "abc").as_display_value());
-    }
-
-    #[test]
-    fn parse_tinytext() {
-        let exp = r#""abc""#;
-        assert_eq!(exp, sut("tinytext", "abc").as_display_value());
-    }
-
-    #[test]
-    fn parse_text() {
-        let exp = r#""abc""#;
-        assert_eq!(exp, sut("text", "abc").as_display_value());
-    }
-
-    #[test]
-    fn parse_mediumtext() {
-        let exp = r#""abc""#;
-        assert_eq!(exp, sut("mediumtext", "abc").as_display_value());
-    }
-
-    #[test]
-    fn parse_longtext() {
-        let exp = r#""abc""#;
-        assert_eq!(exp, sut("longtext", "abc").as_display_value());
-    }
-
-    #[test]
-    fn parse_enum() {
-        let exp = r#""abc""#;
-        assert_eq!(exp, sut("enum", "abc").as_display_value());
-    }
-
-    #[test]
-    fn parse_set() {
-        let exp = r#""abc,def""#;
-        assert_eq!(exp, sut("set", "abc,def").as_display_value());
-    }
-
-    #[test]
-    fn parse_json() {
-        let exp = r#"{"id": 1, "name": "John"}"#;
-        assert_eq!(exp, sut("json", r#"{"id": 1, "name": "John"}"#).as_display_value());
-    }
-}
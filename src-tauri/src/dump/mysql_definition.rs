@@ -0,0 +1,165 @@
+use anyhow::anyhow;
+use itertools::Itertools;
+use mysql::{from_row, Conn};
+
+use crate::domain::db_object::{DbObject, DbObjectType};
+use crate::domain::schema::{ColName, TableName};
+use crate::domain::table_definition::{ColDefinition, ForeignKeyDefinition, IndexDefinition, TableDefinition};
+use crate::logger;
+
+// table_name, column_name, column_type, is_nullable, column_default, character_set_name, collation_name
+type ColRow = (TableName, ColName, String, String, Option<String>, Option<String>, Option<String>);
+
+// table_name, index_name, column_name ( 関数インデックスは null ), non_unique
+type IndexRow = (TableName, String, Option<ColName>, i64);
+
+// table_name, constraint_name, column_name, referenced_table_name, referenced_column_name
+type ForeignKeyRow = (TableName, String, ColName, TableName, ColName);
+
+// MySQL 8.0 / 5.7 / 5.6 と MariaDB は information_schema が共通なので、テーブルの定義は同じクエリで取得する
+pub fn get_table_definitions(conn: &mut Conn, schema: &str, table_types: &[&str]) -> anyhow::Result<Vec<TableDefinition>> {
+    let table_types = table_types.iter().map(|table_type| format!("'{table_type}'")).join(", ");
+
+    let query = format!(
+        "select c.table_name, c.column_name, c.column_type, c.is_nullable, c.column_default, c.character_set_name, c.collation_name from information_schema.columns c join information_schema.tables t on c.table_schema = t.table_schema and c.table_name = t.table_name where c.table_schema = '{}' and t.table_type in ({}) order by c.table_name, c.ordinal_position",
+        schema, table_types
+    );
+    logger::info(format!("query: {}", &query));
+    let col_rows = conn.query(query).map_err(|e| anyhow!(e))?.map(|x| from_row::<ColRow>(x.unwrap())).collect_vec();
+
+    let query = format!(
+        "select table_name, index_name, column_name, non_unique from information_schema.statistics where table_schema = '{}' order by table_name, index_name, seq_in_index",
+        schema
+    );
+    logger::info(format!("query: {}", &query));
+    let index_rows = conn.query(query).map_err(|e| anyhow!(e))?.map(|x| from_row::<IndexRow>(x.unwrap())).collect_vec();
+
+    let query = format!(
+        "select table_name, constraint_name, column_name, referenced_table_name, referenced_column_name from information_schema.key_column_usage where table_schema = '{}' and referenced_table_name is not null order by table_name, constraint_name, ordinal_position",
+        schema
+    );
+    logger::info(format!("query: {}", &query));
+    let foreign_key_rows = conn.query(query).map_err(|e| anyhow!(e))?.map(|x| from_row::<ForeignKeyRow>(x.unwrap())).collect_vec();
+
+    Ok(create_table_definitions(col_rows, index_rows, foreign_key_rows))
+}
+
+// 本体だけでなく、トリガーの発火条件や関数の戻り値の型、イベントのスケジュールも 1 行目に含めて比較する
+pub fn get_db_objects(conn: &mut Conn, schema: &str) -> anyhow::Result<Vec<DbObject>> {
+    let queries = [
+        format!("select 'VIEW', table_name, '', view_definition from information_schema.views where table_schema = '{}' order by table_name", schema),
+        format!(
+            "select 'TRIGGER', trigger_name, concat(action_timing, ' ', event_manipulation, ' on ', event_object_table, ' for each row'), action_statement from information_schema.triggers where trigger_schema = '{}' order by trigger_name",
+            schema
+        ),
+        format!(
+            "select routine_type, routine_name, if(routine_type = 'FUNCTION', concat('returns ', dtd_identifier), ''), routine_definition from information_schema.routines where routine_schema = '{}' order by routine_type, routine_name",
+            schema
+        ),
+        format!(
+            "select 'EVENT', event_name, concat(ifnull(concat('every ', interval_value, ' ', interval_field), concat('at ', execute_at)), ' ', status), event_definition from information_schema.events where event_schema = '{}' order by event_name",
+            schema
+        ),
+    ];
+
+    let mut db_objects = vec![];
+    for query in queries {
+        logger::info(format!("query: {}", &query));
+        for row in conn.query(query).map_err(|e| anyhow!(e))? {
+            let (object_type, object_name, header, body) = from_row::<(String, String, Option<String>, Option<String>)>(row.map_err(|e| anyhow!(e))?);
+            let object_type = match object_type.as_str() {
+                "VIEW" => DbObjectType::View,
+                "TRIGGER" => DbObjectType::Trigger,
+                "PROCEDURE" => DbObjectType::Procedure,
+                "FUNCTION" => DbObjectType::Function,
+                _ => DbObjectType::Event,
+            };
+            // 権限がないと本体は null になるので、空の定義として記録する
+            let definition = [header, body].into_iter().flatten().filter(|s| !s.is_empty()).join("\n");
+            db_objects.push(DbObject::new(object_type, &object_name, &definition));
+        }
+    }
+    Ok(db_objects)
+}
+
+// 行はテーブル名の順に並んでいる前提で、テーブルごとにまとめる ( ビューなどカラムの行がないテーブルのインデックスは捨てる )
+fn create_table_definitions(col_rows: Vec<ColRow>, index_rows: Vec<IndexRow>, foreign_key_rows: Vec<ForeignKeyRow>) -> Vec<TableDefinition> {
+    let mut indexes = index_rows.into_iter().into_group_map_by(|row| row.0.clone());
+    let mut foreign_keys = foreign_key_rows.into_iter().into_group_map_by(|row| row.0.clone());
+
+    let mut table_definitions = vec![];
+    for (table_name, col_rows) in &col_rows.into_iter().group_by(|row| row.0.clone()) {
+        let cols = col_rows
+            .map(|(_, col_name, col_type, is_nullable, default, charset, collation)| {
+                ColDefinition::new(&col_name, &col_type, is_nullable == "YES").with_default(default).with_collation(charset, collation)
+            })
+            .collect();
+
+        let mut index_definitions = vec![];
+        for (index_name, rows) in &indexes.remove(&table_name).unwrap_or_default().into_iter().group_by(|row| row.1.clone()) {
+            let rows = rows.collect_vec();
+            index_definitions.push(IndexDefinition {
+                primary: index_name == "PRIMARY",
+                unique: rows[0].3 == 0,
+                col_names: rows.into_iter().map(|row| row.2.unwrap_or_else(|| "<expression>".to_string())).collect(),
+                index_name,
+            });
+        }
+
+        let mut foreign_key_definitions = vec![];
+        for (constraint_name, rows) in &foreign_keys.remove(&table_name).unwrap_or_default().into_iter().group_by(|row| row.1.clone()) {
+            let rows = rows.collect_vec();
+            foreign_key_definitions.push(ForeignKeyDefinition {
+                constraint_name,
+                col_names: rows.iter().map(|row| row.2.clone()).collect(),
+                ref_table_name: rows[0].3.clone(),
+                ref_col_names: rows.iter().map(|row| row.4.clone()).collect(),
+            });
+        }
+
+        table_definitions.push(TableDefinition { table_name, cols, indexes: index_definitions, foreign_keys: foreign_key_definitions });
+    }
+    table_definitions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dump::mysql_definition::create_table_definitions;
+
+    fn s(s: &str) -> String {
+        s.to_string()
+    }
+
+    #[test]
+    fn table_definitions() {
+        let col_rows = vec![
+            (s("groups"), s("id"), s("int"), s("NO"), None, None, None),
+            (s("users"), s("id"), s("int unsigned"), s("NO"), None, None, None),
+            (s("users"), s("name"), s("varchar(255)"), s("YES"), Some(s("guest")), Some(s("utf8mb4")), Some(s("utf8mb4_bin"))),
+            (s("users"), s("group_id"), s("int"), s("YES"), None, None, None),
+        ];
+        let index_rows = vec![
+            (s("groups"), s("PRIMARY"), Some(s("id")), 0),
+            (s("users"), s("PRIMARY"), Some(s("id")), 0),
+            (s("users"), s("idx_group_name"), Some(s("group_id")), 1),
+            (s("users"), s("idx_group_name"), Some(s("name")), 1),
+            (s("users_view"), s("PRIMARY"), Some(s("id")), 0),
+        ];
+        let foreign_key_rows = vec![(s("users"), s("fk_group"), s("group_id"), s("groups"), s("id"))];
+
+        let act = create_table_definitions(col_rows, index_rows, foreign_key_rows);
+
+        assert_eq!(vec!["groups", "users"], act.iter().map(|definition| definition.table_name.as_str()).collect::<Vec<_>>());
+
+        let users = &act[1];
+        assert_eq!(
+            vec!["int unsigned not null", "varchar(255) default guest charset utf8mb4 collate utf8mb4_bin", "int"],
+            users.cols.iter().map(|col| col.as_ddl()).collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["primary key (id)", "index (group_id, name)"], users.indexes.iter().map(|index| index.as_ddl()).collect::<Vec<_>>());
+        assert_eq!(
+            vec!["foreign key (group_id) references groups (id)"],
+            users.foreign_keys.iter().map(|foreign_key| foreign_key.as_ddl()).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,424 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::anyhow;
+use itertools::Itertools;
+use mysql::Value::NULL;
+use mysql::{from_row, from_value, Conn, Value};
+
+use crate::domain::dump_config::DumpConfig;
+use crate::domain::schema::{ColName, ColSchema, TableName, TableSchema};
+use crate::domain::snapshot::ColValue::*;
+use crate::domain::snapshot::{ColValue, PrimaryColValues, RowSnapshot};
+use crate::dump::adapter::{create_key_condition, create_keyset_condition, create_where_clause};
+use crate::logger;
+
+// MySQL 8.0、5.7 ( 5.6 ) 、MariaDB のアダプタで共通する、行の取得と SQL の実行
+// 値の読み方はバージョンごとに異なるので、各アダプタが parse_col_value を渡す
+
+pub fn get_dump_configs(conn: &mut Conn, schema: &str) -> anyhow::Result<Vec<DumpConfig>> {
+    let mut map: HashMap<TableName, Vec<ColName>> = HashMap::new();
+
+    let query = format!(
+        "select table_name, column_name from information_schema.columns where table_schema = '{}' order by table_name, ordinal_position",
+        schema
+    );
+    logger::info(format!("query: {}", &query));
+
+    let result = conn.query(query).map_err(|e| anyhow!(e))?;
+
+    for row in result.map(|x| x.unwrap()) {
+        let (table_name, col_name) = from_row::<(TableName, ColName)>(row);
+        map.entry(table_name).or_default().push(col_name);
+    }
+
+    Ok(map.into_iter().map(|(k, v)| DumpConfig::init(k, v)).collect_vec())
+}
+
+// query は table_name, column_name, data_type, column_key の順に返す ( json_cols に含まれるカラムは json 型として扱う )
+pub fn select_table_schemata(conn: &mut Conn, query: &str, json_cols: &HashSet<(TableName, ColName)>) -> anyhow::Result<Vec<TableSchema>> {
+    logger::info(format!("query: {}", query));
+
+    let mut table_names = HashSet::new();
+    let mut map1: HashMap<TableName, Vec<ColSchema>> = HashMap::new();
+    let mut map2: HashMap<TableName, Vec<ColSchema>> = HashMap::new();
+
+    let result = conn.query(query).map_err(|e| anyhow!(e))?;
+
+    for row in result.map(|x| x.unwrap()) {
+        let (table_name, col_name, data_type, col_key) = from_row::<(String, String, String, String)>(row);
+
+        table_names.insert(table_name.clone());
+
+        let data_type = if json_cols.contains(&(table_name.clone(), col_name.clone())) { "json".to_string() } else { data_type };
+
+        if &col_key == "PRI" {
+            map1.entry(table_name).or_default().push(ColSchema { col_name, data_type });
+        } else {
+            map2.entry(table_name).or_default().push(ColSchema { col_name, data_type });
+        }
+    }
+
+    let mut table_names = table_names.into_iter().collect_vec();
+    table_names.sort();
+    Ok(table_names
+        .iter()
+        .map(|table_name| TableSchema::new(table_name, map1.get(table_name).unwrap_or(&vec![]), map2.get(table_name).unwrap_or(&vec![])))
+        .collect())
+}
+
+pub fn validate_filter(conn: &mut Conn, table_name: &TableName, filter: &str) -> anyhow::Result<()> {
+    let query = format!("select 1 from `{}` where ({}) limit 0", table_name, filter);
+
+    logger::info(format!("query: {}", &query));
+
+    conn.query(query).map(|_| ()).map_err(|e| anyhow!(e))
+}
+
+pub fn get_row_snapshots(
+    conn: &mut Conn,
+    table_schema: &TableSchema,
+    dump_config: &DumpConfig,
+    limit: usize,
+    parse_col_value: &dyn Fn(&ColSchema, String) -> ColValue,
+) -> anyhow::Result<Vec<RowSnapshot>> {
+    let condition = create_where_clause(vec![dump_config.get_filter().map(String::from)]);
+    let order_by = if dump_config.value == "limited" || dump_config.is_all() { "".to_string() } else { format!("order by {}", dump_config.value) };
+
+    select_row_snapshots(conn, table_schema, &condition, &order_by, limit, parse_col_value)
+}
+
+pub fn get_row_snapshots_after(
+    conn: &mut Conn,
+    table_schema: &TableSchema,
+    dump_config: &DumpConfig,
+    after: Option<&PrimaryColValues>,
+    limit: usize,
+    parse_col_value: &dyn Fn(&ColSchema, String) -> ColValue,
+) -> anyhow::Result<Vec<RowSnapshot>> {
+    let keyset_condition = match after {
+        Some(after) => Some(create_keyset_condition(&table_schema.primary_cols, after, quote_col_name, true)?),
+        None => None,
+    };
+    let condition = create_where_clause(vec![dump_config.get_filter().map(String::from), keyset_condition]);
+    let order_by = format!("order by {}", table_schema.primary_cols.iter().map(|col| quote_col_name(&col.col_name)).join(","));
+
+    select_row_snapshots(conn, table_schema, &condition, &order_by, limit, parse_col_value)
+}
+
+pub fn get_row_snapshots_by_keys(
+    conn: &mut Conn,
+    table_schema: &TableSchema,
+    keys: &[PrimaryColValues],
+    parse_col_value: &dyn Fn(&ColSchema, String) -> ColValue,
+) -> anyhow::Result<Vec<RowSnapshot>> {
+    let condition = create_where_clause(vec![Some(create_key_condition(&table_schema.primary_cols, keys, quote_col_name, true)?)]);
+    let order_by = format!("order by {}", table_schema.primary_cols.iter().map(|col| quote_col_name(&col.col_name)).join(","));
+
+    select_row_snapshots(conn, table_schema, &condition, &order_by, keys.len(), parse_col_value)
+}
+
+pub fn execute_statements(conn: &mut Conn, statements: &[String]) -> anyhow::Result<()> {
+    // 外部キーの参照順に関係なく実行できるように、実行する間だけ外部キーの確認を止める
+    conn.query("set foreign_key_checks = 0").map_err(|e| anyhow!(e))?;
+
+    let result = execute_in_transaction(conn, statements);
+
+    conn.query("set foreign_key_checks = 1").map_err(|e| anyhow!(e))?;
+
+    result
+}
+
+fn select_row_snapshots(
+    conn: &mut Conn,
+    table_schema: &TableSchema,
+    condition: &str,
+    order_by: &str,
+    limit: usize,
+    parse_col_value: &dyn Fn(&ColSchema, String) -> ColValue,
+) -> anyhow::Result<Vec<RowSnapshot>> {
+    let all_cols = table_schema.get_all_col_refs();
+
+    let col_names = all_cols.iter().map(|col| as_select_col(col)).join(",");
+    let query = format!("select {} from `{}` {} {} limit {}", col_names, table_schema.table_name, condition, order_by, limit);
+
+    logger::info(format!("query: {}", &query));
+
+    conn.query(query)
+        .map(|result| {
+            result
+                .map(|x| x.unwrap())
+                .map(|row| {
+                    let mut primary_cols = vec![];
+                    let mut cols = vec![];
+
+                    for (i, is_primary) in table_schema.get_col_indices() {
+                        let value: Value = row.get(i).unwrap();
+                        let col = if value == NULL { Null } else { parse_col_value(all_cols[i], from_value(value)) };
+                        if is_primary {
+                            primary_cols.push(col);
+                        } else {
+                            cols.push(col);
+                        }
+                    }
+
+                    RowSnapshot::new(primary_cols, cols)
+                })
+                .collect()
+        })
+        .map_err(|e| anyhow!(e))
+}
+
+// 途中で失敗した場合は、トランザクションが破棄されてロールバックされる
+fn execute_in_transaction(conn: &mut Conn, statements: &[String]) -> anyhow::Result<()> {
+    let mut transaction = conn.start_transaction(false, None, None).map_err(|e| anyhow!(e))?;
+
+    for statement in statements {
+        logger::info(format!("query: {}", statement));
+
+        transaction.query(statement).map_err(|e| anyhow!(e))?;
+    }
+
+    transaction.commit().map_err(|e| anyhow!(e))
+}
+
+fn quote_col_name(col_name: &str) -> String {
+    format!("`{col_name}`")
+}
+
+fn as_select_col(col: &ColSchema) -> String {
+    match col.data_type.as_str() {
+        "bit" => format!("bin(`{}`)", col.col_name),
+        _ => format!("`{}`", col.col_name),
+    }
+}
+
+pub fn parse_col_value(col_schema: &ColSchema, value: String) -> ColValue {
+    match col_schema.data_type.as_str() {
+        "tinyint" | "smallint" | "mediumint" | "int" | "bigint" => SimpleNumber(value),
+        "decimal" | "float" | "double" => SimpleNumber(value),
+        "bit" => BitNumber(value),
+        "date" | "time" | "datetime" | "timestamp" | "year" => DateString(value),
+        "char" | "varchar" => SimpleString(value),
+        "binary" | "varbinary" => BinaryString(value),
+        "tinyblob" | "mediumblob" | "blob" | "longblob" => BinaryString(value),
+        "tinytext" | "mediumtext" | "text" | "longtext" => SimpleString(value),
+        "enum" | "set" => SimpleString(value),
+        "json" => JsonString(value),
+        _ => ParseError,
+    }
+}
+
+#[cfg(test)]
+mod parse_col_value_tests {
+    use crate::domain::schema::ColSchema;
+    use crate::domain::snapshot::ColValue;
+    use crate::dump::mysql_protocol::parse_col_value;
+
+    fn sut(data_type: &str, value: &str) -> ColValue {
+        parse_col_value(&ColSchema { col_name: "col_test".to_string(), data_type: data_type.to_string() }, value.to_string())
+    }
+
+    #[test]
+    fn parse_i_tinyint() {
+        let exp = "42";
+        assert_eq!(exp, sut("tinyint", "42").as_display_value());
+    }
+
+    #[test]
+    fn parse_u_tinyint() {
+        let exp = "42";
+        assert_eq!(exp, sut("tinyint", "42").as_display_value());
+    }
+
+    #[test]
+    fn parse_i_smallint() {
+        let exp = "42";
+        assert_eq!(exp, sut("smallint", "42").as_display_value());
+    }
+
+    #[test]
+    fn parse_u_smallint() {
+        let exp = "42";
+        assert_eq!(exp, sut("smallint", "42").as_display_value());
+    }
+
+    #[test]
+    fn parse_i_mediumint() {
+        let exp = "42";
+        assert_eq!(exp, sut("mediumint", "42").as_display_value());
+    }
+
+    #[test]
+    fn parse_u_mediumint() {
+        let exp = "42";
+        assert_eq!(exp, sut("mediumint", "42").as_display_value());
+    }
+
+    #[test]
+    fn parse_i_int() {
+        let exp = "42";
+        assert_eq!(exp, sut("int", "42").as_display_value());
+    }
+
+    #[test]
+    fn parse_u_int() {
+        let exp = "42";
+        assert_eq!(exp, sut("int", "42").as_display_value());
+    }
+
+    #[test]
+    fn parse_i_bigint() {
+        let exp = "42";
+        assert_eq!(exp, sut("bigint", "42").as_display_value());
+    }
+
+    #[test]
+    fn parse_u_bigint() {
+        let exp = "42";
+        assert_eq!(exp, sut("bigint", "42").as_display_value());
+    }
+
+    #[test]
+    fn parse_decimal() {
+        let exp = "42.0";
+        assert_eq!(exp, sut("decimal", "42.0").as_display_value());
+    }
+
+    #[test]
+    fn parse_float() {
+        let exp = "42.0";
+        assert_eq!(exp, sut("float", "42.0").as_display_value());
+    }
+
+    #[test]
+    fn parse_double() {
+        let exp = "42.0";
+        assert_eq!(exp, sut("double", "42.0").as_display_value());
+    }
+
+    #[test]
+    fn parse_bit() {
+        let exp = "bit(111)";
+        assert_eq!(exp, sut("bit", "111").as_display_value());
+    }
+
+    #[test]
+    fn parse_date() {
+        let exp = r#""2020-01-01""#;
+        assert_eq!(exp, sut("date", "2020-01-01").as_display_value());
+    }
+
+    #[test]
+    fn parse_time() {
+        let exp = r#""12:34:56""#;
+        assert_eq!(exp, sut("time", "12:34:56").as_display_value());
+    }
+
+    #[test]
+    fn parse_datetime() {
+        let exp = r#""2020-01-01 12:34:56""#;
+        assert_eq!(exp, sut("datetime", "2020-01-01 12:34:56").as_display_value());
+    }
+
+    #[test]
+    fn parse_timestamp() {
+        let exp = r#""2020-01-01 12:34:56""#;
+        assert_eq!(exp, sut("timestamp", "2020-01-01 12:34:56").as_display_value());
+    }
+
+    #[test]
+    fn parse_year() {
+        let exp = r#""2020""#;
+        assert_eq!(exp, sut("year", "2020").as_display_value());
+    }
+
+    #[test]
+    fn parse_char() {
+        let exp = r#""abc""#;
+        assert_eq!(exp, sut("char", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_varchar() {
+        let exp = r#""abc""#;
+        assert_eq!(exp, sut("varchar", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_binary() {
+        let exp = "binary";
+        assert_eq!(exp, sut("binary", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_varbinary() {
+        let exp = "binary";
+        assert_eq!(exp, sut("varbinary", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_tinyblob() {
+        let exp = "binary";
+        assert_eq!(exp, sut("tinyblob", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_blob() {
+        let exp = "binary";
+        assert_eq!(exp, sut("blob", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_mediumblob() {
+        let exp = "binary";
+        assert_eq!(exp, sut("mediumblob", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_longblob() {
+        let exp = "binary";
+        assert_eq!(exp, sut("longblob", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_tinytext() {
+        let exp = r#""abc""#;
+        assert_eq!(exp, sut("tinytext", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_text() {
+        let exp = r#""abc""#;
+        assert_eq!(exp, sut("text", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_mediumtext() {
+        let exp = r#""abc""#;
+        assert_eq!(exp, sut("mediumtext", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_longtext() {
+        let exp = r#""abc""#;
+        assert_eq!(exp, sut("longtext", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_enum() {
+        let exp = r#""abc""#;
+        assert_eq!(exp, sut("enum", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_set() {
+        let exp = r#""abc,def""#;
+        assert_eq!(exp, sut("set", "abc,def").as_display_value());
+    }
+
+    #[test]
+    fn parse_json() {
+        let exp = r#"{"id": 1, "name": "John"}"#;
+        assert_eq!(exp, sut("json", r#"{"id": 1, "name": "John"}"#).as_display_value());
+    }
+}
@@ -0,0 +1,62 @@
+use anyhow::anyhow;
+use mysql::from_row;
+
+use crate::domain::project::Project;
+use crate::logger;
+
+#[derive(Eq, PartialEq, Debug)]
+pub enum MysqlVersion {
+    V80,
+    V57,
+    V56,
+}
+
+impl MysqlVersion {
+    pub fn detect(project: &Project) -> anyhow::Result<Self> {
+        let mut conn = project.create_connection()?;
+
+        let query = "select version()";
+        logger::info(format!("query: {}", &query));
+
+        let version = match conn.query(query).map_err(|e| anyhow!(e))?.map(|x| x.unwrap()).next() {
+            Some(row) => from_row::<String>(row),
+            None => return Err(anyhow!("version not found")),
+        };
+        logger::info(format!("version: {}", &version));
+
+        Self::parse(&version)
+    }
+
+    fn parse(version: &str) -> anyhow::Result<Self> {
+        if version.contains("MariaDB") {
+            return Err(anyhow!("MariaDB is detected, select MariaDB as the system of the project: {}", version));
+        }
+
+        let mut numbers = version.split(|c: char| !c.is_ascii_digit()).map(|s| s.parse::<u32>().ok());
+
+        match (numbers.next().flatten(), numbers.next().flatten()) {
+            (Some(major), _) if 8 <= major => Ok(Self::V80),
+            (Some(5), Some(7)) => Ok(Self::V57),
+            (Some(5), Some(6)) => Ok(Self::V56),
+            _ => Err(anyhow!("unsupported MySQL version: {}", version)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dump::mysql_version::MysqlVersion;
+    use crate::dump::mysql_version::MysqlVersion::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(V80, MysqlVersion::parse("8.0.34").unwrap());
+        assert_eq!(V80, MysqlVersion::parse("8.1.0").unwrap());
+        assert_eq!(V57, MysqlVersion::parse("5.7.43-log").unwrap());
+        assert_eq!(V56, MysqlVersion::parse("5.6.51").unwrap());
+
+        assert!(MysqlVersion::parse("5.5.62").is_err());
+        assert!(MysqlVersion::parse("10.11.2-MariaDB-1:10.11.2+maria~ubu2204").is_err());
+        assert!(MysqlVersion::parse("").is_err());
+    }
+}
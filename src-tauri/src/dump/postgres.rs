@@ -0,0 +1,853 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::anyhow;
+use itertools::Itertools;
+use postgres::Client;
+
+use crate::domain::db_object::{DbObject, DbObjectType};
+use crate::domain::dump_config::DumpConfig;
+use crate::domain::project::Project;
+use crate::domain::schema::{ColName, ColSchema, TableName, TableSchema};
+use crate::domain::snapshot::ColValue::*;
+use crate::domain::snapshot::{ColValue, PrimaryColValues, RowSnapshot};
+use crate::domain::table_definition::{ColDefinition, ForeignKeyDefinition, IndexDefinition, TableDefinition};
+use crate::dump::adapter::{create_key_condition, create_keyset_condition, create_where_clause, TargetDbAdapter};
+use crate::logger;
+
+pub struct TargetDbPostgres {
+    client: Client,
+}
+
+impl TargetDbPostgres {
+    pub fn new(project: &Project) -> anyhow::Result<Self> {
+        let mut client = project.create_client()?;
+
+        // timestamptz や interval の文字列表現がセッションの設定に左右されないように固定する
+        client.batch_execute("set time zone 'UTC'; set intervalstyle = 'postgres'; set datestyle = 'ISO, YMD'").map_err(|e| anyhow!(e))?;
+
+        Ok(Self { client })
+    }
+
+    fn get_key_constraints(&mut self) -> anyhow::Result<HashMap<TableName, Vec<KeyConstraint>>> {
+        let query = "select cls.relname::text, con.contype::text, con.oid::bigint, att.attname::text
+            from pg_constraint con
+            join pg_class cls on cls.oid = con.conrelid
+            join pg_namespace nsp on nsp.oid = cls.relnamespace
+            cross join unnest(con.conkey) with ordinality as k(attnum, ord)
+            join pg_attribute att on att.attrelid = con.conrelid and att.attnum = k.attnum
+            where nsp.nspname = current_schema() and con.contype in ('p', 'u')
+            order by cls.relname, con.contype, con.oid, k.ord";
+
+        logger::info(format!("query: {}", &query));
+
+        let mut map: HashMap<TableName, Vec<KeyConstraint>> = HashMap::new();
+
+        for row in self.client.query(query, &[]).map_err(|e| anyhow!(e))? {
+            let (table_name, contype, oid, col_name): (String, String, i64, String) = (row.get(0), row.get(1), row.get(2), row.get(3));
+
+            let constraints = map.entry(table_name).or_default();
+            match constraints.last_mut() {
+                Some(constraint) if constraint.oid == oid => constraint.col_names.push(col_name),
+                _ => constraints.push(KeyConstraint { oid, primary: contype == "p", col_names: vec![col_name] }),
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+impl TargetDbAdapter for TargetDbPostgres {
+    fn get_dump_configs(&mut self) -> anyhow::Result<Vec<DumpConfig>> {
+        let mut map: HashMap<TableName, Vec<ColName>> = HashMap::new();
+
+        let query = "select table_name::text, column_name::text from information_schema.columns where table_schema = current_schema() order by table_name, ordinal_position";
+        logger::info(format!("query: {}", &query));
+
+        for row in self.client.query(query, &[]).map_err(|e| anyhow!(e))? {
+            let (table_name, col_name): (TableName, ColName) = (row.get(0), row.get(1));
+            map.entry(table_name).or_default().push(col_name);
+        }
+
+        Ok(map.into_iter().map(|(k, v)| DumpConfig::init(k, v)).collect_vec())
+    }
+
+    fn get_table_schemata(&mut self) -> anyhow::Result<Vec<TableSchema>> {
+        let key_constraints = self.get_key_constraints()?;
+
+        let query = "select table_name::text, column_name::text, data_type::text, is_nullable::text from information_schema.columns where table_schema = current_schema() order by table_name, ordinal_position";
+
+        logger::info(format!("query: {}", &query));
+
+        let mut table_names = HashSet::new();
+        let mut cols: HashMap<TableName, Vec<(ColSchema, bool)>> = HashMap::new();
+
+        for row in self.client.query(query, &[]).map_err(|e| anyhow!(e))? {
+            let (table_name, col_name, data_type, is_nullable): (String, String, String, String) = (row.get(0), row.get(1), row.get(2), row.get(3));
+
+            table_names.insert(table_name.clone());
+            cols.entry(table_name).or_default().push((ColSchema { col_name, data_type }, is_nullable == "NO"));
+        }
+
+        let mut table_names = table_names.into_iter().collect_vec();
+        table_names.sort();
+        Ok(table_names
+            .iter()
+            .map(|table_name| {
+                let cols = cols.get(table_name).unwrap();
+                let not_null_col_names = cols.iter().filter(|(_, not_null)| *not_null).map(|(col, _)| &col.col_name).collect();
+                let primary_col_names =
+                    find_primary_col_names(key_constraints.get(table_name).map(Vec::as_slice).unwrap_or(&[]), &not_null_col_names);
+
+                let (primary_cols, cols): (Vec<_>, Vec<_>) =
+                    cols.iter().map(|(col, _)| col.clone()).partition(|col| primary_col_names.contains(&&col.col_name));
+                TableSchema::new(table_name, &primary_cols, &cols)
+            })
+            .collect())
+    }
+
+    fn get_table_definitions(&mut self) -> anyhow::Result<Vec<TableDefinition>> {
+        // 照合順序は、型の既定と異なる場合だけ記録する
+        let query = "select cls.relname::text, att.attname::text, format_type(att.atttypid, att.atttypmod)::text, not att.attnotnull,
+                pg_get_expr(def.adbin, def.adrelid)::text, case when att.attcollation <> typ.typcollation then coll.collname::text end
+            from pg_attribute att
+            join pg_class cls on cls.oid = att.attrelid
+            join pg_namespace nsp on nsp.oid = cls.relnamespace
+            join pg_type typ on typ.oid = att.atttypid
+            left join pg_attrdef def on def.adrelid = att.attrelid and def.adnum = att.attnum
+            left join pg_collation coll on coll.oid = att.attcollation
+            where nsp.nspname = current_schema() and cls.relkind in ('r', 'p') and att.attnum > 0 and not att.attisdropped
+            order by cls.relname, att.attnum";
+
+        logger::info(format!("query: {}", &query));
+
+        let mut table_definitions: Vec<TableDefinition> = vec![];
+        for row in self.client.query(query, &[]).map_err(|e| anyhow!(e))? {
+            let (table_name, col_name, col_type, nullable): (String, String, String, bool) = (row.get(0), row.get(1), row.get(2), row.get(3));
+            let col = ColDefinition::new(&col_name, &col_type, nullable).with_default(row.get(4)).with_collation(None, row.get(5));
+
+            match table_definitions.last_mut() {
+                Some(table_definition) if table_definition.table_name == table_name => table_definition.cols.push(col),
+                _ => table_definitions.push(TableDefinition { table_name, cols: vec![col], indexes: vec![], foreign_keys: vec![] }),
+            }
+        }
+
+        let query = "select cls.relname::text, idx.relname::text, ind.indisprimary, ind.indisunique,
+                array(select pg_get_indexdef(ind.indexrelid, k, true) from generate_series(1, ind.indnatts) k order by k)::text[]
+            from pg_index ind
+            join pg_class idx on idx.oid = ind.indexrelid
+            join pg_class cls on cls.oid = ind.indrelid
+            join pg_namespace nsp on nsp.oid = cls.relnamespace
+            where nsp.nspname = current_schema() and cls.relkind in ('r', 'p')
+            order by cls.relname, idx.relname";
+
+        logger::info(format!("query: {}", &query));
+
+        for row in self.client.query(query, &[]).map_err(|e| anyhow!(e))? {
+            let table_name: String = row.get(0);
+            let index = IndexDefinition { index_name: row.get(1), primary: row.get(2), unique: row.get(3), col_names: row.get(4) };
+
+            if let Some(table_definition) = table_definitions.iter_mut().find(|table_definition| table_definition.table_name == table_name) {
+                table_definition.indexes.push(index);
+            }
+        }
+
+        let query = "select cls.relname::text, con.conname::text,
+                array(select att.attname::text from unnest(con.conkey) with ordinality as k(attnum, ord) join pg_attribute att on att.attrelid = con.conrelid and att.attnum = k.attnum order by k.ord),
+                ref.relname::text,
+                array(select att.attname::text from unnest(con.confkey) with ordinality as k(attnum, ord) join pg_attribute att on att.attrelid = con.confrelid and att.attnum = k.attnum order by k.ord)
+            from pg_constraint con
+            join pg_class cls on cls.oid = con.conrelid
+            join pg_class ref on ref.oid = con.confrelid
+            join pg_namespace nsp on nsp.oid = cls.relnamespace
+            where nsp.nspname = current_schema() and con.contype = 'f'
+            order by cls.relname, con.conname";
+
+        logger::info(format!("query: {}", &query));
+
+        for row in self.client.query(query, &[]).map_err(|e| anyhow!(e))? {
+            let table_name: String = row.get(0);
+            let foreign_key =
+                ForeignKeyDefinition { constraint_name: row.get(1), col_names: row.get(2), ref_table_name: row.get(3), ref_col_names: row.get(4) };
+
+            if let Some(table_definition) = table_definitions.iter_mut().find(|table_definition| table_definition.table_name == table_name) {
+                table_definition.foreign_keys.push(foreign_key);
+            }
+        }
+
+        Ok(table_definitions)
+    }
+
+    fn get_db_objects(&mut self) -> anyhow::Result<Vec<DbObject>> {
+        // 関数はオーバーロードできるので引数の型も名前に含め、拡張機能が作成した関数は含めない
+        let query = "select 'view', cls.relname::text, pg_get_viewdef(cls.oid, true)
+            from pg_class cls
+            join pg_namespace nsp on nsp.oid = cls.relnamespace
+            where nsp.nspname = current_schema() and cls.relkind in ('v', 'm')
+            union all
+            select 'trigger', cls.relname::text || '.' || tg.tgname::text, pg_get_triggerdef(tg.oid, true)
+            from pg_trigger tg
+            join pg_class cls on cls.oid = tg.tgrelid
+            join pg_namespace nsp on nsp.oid = cls.relnamespace
+            where nsp.nspname = current_schema() and not tg.tgisinternal
+            union all
+            select case pro.prokind when 'p' then 'procedure' else 'function' end, pro.proname::text || '(' || pg_get_function_identity_arguments(pro.oid) || ')', pg_get_functiondef(pro.oid)
+            from pg_proc pro
+            join pg_namespace nsp on nsp.oid = pro.pronamespace
+            where nsp.nspname = current_schema() and pro.prokind in ('f', 'p')
+                and not exists (select 1 from pg_depend dep where dep.objid = pro.oid and dep.deptype = 'e')
+            order by 1, 2";
+
+        logger::info(format!("query: {}", &query));
+
+        Ok(self
+            .client
+            .query(query, &[])
+            .map_err(|e| anyhow!(e))?
+            .iter()
+            .map(|row| {
+                let (object_type, object_name, definition): (String, String, String) = (row.get(0), row.get(1), row.get(2));
+                let object_type = match object_type.as_str() {
+                    "view" => DbObjectType::View,
+                    "trigger" => DbObjectType::Trigger,
+                    "procedure" => DbObjectType::Procedure,
+                    _ => DbObjectType::Function,
+                };
+                DbObject::new(object_type, &object_name, &definition)
+            })
+            .collect())
+    }
+
+    fn validate_filter(&mut self, table_name: &TableName, filter: &str) -> anyhow::Result<()> {
+        let query = format!(r#"select 1 from "{}" where ({}) limit 0"#, table_name, filter);
+
+        logger::info(format!("query: {}", &query));
+
+        self.client.query(&query, &[]).map(|_| ()).map_err(|e| anyhow!(e))
+    }
+
+    fn get_row_snapshots(&mut self, table_schema: &TableSchema, dump_config: &DumpConfig, limit: usize) -> anyhow::Result<Vec<RowSnapshot>> {
+        let condition = create_where_clause(vec![dump_config.get_filter().map(String::from)]);
+        let order_by =
+            if dump_config.value == "limited" || dump_config.is_all() { "".to_string() } else { format!(r#"order by "{}""#, dump_config.value) };
+
+        self.select_row_snapshots(table_schema, &condition, &order_by, limit)
+    }
+
+    fn get_row_snapshots_after(
+        &mut self,
+        table_schema: &TableSchema,
+        dump_config: &DumpConfig,
+        after: Option<&PrimaryColValues>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<RowSnapshot>> {
+        let keyset_condition = match after {
+            Some(after) => Some(create_keyset_condition(&table_schema.primary_cols, after, quote_col_name, false)?),
+            None => None,
+        };
+        let condition = create_where_clause(vec![dump_config.get_filter().map(String::from), keyset_condition]);
+        let order_by = format!("order by {}", table_schema.primary_cols.iter().map(|col| quote_col_name(&col.col_name)).join(","));
+
+        self.select_row_snapshots(table_schema, &condition, &order_by, limit)
+    }
+
+    fn get_row_snapshots_by_keys(&mut self, table_schema: &TableSchema, keys: &[PrimaryColValues]) -> anyhow::Result<Vec<RowSnapshot>> {
+        let condition = create_where_clause(vec![Some(create_key_condition(&table_schema.primary_cols, keys, quote_col_name, false)?)]);
+        let order_by = format!("order by {}", table_schema.primary_cols.iter().map(|col| quote_col_name(&col.col_name)).join(","));
+
+        self.select_row_snapshots(table_schema, &condition, &order_by, keys.len())
+    }
+
+    fn execute_statements(&mut self, statements: &[String]) -> anyhow::Result<()> {
+        let mut transaction = self.client.transaction().map_err(|e| anyhow!(e))?;
+
+        // 文は外部キーの順に並んでいるが、遅延可能な外部キーはコミット時にまとめて確認する
+        transaction.batch_execute("set constraints all deferred").map_err(|e| anyhow!(e))?;
+
+        for statement in statements {
+            logger::info(format!("query: {}", statement));
+
+            transaction.batch_execute(statement).map_err(|e| anyhow!(e))?;
+        }
+
+        transaction.commit().map_err(|e| anyhow!(e))
+    }
+}
+
+impl TargetDbPostgres {
+    fn select_row_snapshots(
+        &mut self,
+        table_schema: &TableSchema,
+        condition: &str,
+        order_by: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<RowSnapshot>> {
+        let all_cols = table_schema.get_all_col_refs();
+
+        let col_names = all_cols.iter().map(|col| format!(r#""{}"::text"#, col.col_name)).join(",");
+        let query = format!(r#"select {} from "{}" {} {} limit {}"#, col_names, table_schema.table_name, condition, order_by, limit);
+
+        logger::info(format!("query: {}", &query));
+
+        self.client
+            .query(&query, &[])
+            .map(|rows| {
+                rows.iter()
+                    .map(|row| {
+                        let mut primary_cols = vec![];
+                        let mut cols = vec![];
+
+                        for (i, is_primary) in table_schema.get_col_indices() {
+                            let value: Option<String> = row.get(i);
+                            let col = match value {
+                                Some(value) => parse_col_value(all_cols[i], value),
+                                None => Null,
+                            };
+                            if is_primary {
+                                primary_cols.push(col);
+                            } else {
+                                cols.push(col);
+                            }
+                        }
+
+                        RowSnapshot::new(primary_cols, cols)
+                    })
+                    .collect()
+            })
+            .map_err(|e| anyhow!(e))
+    }
+}
+
+fn quote_col_name(col_name: &str) -> String {
+    format!(r#""{col_name}""#)
+}
+
+struct KeyConstraint {
+    oid: i64,
+    primary: bool,
+    col_names: Vec<ColName>,
+}
+
+// 主キー制約があればそれを、なければ全カラムが Not Null である最初の一意制約を主キーとみなす
+fn find_primary_col_names<'a>(key_constraints: &'a [KeyConstraint], not_null_col_names: &HashSet<&ColName>) -> Vec<&'a ColName> {
+    key_constraints
+        .iter()
+        .find(|constraint| constraint.primary)
+        .or_else(|| key_constraints.iter().find(|constraint| constraint.col_names.iter().all(|col_name| not_null_col_names.contains(col_name))))
+        .map(|constraint| constraint.col_names.iter().collect())
+        .unwrap_or_default()
+}
+
+fn parse_col_value(col_schema: &ColSchema, value: String) -> ColValue {
+    match col_schema.data_type.as_str() {
+        "smallint" | "integer" | "bigint" => SimpleNumber(value),
+        "numeric" | "real" | "double precision" => SimpleNumber(value),
+        "boolean" => SimpleNumber(value),
+        "bit" | "bit varying" => BitNumber(value),
+        "date" | "time without time zone" | "time with time zone" | "timestamp without time zone" | "timestamp with time zone" | "interval" => {
+            DateString(value)
+        }
+        "character" | "character varying" | "text" => SimpleString(value),
+        "uuid" | "inet" | "cidr" | "macaddr" | "money" | "xml" => SimpleString(value),
+        "USER-DEFINED" | "ARRAY" => SimpleString(value),
+        "bytea" => BinaryString(value),
+        "json" | "jsonb" => JsonString(value),
+        _ => ParseError,
+    }
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod adapter_tests {
+    use itertools::Itertools;
+
+    use crate::db::{create_sqlite_connection, migrate_sqlite};
+    use crate::db::project::insert_project;
+    use crate::db::dump_config::insert_dump_configs;
+    use crate::db::snapshot::find_table_snapshots;
+    use crate::domain::dump_config::DumpConfig;
+    use crate::domain::project::{create_project_id, Project};
+    use crate::domain::project::Rdbms::Postgres;
+    use crate::domain::snapshot::{ColValue, create_snapshot_id, TableSnapshot};
+    use crate::domain::snapshot::ColValue::*;
+    use crate::dump::adapter::TargetDbAdapter;
+    use crate::dump::{dump, restore};
+    use crate::dump::postgres::TargetDbPostgres;
+
+    fn s(s: &str) -> String {
+        s.to_string()
+    }
+
+    fn drop_all(adapter: &mut TargetDbPostgres) -> anyhow::Result<()> {
+        adapter.client.batch_execute("drop schema public cascade; create schema public")?;
+        Ok(())
+    }
+
+    #[test]
+    fn get_dump_configs() -> anyhow::Result<()> {
+        let project = Project::new(&create_project_id(), "test-project", "red", Postgres, "user","password","127.0.0.1","19002","testdata");
+
+        let mut adapter = TargetDbPostgres::new(&project)?;
+
+        drop_all(&mut adapter)?;
+
+        adapter.client.batch_execute("create table \"01_number_01_integer\" ( id serial, col_smallint smallint, col_integer integer, col_bigint bigint, updated_at timestamp, primary key (id) )")?;
+        adapter.client.batch_execute("create table \"11_string_01_char\" ( id serial, col_char char(3), col_varchar varchar(3), updated_at timestamp, primary key (id) )")?;
+
+        let sut = DumpConfig::sort(adapter.get_dump_configs()?);
+
+        assert_eq!("01_number_01_integer", sut[0].table_name);
+        assert_eq!("id, col_smallint, col_integer, col_bigint, updated_at", sut[0].col_names.join(", "));
+        assert_eq!("updated_at", sut[0].value);
+
+        assert_eq!("11_string_01_char", sut[1].table_name);
+        assert_eq!("id, col_char, col_varchar, updated_at", sut[1].col_names.join(", "));
+        assert_eq!("updated_at", sut[1].value);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump_all() -> anyhow::Result<()> {
+        let project = Project::new(&create_project_id(), "test-project", "red", Postgres, "user","password","127.0.0.1","19002","testdata");
+
+        let mut adapter = TargetDbPostgres::new(&project)?;
+
+        drop_all(&mut adapter)?;
+
+        adapter.client.batch_execute("create table \"01_number_01_integer\" ( id serial, col_smallint smallint, col_integer integer, col_bigint bigint, updated_at timestamp, primary key (id) )")?;
+        adapter.client.batch_execute("insert into \"01_number_01_integer\" values (1, 32767, 2147483647, 9223372036854775807, '2023-09-01 12:34:56'), (2, -32768, -2147483648, -9223372036854775808, '2023-09-01 12:34:56')")?;
+
+        adapter.client.batch_execute("create table \"02_number_02_numeric\" ( id serial, col_numeric numeric(5, 2), col_real real, col_double double precision, updated_at timestamp, primary key (id) )")?;
+        adapter.client.batch_execute("insert into \"02_number_02_numeric\" values (1, 999.99, 999.99, 999.99, '2023-09-01 12:34:56'), (2, -999.99, -999.99, -999.99, '2023-09-01 12:34:56')")?;
+
+        adapter.client.batch_execute("create table \"03_number_03_boolean\" ( id serial, col_boolean boolean, updated_at timestamp, primary key (id) )")?;
+        adapter.client.batch_execute("insert into \"03_number_03_boolean\" values (1, true, '2023-09-01 12:34:56'), (2, false, '2023-09-01 12:34:56')")?;
+
+        adapter.client.batch_execute("create table \"04_number_04_bit\" ( id serial, col_bit bit(10), updated_at timestamp, primary key (id) )")?;
+        adapter.client.batch_execute("insert into \"04_number_04_bit\" values (1, b'1000000000', '2023-09-01 12:34:56'), (2, b'0000000000', '2023-09-01 12:34:56')")?;
+
+        adapter.client.batch_execute("create table \"05_date_01_date\" ( id serial, col_date date, col_time time, col_timestamp timestamp, col_timestamptz timestamptz, col_interval interval, updated_at timestamp, primary key (id) )")?;
+        adapter.client.batch_execute("insert into \"05_date_01_date\" values (1, '2020-01-01', '00:00:00', '2020-01-01 00:00:00', '2020-01-01 09:00:00+09', '1 day 02:00:00', '2023-09-01 12:34:56')")?;
+
+        adapter.client.batch_execute("create table \"06_string_01_char\" ( id serial, col_char char(3), col_varchar varchar(3), col_text text, updated_at timestamp, primary key (id) )")?;
+        adapter.client.batch_execute("insert into \"06_string_01_char\" values (1, 'abc', 'abc', 'abc', '2023-09-01 12:34:56'), (2, null, null, null, '2023-09-01 12:34:56')")?;
+
+        adapter.client.batch_execute("create table \"07_string_02_bytea\" ( id serial, col_bytea bytea, updated_at timestamp, primary key (id) )")?;
+        adapter.client.batch_execute("insert into \"07_string_02_bytea\" values (1, 'abc', '2023-09-01 12:34:56')")?;
+
+        adapter.client.batch_execute("create type status as enum ('active', 'inactive')")?;
+        adapter.client.batch_execute("create table \"08_string_03_enum\" ( id serial, col_enum status, updated_at timestamp, primary key (id) )")?;
+        adapter.client.batch_execute("insert into \"08_string_03_enum\" values (1, 'active', '2023-09-01 12:34:56'), (2, 'inactive', '2023-09-01 12:34:56')")?;
+
+        adapter.client.batch_execute("create table \"09_string_04_uuid\" ( id serial, col_uuid uuid, col_inet inet, updated_at timestamp, primary key (id) )")?;
+        adapter.client.batch_execute("insert into \"09_string_04_uuid\" values (1, 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11', '192.168.0.1', '2023-09-01 12:34:56')")?;
+
+        adapter.client.batch_execute("create table \"10_string_05_array\" ( id serial, col_array integer[], updated_at timestamp, primary key (id) )")?;
+        adapter.client.batch_execute("insert into \"10_string_05_array\" values (1, '{1, 2, 3}', '2023-09-01 12:34:56')")?;
+
+        adapter.client.batch_execute("create table \"11_string_06_json\" ( id serial, col_json json, col_jsonb jsonb, updated_at timestamp, primary key (id) )")?;
+        adapter.client.batch_execute(r#"insert into "11_string_06_json" values (1, '{"id": 1, "name": "John"}', '{"id": 1, "name": "John"}', '2023-09-01 12:34:56')"#)?;
+
+        adapter.client.batch_execute("create table \"12_key_01_primary\" ( code int, updated_at timestamp, primary key (code) )")?;
+
+        adapter.client.batch_execute("create table \"13_key_02_unique\" ( code int, updated_at timestamp, unique (code) )")?;
+
+        adapter.client.batch_execute("create table \"14_key_03_unique_not_null\" ( code int not null, updated_at timestamp, unique (code) )")?;
+
+        adapter.client.batch_execute("create table \"15_key_04_primary_primary\" ( code1 int, code2 int, updated_at timestamp, primary key (code1, code2) )")?;
+
+        adapter.client.batch_execute("create table \"16_key_05_primary_unique_not_null\" ( code1 int, code2 int not null, updated_at timestamp, primary key (code1), unique (code2) )")?;
+
+        adapter.client.batch_execute("create table \"17_key_06_multi_unique_not_null_unique\" ( code1 int not null, code2 int, updated_at timestamp, unique (code1, code2) )")?;
+
+        adapter.client.batch_execute("create table \"18_key_07_multi_unique_not_null_unique_not_null\" ( code1 int not null, code2 int not null, updated_at timestamp, unique (code1, code2) )")?;
+
+        adapter.client.batch_execute("create table \"19_key_08_nothing\" ( code int )")?;
+
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+
+        let project_id = create_project_id();
+        let project = Project::new(&project_id, "testdata-postgres15", "red", Postgres, "user", "password", "localhost", "19002", "testdata");
+        insert_project(&conn, &project)?;
+
+        let snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &snapshot_id, "test dump".to_string(), &adapter.get_dump_configs()?)?;
+
+        let act = find_table_snapshots(&conn, &snapshot_id)?;
+
+        fn assert(act: &TableSnapshot, table_name: &str, primary_col_name: &str, col_names: Vec<&str>, col_values: Vec<Vec<ColValue>>) {
+            assert_eq!(table_name, act.table_name);
+            assert_eq!(primary_col_name, act.primary_col_name);
+            assert_eq!(col_names.into_iter().map(String::from).collect_vec(), act.col_names);
+            assert_eq!(col_values.len(), act.row_snapshots.len());
+            for (i, col_value) in col_values.into_iter().enumerate() {
+                assert_eq!(col_value, act.row_snapshots[i].col_values);
+            }
+        }
+
+        assert(
+            &act[0],
+            "01_number_01_integer",
+            "id",
+            vec!["col_smallint", "col_integer", "col_bigint", "updated_at"],
+            vec![
+                vec![SimpleNumber(s("32767")),  SimpleNumber(s("2147483647")),  SimpleNumber(s("9223372036854775807")),  DateString(s("2023-09-01 12:34:56"))],
+                vec![SimpleNumber(s("-32768")), SimpleNumber(s("-2147483648")), SimpleNumber(s("-9223372036854775808")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[1],
+            "02_number_02_numeric",
+            "id",
+            vec!["col_numeric", "col_real", "col_double", "updated_at"],
+            vec![
+                vec![SimpleNumber(s("999.99")),  SimpleNumber(s("999.99")),  SimpleNumber(s("999.99")),  DateString(s("2023-09-01 12:34:56"))],
+                vec![SimpleNumber(s("-999.99")), SimpleNumber(s("-999.99")), SimpleNumber(s("-999.99")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[2],
+            "03_number_03_boolean",
+            "id",
+            vec!["col_boolean", "updated_at"],
+            vec![
+                vec![SimpleNumber(s("true")),  DateString(s("2023-09-01 12:34:56"))],
+                vec![SimpleNumber(s("false")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[3],
+            "04_number_04_bit",
+            "id",
+            vec!["col_bit", "updated_at"],
+            vec![
+                vec![BitNumber(s("1000000000")), DateString(s("2023-09-01 12:34:56"))],
+                vec![BitNumber(s("0000000000")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[4],
+            "05_date_01_date",
+            "id",
+            vec!["col_date", "col_time", "col_timestamp", "col_timestamptz", "col_interval", "updated_at"],
+            vec![
+                vec![DateString(s("2020-01-01")), DateString(s("00:00:00")), DateString(s("2020-01-01 00:00:00")), DateString(s("2020-01-01 00:00:00+00")), DateString(s("1 day 02:00:00")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[5],
+            "06_string_01_char",
+            "id",
+            vec!["col_char", "col_varchar", "col_text", "updated_at"],
+            vec![
+                vec![SimpleString(s("abc")), SimpleString(s("abc")), SimpleString(s("abc")), DateString(s("2023-09-01 12:34:56"))],
+                vec![Null,                   Null,                   Null,                   DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[6],
+            "07_string_02_bytea",
+            "id",
+            vec!["col_bytea", "updated_at"],
+            vec![
+                vec![BinaryString(s(r"\x616263")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[7],
+            "08_string_03_enum",
+            "id",
+            vec!["col_enum", "updated_at"],
+            vec![
+                vec![SimpleString(s("active")),   DateString(s("2023-09-01 12:34:56"))],
+                vec![SimpleString(s("inactive")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[8],
+            "09_string_04_uuid",
+            "id",
+            vec!["col_uuid", "col_inet", "updated_at"],
+            vec![
+                vec![SimpleString(s("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11")), SimpleString(s("192.168.0.1")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[9],
+            "10_string_05_array",
+            "id",
+            vec!["col_array", "updated_at"],
+            vec![
+                vec![SimpleString(s("{1,2,3}")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[10],
+            "11_string_06_json",
+            "id",
+            vec!["col_json", "col_jsonb", "updated_at"],
+            vec![
+                vec![JsonString(s(r#"{"id": 1, "name": "John"}"#)), JsonString(s(r#"{"id": 1, "name": "John"}"#)), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[11],
+            "12_key_01_primary",
+            "code",
+            vec!["updated_at"],
+            vec![]
+        );
+
+        // 13_key_02_unique
+
+        assert(
+            &act[12],
+            "14_key_03_unique_not_null",
+            "code",
+            vec!["updated_at"],
+            vec![]
+        );
+
+        assert(
+            &act[13],
+            "15_key_04_primary_primary",
+            "code1-code2",
+            vec!["updated_at"],
+            vec![]
+        );
+
+        assert(
+            &act[14],
+            "16_key_05_primary_unique_not_null",
+            "code1",
+            vec!["code2", "updated_at"],
+            vec![]
+        );
+
+        // 17_key_06_multi_unique_not_null_unique
+
+        assert(
+            &act[15],
+            "18_key_07_multi_unique_not_null_unique_not_null",
+            "code1-code2",
+            vec!["updated_at"],
+            vec![]
+        );
+
+        // 19_key_08_nothing
+
+        Ok(())
+    }
+
+    #[test]
+    fn restore_with_foreign_key() -> anyhow::Result<()> {
+        let project = Project::new(&create_project_id(), "test-project", "red", Postgres, "user","password","127.0.0.1","19002","testdata");
+
+        let mut adapter = TargetDbPostgres::new(&project)?;
+
+        drop_all(&mut adapter)?;
+
+        // 遅延できない外部キーでも、参照する側の行から削除し、参照される側の行から追加する
+        adapter.client.batch_execute("create table \"01_account\" ( id int, name text, primary key (id) )")?;
+        adapter.client.batch_execute("create table \"02_user\" ( id int, account_id int references \"01_account\" (id), name text, primary key (id) )")?;
+        adapter.client.batch_execute("insert into \"01_account\" values (1, 'a'); insert into \"02_user\" values (1, 1, 'John')")?;
+
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+        insert_project(&conn, &project)?;
+
+        let dump_configs = vec![
+            DumpConfig::new("01_account", vec!["id", "name"], "all"),
+            DumpConfig::new("02_user", vec!["id", "account_id", "name"], "all"),
+        ];
+
+        let snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &snapshot_id, "test dump".to_string(), &dump_configs)?;
+        insert_dump_configs(&conn, &project.project_id, &snapshot_id, &dump_configs)?;
+
+        adapter.client.batch_execute("delete from \"02_user\"; delete from \"01_account\"")?;
+        adapter.client.batch_execute("insert into \"01_account\" values (2, 'b'); insert into \"02_user\" values (2, 2, 'Jack')")?;
+
+        assert_eq!(4, restore(&conn, &project, &snapshot_id)?);
+
+        let restored_snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &restored_snapshot_id, "test dump".to_string(), &dump_configs)?;
+
+        let exp = find_table_snapshots(&conn, &snapshot_id)?;
+        let act = find_table_snapshots(&conn, &restored_snapshot_id)?;
+
+        assert_eq!(exp[0].row_snapshots, act[0].row_snapshots);
+        assert_eq!(exp[1].row_snapshots, act[1].row_snapshots);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod find_primary_col_names_tests {
+    use std::collections::HashSet;
+
+    use crate::domain::schema::ColName;
+    use crate::dump::postgres::{find_primary_col_names, KeyConstraint};
+
+    fn p(oid: i64, col_names: Vec<&str>) -> KeyConstraint {
+        KeyConstraint { oid, primary: true, col_names: col_names.into_iter().map(String::from).collect() }
+    }
+
+    fn u(oid: i64, col_names: Vec<&str>) -> KeyConstraint {
+        KeyConstraint { oid, primary: false, col_names: col_names.into_iter().map(String::from).collect() }
+    }
+
+    fn sut(key_constraints: Vec<KeyConstraint>, not_null_col_names: Vec<&str>) -> String {
+        let not_null_col_names: Vec<ColName> = not_null_col_names.into_iter().map(String::from).collect();
+        let not_null_col_names: HashSet<&ColName> = not_null_col_names.iter().collect();
+        find_primary_col_names(&key_constraints, &not_null_col_names).into_iter().map(|col_name| col_name.as_str()).collect::<Vec<_>>().join("-")
+    }
+
+    #[test]
+    fn primary() {
+        assert_eq!("col1", sut(vec![p(1, vec!["col1"])], vec!["col1"]));
+    }
+
+    #[test]
+    fn unique() {
+        assert_eq!("", sut(vec![u(1, vec!["col1"])], vec![]));
+    }
+
+    #[test]
+    fn unique_not_null() {
+        assert_eq!("col1", sut(vec![u(1, vec!["col1"])], vec!["col1"]));
+    }
+
+    #[test]
+    fn primary_primary() {
+        assert_eq!("col1-col2", sut(vec![p(1, vec!["col1", "col2"])], vec!["col1", "col2"]));
+    }
+
+    #[test]
+    fn primary_unique_not_null() {
+        assert_eq!("col1", sut(vec![p(1, vec!["col1"]), u(2, vec!["col2"])], vec!["col1", "col2"]));
+    }
+
+    #[test]
+    fn unique_unique_not_null() {
+        assert_eq!("col2", sut(vec![u(1, vec!["col1"]), u(2, vec!["col2"])], vec!["col2"]));
+    }
+
+    #[test]
+    fn multi_unique_not_null_unique() {
+        assert_eq!("", sut(vec![u(1, vec!["col1", "col2"])], vec!["col1"]));
+    }
+
+    #[test]
+    fn multi_unique_not_null_unique_not_null() {
+        assert_eq!("col1-col2", sut(vec![u(1, vec!["col1", "col2"])], vec!["col1", "col2"]));
+    }
+}
+
+#[cfg(test)]
+mod parse_col_value_tests {
+    use crate::domain::schema::ColSchema;
+    use crate::domain::snapshot::ColValue;
+    use crate::dump::postgres::parse_col_value;
+
+    fn sut(data_type: &str, value: &str) -> ColValue {
+        parse_col_value(&ColSchema { col_name: "col_test".to_string(), data_type: data_type.to_string() }, value.to_string())
+    }
+
+    #[test]
+    fn parse_integer() {
+        let exp = "42";
+        assert_eq!(exp, sut("integer", "42").as_display_value());
+    }
+
+    #[test]
+    fn parse_numeric() {
+        let exp = "3.14";
+        assert_eq!(exp, sut("numeric", "3.14").as_display_value());
+    }
+
+    #[test]
+    fn parse_double_precision() {
+        let exp = "3.14";
+        assert_eq!(exp, sut("double precision", "3.14").as_display_value());
+    }
+
+    #[test]
+    fn parse_boolean() {
+        let exp = "true";
+        assert_eq!(exp, sut("boolean", "true").as_display_value());
+    }
+
+    #[test]
+    fn parse_bit() {
+        let exp = "bit(101)";
+        assert_eq!(exp, sut("bit", "101").as_display_value());
+    }
+
+    #[test]
+    fn parse_timestamptz() {
+        let exp = r#""2020-01-01 00:00:00+00""#;
+        assert_eq!(exp, sut("timestamp with time zone", "2020-01-01 00:00:00+00").as_display_value());
+    }
+
+    #[test]
+    fn parse_interval() {
+        let exp = r#""1 day 02:00:00""#;
+        assert_eq!(exp, sut("interval", "1 day 02:00:00").as_display_value());
+    }
+
+    #[test]
+    fn parse_varchar() {
+        let exp = r#""abc""#;
+        assert_eq!(exp, sut("character varying", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_uuid() {
+        let exp = r#""a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11""#;
+        assert_eq!(exp, sut("uuid", "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11").as_display_value());
+    }
+
+    #[test]
+    fn parse_inet() {
+        let exp = r#""192.168.0.1""#;
+        assert_eq!(exp, sut("inet", "192.168.0.1").as_display_value());
+    }
+
+    #[test]
+    fn parse_enum() {
+        let exp = r#""active""#;
+        assert_eq!(exp, sut("USER-DEFINED", "active").as_display_value());
+    }
+
+    #[test]
+    fn parse_array() {
+        let exp = r#""{1,2,3}""#;
+        assert_eq!(exp, sut("ARRAY", "{1,2,3}").as_display_value());
+    }
+
+    #[test]
+    fn parse_bytea() {
+        let exp = "binary";
+        assert_eq!(exp, sut("bytea", r"\x616263").as_display_value());
+    }
+
+    #[test]
+    fn parse_jsonb() {
+        let exp = r#"{"id": 1, "name": "John"}"#;
+        assert_eq!(exp, sut("jsonb", r#"{"id": 1, "name": "John"}"#).as_display_value());
+    }
+}
@@ -0,0 +1,975 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::anyhow;
+use diesel::sql_types::{Integer, Nullable, Text};
+use diesel::{sql_query, Connection, RunQueryDsl, SqliteConnection};
+use itertools::Itertools;
+use serde_json::Value;
+
+use crate::domain::db_object::{DbObject, DbObjectType};
+use crate::domain::dump_config::DumpConfig;
+use crate::domain::project::Project;
+use crate::domain::schema::{ColName, ColSchema, TableName, TableSchema};
+use crate::domain::snapshot::ColValue::*;
+use crate::domain::snapshot::{ColValue, PrimaryColValues, RowSnapshot};
+use crate::domain::table_definition::{ColDefinition, ForeignKeyDefinition, IndexDefinition, TableDefinition};
+use crate::dump::adapter::{create_key_condition, create_keyset_condition, create_where_clause, TargetDbAdapter};
+use crate::logger;
+
+pub struct TargetDbSqlite {
+    conn: SqliteConnection,
+}
+
+impl TargetDbSqlite {
+    pub fn new(project: &Project) -> anyhow::Result<Self> {
+        let conn = project.create_sqlite_file_connection()?;
+
+        Ok(Self { conn })
+    }
+}
+
+#[derive(QueryableByName)]
+struct ColRow {
+    #[sql_type = "Text"]
+    table_name: String,
+    #[sql_type = "Text"]
+    col_name: String,
+    #[sql_type = "Text"]
+    data_type: String,
+    #[sql_type = "Integer"]
+    pk: i32,
+}
+
+#[derive(QueryableByName)]
+struct ColDefinitionRow {
+    #[sql_type = "Text"]
+    table_name: String,
+    #[sql_type = "Text"]
+    col_name: String,
+    #[sql_type = "Text"]
+    data_type: String,
+    #[sql_type = "Integer"]
+    not_null: i32,
+    #[sql_type = "Nullable<Text>"]
+    default_value: Option<String>,
+    #[sql_type = "Integer"]
+    pk: i32,
+}
+
+#[derive(QueryableByName)]
+struct IndexRow {
+    #[sql_type = "Text"]
+    table_name: String,
+    #[sql_type = "Text"]
+    index_name: String,
+    #[sql_type = "Integer"]
+    is_unique: i32,
+    #[sql_type = "Text"]
+    origin: String,
+    #[sql_type = "Nullable<Text>"]
+    col_name: Option<String>,
+}
+
+#[derive(QueryableByName)]
+struct ForeignKeyRow {
+    #[sql_type = "Text"]
+    table_name: String,
+    #[sql_type = "Integer"]
+    id: i32,
+    #[sql_type = "Text"]
+    ref_table_name: String,
+    #[sql_type = "Text"]
+    col_name: String,
+    #[sql_type = "Nullable<Text>"]
+    ref_col_name: Option<String>,
+}
+
+#[derive(QueryableByName)]
+struct DbObjectRow {
+    #[sql_type = "Text"]
+    object_type: String,
+    #[sql_type = "Text"]
+    object_name: String,
+    #[sql_type = "Text"]
+    definition: String,
+}
+
+#[derive(QueryableByName)]
+struct ValuesRow {
+    #[sql_type = "Text"]
+    col_values: String,
+}
+
+impl TargetDbSqlite {
+    fn get_col_rows(&mut self) -> anyhow::Result<Vec<ColRow>> {
+        let query = "select m.name as table_name, p.name as col_name, p.type as data_type, p.pk as pk from sqlite_master m join pragma_table_info(m.name) p where m.type = 'table' and m.name not like 'sqlite_%' order by m.name, p.cid";
+
+        logger::info(format!("query: {}", &query));
+
+        sql_query(query).load(&self.conn).map_err(|e| anyhow!(e))
+    }
+}
+
+impl TargetDbAdapter for TargetDbSqlite {
+    fn get_dump_configs(&mut self) -> anyhow::Result<Vec<DumpConfig>> {
+        let mut map: HashMap<TableName, Vec<ColName>> = HashMap::new();
+
+        for row in self.get_col_rows()? {
+            map.entry(row.table_name).or_default().push(row.col_name);
+        }
+
+        Ok(map.into_iter().map(|(k, v)| DumpConfig::init(k, v)).collect_vec())
+    }
+
+    fn get_table_schemata(&mut self) -> anyhow::Result<Vec<TableSchema>> {
+        let mut table_names = HashSet::new();
+        let mut map1: HashMap<TableName, Vec<(i32, ColSchema)>> = HashMap::new();
+        let mut map2: HashMap<TableName, Vec<ColSchema>> = HashMap::new();
+
+        for row in self.get_col_rows()? {
+            table_names.insert(row.table_name.clone());
+
+            let col = ColSchema { col_name: row.col_name, data_type: row.data_type };
+            if 0 < row.pk {
+                map1.entry(row.table_name).or_default().push((row.pk, col));
+            } else {
+                map2.entry(row.table_name).or_default().push(col);
+            }
+        }
+
+        let mut table_names = table_names.into_iter().collect_vec();
+        table_names.sort();
+        Ok(table_names
+            .iter()
+            .map(|table_name| {
+                // 主キーのないテーブルは rowid を主キーとみなす
+                let primary_cols = match map1.get(table_name) {
+                    Some(cols) => cols.iter().sorted_by_key(|(pk, _)| pk).map(|(_, col)| col.clone()).collect_vec(),
+                    None => vec![ColSchema { col_name: "rowid".to_string(), data_type: "integer".to_string() }],
+                };
+                TableSchema::new(table_name, &primary_cols, map2.get(table_name).unwrap_or(&vec![]))
+            })
+            .collect())
+    }
+
+    fn get_table_definitions(&mut self) -> anyhow::Result<Vec<TableDefinition>> {
+        let filter = "m.type = 'table' and m.name not like 'sqlite_%'";
+
+        let query = format!(
+            r#"select m.name as table_name, p.name as col_name, p.type as data_type, p."notnull" as not_null, p.dflt_value as default_value, p.pk as pk from sqlite_master m join pragma_table_info(m.name) p where {filter} order by m.name, p.cid"#
+        );
+        logger::info(format!("query: {}", &query));
+        let col_rows: Vec<ColDefinitionRow> = sql_query(query).load(&self.conn).map_err(|e| anyhow!(e))?;
+
+        let query = format!(
+            r#"select m.name as table_name, l.name as index_name, l."unique" as is_unique, l.origin as origin, i.name as col_name from sqlite_master m join pragma_index_list(m.name) l join pragma_index_info(l.name) i where {filter} order by m.name, l.name, i.seqno"#
+        );
+        logger::info(format!("query: {}", &query));
+        let index_rows: Vec<IndexRow> = sql_query(query).load(&self.conn).map_err(|e| anyhow!(e))?;
+
+        let query = format!(
+            r#"select m.name as table_name, f.id as id, f."table" as ref_table_name, f."from" as col_name, f."to" as ref_col_name from sqlite_master m join pragma_foreign_key_list(m.name) f where {filter} order by m.name, f.id, f.seq"#
+        );
+        logger::info(format!("query: {}", &query));
+        let foreign_key_rows: Vec<ForeignKeyRow> = sql_query(query).load(&self.conn).map_err(|e| anyhow!(e))?;
+
+        let cols = col_rows.into_iter().into_group_map_by(|row| row.table_name.clone());
+        let mut indexes = index_rows.into_iter().into_group_map_by(|row| row.table_name.clone());
+        let mut foreign_keys = foreign_key_rows.into_iter().into_group_map_by(|row| row.table_name.clone());
+
+        Ok(cols
+            .into_iter()
+            .sorted_by(|(table_name1, _), (table_name2, _)| table_name1.cmp(table_name2))
+            .map(|(table_name, col_rows)| {
+                // integer primary key は rowid の別名でインデックスを持たないので、主キーは table_info から組み立てる
+                let primary_col_names =
+                    col_rows.iter().filter(|row| 0 < row.pk).sorted_by_key(|row| row.pk).map(|row| row.col_name.clone()).collect_vec();
+                let mut index_definitions = vec![];
+                if !primary_col_names.is_empty() {
+                    index_definitions.push(IndexDefinition {
+                        index_name: "PRIMARY".to_string(),
+                        col_names: primary_col_names,
+                        primary: true,
+                        unique: true,
+                    });
+                }
+                for (index_name, rows) in &indexes.remove(&table_name).unwrap_or_default().into_iter().group_by(|row| row.index_name.clone()) {
+                    let rows = rows.collect_vec();
+                    if rows[0].origin == "pk" {
+                        continue;
+                    }
+                    let col_names = rows.iter().map(|row| row.col_name.clone().unwrap_or_else(|| "<expression>".to_string())).collect();
+                    index_definitions.push(IndexDefinition { index_name, col_names, primary: false, unique: rows[0].is_unique == 1 });
+                }
+
+                // SQLite の外部キーには名前がないので、参照元のカラムを名前の代わりにする
+                let mut foreign_key_definitions = vec![];
+                for (_, rows) in &foreign_keys.remove(&table_name).unwrap_or_default().into_iter().group_by(|row| row.id) {
+                    let rows = rows.collect_vec();
+                    let col_names = rows.iter().map(|row| row.col_name.clone()).collect_vec();
+                    foreign_key_definitions.push(ForeignKeyDefinition {
+                        constraint_name: col_names.join(","),
+                        col_names,
+                        ref_table_name: rows[0].ref_table_name.clone(),
+                        ref_col_names: rows.iter().map(|row| row.ref_col_name.clone().unwrap_or_default()).collect(),
+                    });
+                }
+
+                let col_definitions = col_rows
+                    .into_iter()
+                    .map(|row| ColDefinition::new(&row.col_name, &row.data_type.to_lowercase(), row.not_null == 0).with_default(row.default_value))
+                    .collect();
+
+                TableDefinition { table_name, cols: col_definitions, indexes: index_definitions, foreign_keys: foreign_key_definitions }
+            })
+            .collect())
+    }
+
+    // SQLite にはルーチンとイベントがないので、ビューとトリガーの作成時の SQL だけを取得する
+    fn get_db_objects(&mut self) -> anyhow::Result<Vec<DbObject>> {
+        let query = "select type as object_type, name as object_name, sql as definition from sqlite_master where type in ('view', 'trigger') order by type, name";
+        logger::info(format!("query: {}", &query));
+        let rows: Vec<DbObjectRow> = sql_query(query).load(&self.conn).map_err(|e| anyhow!(e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let object_type = if row.object_type == "view" { DbObjectType::View } else { DbObjectType::Trigger };
+                DbObject::new(object_type, &row.object_name, &row.definition)
+            })
+            .collect())
+    }
+
+    fn validate_filter(&mut self, table_name: &TableName, filter: &str) -> anyhow::Result<()> {
+        let query = format!(r#"select 1 from "{}" where ({}) limit 0"#, table_name, filter);
+
+        logger::info(format!("query: {}", &query));
+
+        sql_query(query).execute(&self.conn).map(|_| ()).map_err(|e| anyhow!(e))
+    }
+
+    fn get_row_snapshots(&mut self, table_schema: &TableSchema, dump_config: &DumpConfig, limit: usize) -> anyhow::Result<Vec<RowSnapshot>> {
+        let condition = create_where_clause(vec![dump_config.get_filter().map(String::from)]);
+        let order_by =
+            if dump_config.value == "limited" || dump_config.is_all() { "".to_string() } else { format!(r#"order by "{}""#, dump_config.value) };
+
+        self.select_row_snapshots(table_schema, &condition, &order_by, limit)
+    }
+
+    fn get_row_snapshots_after(
+        &mut self,
+        table_schema: &TableSchema,
+        dump_config: &DumpConfig,
+        after: Option<&PrimaryColValues>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<RowSnapshot>> {
+        let keyset_condition = match after {
+            Some(after) => Some(create_keyset_condition(&table_schema.primary_cols, after, quote_col_name, false)?),
+            None => None,
+        };
+        let condition = create_where_clause(vec![dump_config.get_filter().map(String::from), keyset_condition]);
+        let order_by = format!("order by {}", table_schema.primary_cols.iter().map(|col| quote_col_name(&col.col_name)).join(","));
+
+        self.select_row_snapshots(table_schema, &condition, &order_by, limit)
+    }
+
+    fn get_row_snapshots_by_keys(&mut self, table_schema: &TableSchema, keys: &[PrimaryColValues]) -> anyhow::Result<Vec<RowSnapshot>> {
+        let condition = create_where_clause(vec![Some(create_key_condition(&table_schema.primary_cols, keys, quote_col_name, false)?)]);
+        let order_by = format!("order by {}", table_schema.primary_cols.iter().map(|col| quote_col_name(&col.col_name)).join(","));
+
+        self.select_row_snapshots(table_schema, &condition, &order_by, keys.len())
+    }
+
+    fn execute_statements(&mut self, statements: &[String]) -> anyhow::Result<()> {
+        self.conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                // 外部キーは、コミット時にまとめて確認する
+                sql_query("pragma defer_foreign_keys = on").execute(&self.conn)?;
+
+                for statement in statements {
+                    logger::info(format!("query: {}", statement));
+
+                    sql_query(statement).execute(&self.conn)?;
+                }
+                Ok(())
+            })
+            .map_err(|e| anyhow!(e))
+    }
+}
+
+impl TargetDbSqlite {
+    fn select_row_snapshots(
+        &mut self,
+        table_schema: &TableSchema,
+        condition: &str,
+        order_by: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<RowSnapshot>> {
+        let all_cols = table_schema.get_all_col_refs();
+
+        // 動的型付けのため、値ごとのストレージクラスも合わせて取得する
+        let col_names = all_cols
+            .iter()
+            .map(|col| format!(r#"typeof("{0}"), case when typeof("{0}") = 'blob' then hex("{0}") else "{0}" end"#, col.col_name))
+            .join(",");
+        let query = format!(
+            r#"select json_array({}) as col_values from "{}" {} {} limit {}"#,
+            col_names, table_schema.table_name, condition, order_by, limit
+        );
+
+        logger::info(format!("query: {}", &query));
+
+        let rows: Vec<ValuesRow> = sql_query(query).load(&self.conn).map_err(|e| anyhow!(e))?;
+
+        rows.iter()
+            .map(|row| {
+                let values: Vec<Value> = serde_json::from_str(&row.col_values)?;
+
+                let mut primary_cols = vec![];
+                let mut cols = vec![];
+
+                for (i, is_primary) in table_schema.get_col_indices() {
+                    let storage_class = values[i * 2].as_str().unwrap_or_default();
+                    let col = match &values[i * 2 + 1] {
+                        Value::Null => Null,
+                        Value::String(value) => parse_col_value(all_cols[i], storage_class, value.clone()),
+                        value => parse_col_value(all_cols[i], storage_class, value.to_string()),
+                    };
+                    if is_primary {
+                        primary_cols.push(col);
+                    } else {
+                        cols.push(col);
+                    }
+                }
+
+                Ok(RowSnapshot::new(primary_cols, cols))
+            })
+            .collect()
+    }
+}
+
+fn quote_col_name(col_name: &str) -> String {
+    format!(r#""{col_name}""#)
+}
+
+// 値はストレージクラスで判定し、テキストだけは宣言型から日付や json を見分ける
+fn parse_col_value(col_schema: &ColSchema, storage_class: &str, value: String) -> ColValue {
+    let data_type = col_schema.data_type.to_lowercase();
+    match storage_class {
+        "integer" | "real" => SimpleNumber(value),
+        "blob" => BinaryString(value),
+        "text" if data_type.contains("date") || data_type.contains("time") => DateString(value),
+        "text" if data_type.contains("json") => JsonString(value),
+        "text" => SimpleString(value),
+        _ => ParseError,
+    }
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod adapter_tests {
+    use std::env::temp_dir;
+
+    use diesel::{Connection, RunQueryDsl, sql_query, SqliteConnection};
+    use itertools::Itertools;
+
+    use crate::db::{create_sqlite_connection, migrate_sqlite};
+    use crate::db::dump_config::{find_dump_config, insert_dump_configs};
+    use crate::db::project::insert_project;
+    use crate::db::snapshot::{all_snapshot_summaries, find_table_snapshots};
+    use crate::db::db_object::find_db_objects;
+    use crate::db::table_definition::find_table_definitions;
+    use crate::domain::db_object::DbObjectType;
+    use crate::domain::dump_config::DumpConfig;
+    use crate::domain::project::{create_project_id, Project};
+    use crate::domain::project::Rdbms::Sqlite;
+    use crate::domain::snapshot::{ColValue, create_snapshot_id, RowSnapshot, TableSnapshot};
+    use crate::domain::snapshot::ColValue::*;
+    use crate::dump::adapter::TargetDbAdapter;
+    use crate::dump::{dump, dump_live, keep_live, restore};
+    use crate::dump::sqlite::TargetDbSqlite;
+
+    fn s(s: &str) -> String {
+        s.to_string()
+    }
+
+    fn setup(queries: Vec<&str>) -> anyhow::Result<Project> {
+        let path = temp_dir().join(format!("db-diff-test-{}.sqlite", create_project_id()));
+        let conn = SqliteConnection::establish(path.to_str().unwrap())?;
+        for query in queries {
+            sql_query(query).execute(&conn)?;
+        }
+
+        Ok(Project::new(&create_project_id(), "test-project", "red", Sqlite, "", "", "", "", path.to_str().unwrap()))
+    }
+
+    #[test]
+    fn file_not_found() {
+        let project = Project::new(&create_project_id(), "test-project", "red", Sqlite, "", "", "", "", "/not/found.sqlite");
+
+        assert!(TargetDbSqlite::new(&project).is_err());
+    }
+
+    #[test]
+    fn get_dump_configs() -> anyhow::Result<()> {
+        let project = setup(vec![
+            "create table \"01_number_01_integer\" ( id integer primary key, col_integer integer, col_real real, updated_at datetime )",
+            "create table \"11_string_01_text\" ( id integer primary key, col_text text, col_blob blob, updated_at datetime )",
+        ])?;
+
+        let mut adapter = TargetDbSqlite::new(&project)?;
+
+        let sut = DumpConfig::sort(adapter.get_dump_configs()?);
+
+        assert_eq!("01_number_01_integer", sut[0].table_name);
+        assert_eq!("id, col_integer, col_real, updated_at", sut[0].col_names.join(", "));
+        assert_eq!("updated_at", sut[0].value);
+
+        assert_eq!("11_string_01_text", sut[1].table_name);
+        assert_eq!("id, col_text, col_blob, updated_at", sut[1].col_names.join(", "));
+        assert_eq!("updated_at", sut[1].value);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump_all() -> anyhow::Result<()> {
+        let project = setup(vec![
+            "create table \"01_number_01_integer\" ( id integer primary key, col_integer integer, col_real real, col_boolean boolean, updated_at datetime )",
+            "insert into \"01_number_01_integer\" values (1, 9223372036854775807, 999.99, true, '2023-09-01 12:34:56'), (2, -9223372036854775808, -999.99, false, '2023-09-01 12:34:56')",
+
+            "create table \"02_date_01_date\" ( id integer primary key, col_date date, col_datetime datetime, col_timestamp timestamp, updated_at datetime )",
+            "insert into \"02_date_01_date\" values (1, '2020-01-01', '2020-01-01 00:00:00', '2020-01-01 00:00:00', '2023-09-01 12:34:56')",
+
+            "create table \"03_string_01_text\" ( id integer primary key, col_text text, col_varchar varchar(3), col_blob blob, col_json json, updated_at datetime )",
+            r#"insert into "03_string_01_text" values (1, 'abc', 'abc', x'616263', '{"id": 1, "name": "John"}', '2023-09-01 12:34:56'), (2, null, null, null, null, '2023-09-01 12:34:56')"#,
+
+            "create table \"04_dynamic_01_any\" ( id integer primary key, col_any, updated_at datetime )",
+            "insert into \"04_dynamic_01_any\" values (1, 42, '2023-09-01 12:34:56'), (2, 'abc', '2023-09-01 12:34:56'), (3, 3.14, '2023-09-01 12:34:56')",
+
+            "create table \"05_key_01_primary_primary\" ( code1 integer, code2 text, updated_at datetime, primary key (code2, code1) )",
+            "insert into \"05_key_01_primary_primary\" values (1, 'a', '2023-09-01 12:34:56')",
+
+            "create table \"06_key_02_without_rowid\" ( code text primary key, updated_at datetime ) without rowid",
+            "insert into \"06_key_02_without_rowid\" values ('a', '2023-09-01 12:34:56')",
+
+            "create table \"07_key_03_nothing\" ( code integer, updated_at datetime )",
+            "insert into \"07_key_03_nothing\" values (1, '2023-09-01 12:34:56'), (1, '2023-09-01 12:34:56')",
+        ])?;
+
+        let mut adapter = TargetDbSqlite::new(&project)?;
+
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+
+        insert_project(&conn, &project)?;
+
+        let snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &snapshot_id, "test dump".to_string(), &adapter.get_dump_configs()?)?;
+
+        let act = find_table_snapshots(&conn, &snapshot_id)?;
+
+        fn assert(act: &TableSnapshot, table_name: &str, primary_col_name: &str, col_names: Vec<&str>, col_values: Vec<Vec<ColValue>>) {
+            assert_eq!(table_name, act.table_name);
+            assert_eq!(primary_col_name, act.primary_col_name);
+            assert_eq!(col_names.into_iter().map(String::from).collect_vec(), act.col_names);
+            assert_eq!(col_values.len(), act.row_snapshots.len());
+            for (i, col_value) in col_values.into_iter().enumerate() {
+                assert_eq!(col_value, act.row_snapshots[i].col_values);
+            }
+        }
+
+        assert(
+            &act[0],
+            "01_number_01_integer",
+            "id",
+            vec!["col_integer", "col_real", "col_boolean", "updated_at"],
+            vec![
+                vec![SimpleNumber(s("9223372036854775807")),  SimpleNumber(s("999.99")),  SimpleNumber(s("1")), DateString(s("2023-09-01 12:34:56"))],
+                vec![SimpleNumber(s("-9223372036854775808")), SimpleNumber(s("-999.99")), SimpleNumber(s("0")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[1],
+            "02_date_01_date",
+            "id",
+            vec!["col_date", "col_datetime", "col_timestamp", "updated_at"],
+            vec![
+                vec![DateString(s("2020-01-01")), DateString(s("2020-01-01 00:00:00")), DateString(s("2020-01-01 00:00:00")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[2],
+            "03_string_01_text",
+            "id",
+            vec!["col_text", "col_varchar", "col_blob", "col_json", "updated_at"],
+            vec![
+                vec![SimpleString(s("abc")), SimpleString(s("abc")), BinaryString(s("616263")), JsonString(s(r#"{"id": 1, "name": "John"}"#)), DateString(s("2023-09-01 12:34:56"))],
+                vec![Null,                   Null,                   Null,                      Null,                                            DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[3],
+            "04_dynamic_01_any",
+            "id",
+            vec!["col_any", "updated_at"],
+            vec![
+                vec![SimpleNumber(s("42")),   DateString(s("2023-09-01 12:34:56"))],
+                vec![SimpleString(s("abc")),  DateString(s("2023-09-01 12:34:56"))],
+                vec![SimpleNumber(s("3.14")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[4],
+            "05_key_01_primary_primary",
+            "code2-code1",
+            vec!["updated_at"],
+            vec![
+                vec![DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[5],
+            "06_key_02_without_rowid",
+            "code",
+            vec!["updated_at"],
+            vec![
+                vec![DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        assert(
+            &act[6],
+            "07_key_03_nothing",
+            "rowid",
+            vec!["code", "updated_at"],
+            vec![
+                vec![SimpleNumber(s("1")), DateString(s("2023-09-01 12:34:56"))],
+                vec![SimpleNumber(s("1")), DateString(s("2023-09-01 12:34:56"))],
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump_all_rows() -> anyhow::Result<()> {
+        let project = setup(vec![
+            "create table \"01_all\" ( code1 integer, code2 text, updated_at datetime, primary key (code1, code2) )",
+            "insert into \"01_all\" with recursive seq(n) as (select 1 union all select n + 1 from seq where n < 1250) select n, 'a', '2023-09-01 12:34:56' from seq union all select n, 'b''c', '2023-09-01 12:34:56' from seq",
+
+            "create table \"02_all_limited\" ( id integer primary key, updated_at datetime )",
+            "insert into \"02_all_limited\" with recursive seq(n) as (select 1 union all select n + 1 from seq where n < 1500) select n, '2023-09-01 12:34:56' from seq",
+
+            "create table \"03_limited\" ( id integer primary key, updated_at datetime )",
+            "insert into \"03_limited\" with recursive seq(n) as (select 1 union all select n + 1 from seq where n < 1500) select n, '2023-09-01 12:34:56' from seq",
+
+            "create table \"04_ordered\" ( id integer primary key, updated_at datetime )",
+            "insert into \"04_ordered\" values (1, '2023-09-01 12:34:56'), (2, '2023-09-01 12:34:56')",
+        ])?;
+
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+
+        insert_project(&conn, &project)?;
+
+        let dump_configs = vec![
+            DumpConfig::new("01_all", vec!["code1", "code2", "updated_at"], "all"),
+            DumpConfig::new("02_all_limited", vec!["id", "updated_at"], "all").with_row_limit(Some(1200)),
+            DumpConfig::new("03_limited", vec!["id", "updated_at"], "limited"),
+            DumpConfig::new("04_ordered", vec!["id", "updated_at"], "updated_at").with_row_limit(Some(2)),
+        ];
+
+        let snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &snapshot_id, "test dump".to_string(), &dump_configs)?;
+
+        let act = find_table_snapshots(&conn, &snapshot_id)?;
+
+        assert_eq!("01_all", act[0].table_name);
+        assert_eq!(2500, act[0].row_snapshots.len());
+        assert_eq!(2500, act[0].row_snapshots.iter().map(|row_snapshot| &row_snapshot.primary_col_values).unique().count());
+        assert_eq!(vec![SimpleNumber(s("1")), SimpleString(s("a"))], act[0].row_snapshots[0].primary_col_values.col_values);
+        assert_eq!(vec![SimpleNumber(s("1")), SimpleString(s("b'c"))], act[0].row_snapshots[1].primary_col_values.col_values);
+        assert!(!act[0].is_truncated());
+
+        assert_eq!("02_all_limited", act[1].table_name);
+        assert_eq!(1200, act[1].row_snapshots.len());
+        assert!(act[1].is_truncated());
+
+        assert_eq!("03_limited", act[2].table_name);
+        assert_eq!(1000, act[2].row_snapshots.len());
+        assert!(act[2].is_truncated());
+
+        assert_eq!("04_ordered", act[3].table_name);
+        assert_eq!(2, act[3].row_snapshots.len());
+        assert!(!act[3].is_truncated());
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump_filtered() -> anyhow::Result<()> {
+        let project = setup(vec![
+            "create table \"01_filtered\" ( id integer primary key, tenant_id integer, updated_at datetime )",
+            "insert into \"01_filtered\" with recursive seq(n) as (select 1 union all select n + 1 from seq where n < 1500) select n, n % 3, '2023-09-01 12:34:56' from seq",
+        ])?;
+
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+
+        insert_project(&conn, &project)?;
+
+        // limited
+        let dump_configs = vec![DumpConfig::new("01_filtered", vec!["id", "tenant_id", "updated_at"], "limited").with_filter(Some(s("tenant_id = 1")))];
+
+        let snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &snapshot_id, "test dump".to_string(), &dump_configs)?;
+
+        let act = find_table_snapshots(&conn, &snapshot_id)?;
+        assert_eq!(500, act[0].row_snapshots.len());
+        assert!(act[0].row_snapshots.iter().all(|row_snapshot| row_snapshot.col_values[0] == SimpleNumber(s("1"))));
+        assert!(!act[0].is_truncated());
+
+        // all
+        let dump_configs = vec![DumpConfig::new("01_filtered", vec!["id", "tenant_id", "updated_at"], "all").with_filter(Some(s("tenant_id = 1 or tenant_id = 2")))];
+
+        let snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &snapshot_id, "test dump".to_string(), &dump_configs)?;
+
+        let act = find_table_snapshots(&conn, &snapshot_id)?;
+        assert_eq!(1000, act[0].row_snapshots.len());
+        assert!(act[0].row_snapshots.iter().all(|row_snapshot| row_snapshot.col_values[0] != SimpleNumber(s("0"))));
+
+        // invalid
+        let dump_configs = vec![DumpConfig::new("01_filtered", vec!["id", "tenant_id", "updated_at"], "limited").with_filter(Some(s("not_found = 1")))];
+
+        let snapshot_id = create_snapshot_id();
+        let act = dump(&conn, &project, &snapshot_id, "test dump".to_string(), &dump_configs);
+        assert!(act.unwrap_err().to_string().starts_with("invalid filter: 01_filtered: "));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump_excluded() -> anyhow::Result<()> {
+        let project = setup(vec![
+            "create table \"01_excluded\" ( id integer primary key, name text, lock_version integer, updated_at datetime )",
+            "insert into \"01_excluded\" values (1, 'John', 3, '2023-09-01 12:34:56')",
+        ])?;
+
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+
+        insert_project(&conn, &project)?;
+
+        let dump_configs = vec![DumpConfig::new("01_excluded", vec!["id", "name", "lock_version", "updated_at"], "updated_at")
+            .with_excluded_col_names(vec![s("id"), s("lock_version"), s("updated_at"), s("not_found")])];
+
+        let snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &snapshot_id, "test dump".to_string(), &dump_configs)?;
+
+        let act = find_table_snapshots(&conn, &snapshot_id)?;
+
+        // 主キーと存在しないカラムは除外されない
+        assert_eq!("id", act[0].primary_col_name);
+        assert_eq!(vec![s("name")], act[0].col_names);
+        assert_eq!(vec![s("lock_version"), s("updated_at")], act[0].excluded_col_names);
+        assert_eq!(RowSnapshot::new(vec![SimpleNumber(s("1"))], vec![SimpleString(s("John"))]), act[0].row_snapshots[0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump_key_cols() -> anyhow::Result<()> {
+        let project = setup(vec![
+            "create table \"01_key\" ( id integer primary key, tenant_id integer, code text, name text )",
+            "insert into \"01_key\" values (1, 1, 'b', 'John'), (2, 1, 'a', 'Jane'), (3, 2, 'a', 'Jack')",
+            "create table \"02_duplicated\" ( id integer primary key, tenant_id integer, code text )",
+            "insert into \"02_duplicated\" values (1, 1, 'a'), (2, 1, 'a'), (3, 2, 'b'), (4, 2, 'b'), (5, 3, 'c')",
+        ])?;
+
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+
+        insert_project(&conn, &project)?;
+
+        let dump_configs = vec![
+            DumpConfig::new("01_key", vec!["id", "tenant_id", "code", "name"], "all").with_key_col_names(vec![s("tenant_id"), s("code")]),
+            DumpConfig::new("02_duplicated", vec!["id", "tenant_id", "code"], "ignore"),
+        ];
+
+        let snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &snapshot_id, "test dump".to_string(), &dump_configs)?;
+
+        let act = find_table_snapshots(&conn, &snapshot_id)?;
+
+        // 元の主キーは通常のカラムとして扱われる
+        assert_eq!("tenant_id-code", act[0].primary_col_name);
+        assert_eq!(vec![s("id"), s("name")], act[0].col_names);
+        assert_eq!(
+            vec!["1-\"a\"", "1-\"b\"", "2-\"a\""],
+            act[0].row_snapshots.iter().map(|row_snapshot| row_snapshot.primary_col_values.as_primary_value()).collect_vec()
+        );
+
+        // 重複するキーの値が表示される
+        let dump_configs = vec![
+            DumpConfig::new("01_key", vec!["id", "tenant_id", "code", "name"], "ignore"),
+            DumpConfig::new("02_duplicated", vec!["id", "tenant_id", "code"], "limited").with_key_col_names(vec![s("tenant_id"), s("code")]),
+        ];
+
+        let act = dump(&conn, &project, &create_snapshot_id(), "test dump".to_string(), &dump_configs).unwrap_err().to_string();
+        assert_eq!(r#"duplicate key values: 02_duplicated ( tenant_id-code ): 1-"a", 2-"b""#, act);
+
+        // 存在しないカラムはキーに指定できない
+        let dump_configs = vec![
+            DumpConfig::new("01_key", vec!["id", "tenant_id", "code", "name"], "limited").with_key_col_names(vec![s("not_found")]),
+            DumpConfig::new("02_duplicated", vec!["id", "tenant_id", "code"], "ignore"),
+        ];
+
+        let act = dump(&conn, &project, &create_snapshot_id(), "test dump".to_string(), &dump_configs).unwrap_err().to_string();
+        assert_eq!("key column not found: 01_key.not_found", act);
+
+        Ok(())
+    }
+
+    #[test]
+    fn restore_snapshot() -> anyhow::Result<()> {
+        let project = setup(vec![
+            "create table \"01_restore\" ( id integer primary key, name text, col_blob blob, tenant_id integer )",
+            "insert into \"01_restore\" values (1, 'John', x'616263', 1), (2, 'Jack', null, 1), (3, 'Jane', null, 2)",
+            "create table \"02_rowid\" ( message text, note text )",
+            "insert into \"02_rowid\" values ('login', null), ('login', null), ('error', 'x')",
+        ])?;
+
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+
+        insert_project(&conn, &project)?;
+
+        let dump_configs = vec![
+            DumpConfig::new("01_restore", vec!["id", "name", "col_blob", "tenant_id"], "all").with_filter(Some(s("tenant_id = 1"))),
+            DumpConfig::new("02_rowid", vec!["message", "note"], "all"),
+        ];
+
+        let snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &snapshot_id, "test dump".to_string(), &dump_configs)?;
+        insert_dump_configs(&conn, &project.project_id, &snapshot_id, &dump_configs)?;
+
+        let target = SqliteConnection::establish(&project.schema)?;
+        for query in [
+            "update \"01_restore\" set name = 'Johnny', col_blob = null, tenant_id = 3 where id = 1",
+            "delete from \"01_restore\" where id = 2",
+            "insert into \"01_restore\" values (4, 'Jill', null, 1)",
+            "update \"01_restore\" set name = 'Janet' where id = 3",
+            "delete from \"02_rowid\" where message = 'error'",
+            "insert into \"02_rowid\" values ('login', null)",
+        ] {
+            sql_query(query).execute(&target)?;
+        }
+
+        // 条件に合わなくなった行は、追加ではなく更新で戻す
+        // 主キーのないテーブルは rowid で行を識別する
+        assert_eq!(4, restore(&conn, &project, &snapshot_id)?);
+
+        let restored_snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &restored_snapshot_id, "test dump".to_string(), &dump_configs)?;
+
+        let exp = find_table_snapshots(&conn, &snapshot_id)?;
+        let act = find_table_snapshots(&conn, &restored_snapshot_id)?;
+
+        assert_eq!(exp[0].row_snapshots, act[0].row_snapshots);
+        assert_eq!(exp[1].row_snapshots, act[1].row_snapshots);
+
+        // 条件に合わない行は変更されない
+        let dump_configs = vec![
+            DumpConfig::new("01_restore", vec!["id", "name", "col_blob", "tenant_id"], "all").with_filter(Some(s("tenant_id = 2"))),
+            DumpConfig::new("02_rowid", vec!["message", "note"], "ignore"),
+        ];
+        let snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &snapshot_id, "test dump".to_string(), &dump_configs)?;
+
+        let act = find_table_snapshots(&conn, &snapshot_id)?;
+        assert_eq!(vec![SimpleString(s("Janet")), Null, SimpleNumber(s("2"))], act[0].row_snapshots[0].col_values);
+
+        // 上限を超えて切り捨てたスナップショットには戻せない
+        let dump_configs = vec![
+            DumpConfig::new("01_restore", vec!["id", "name", "col_blob", "tenant_id"], "limited").with_row_limit(Some(1)),
+            DumpConfig::new("02_rowid", vec!["message", "note"], "ignore"),
+        ];
+        let snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &snapshot_id, "test dump".to_string(), &dump_configs)?;
+        insert_dump_configs(&conn, &project.project_id, &snapshot_id, &dump_configs)?;
+
+        let act = restore(&conn, &project, &snapshot_id).unwrap_err().to_string();
+        assert_eq!("cannot restore a snapshot truncated by row limit: 01_restore", act);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump_live_snapshot() -> anyhow::Result<()> {
+        let project = setup(vec![
+            "create table \"01_live\" ( id integer primary key, name text, tenant_id integer )",
+            "insert into \"01_live\" values (1, 'John', 1), (2, 'Jack', 1), (3, 'Jane', 2)",
+        ])?;
+
+        migrate_sqlite()?;
+        let conn = create_sqlite_connection()?;
+
+        insert_project(&conn, &project)?;
+
+        let dump_configs = vec![DumpConfig::new("01_live", vec!["id", "name", "tenant_id"], "all").with_filter(Some(s("tenant_id = 1")))];
+
+        let snapshot_id = create_snapshot_id();
+        dump(&conn, &project, &snapshot_id, "test dump".to_string(), &dump_configs)?;
+        insert_dump_configs(&conn, &project.project_id, &snapshot_id, &dump_configs)?;
+
+        let target = SqliteConnection::establish(&project.schema)?;
+        for query in [
+            "update \"01_live\" set name = 'Johnny' where id = 1",
+            "insert into \"01_live\" values (4, 'Jill', 2)",
+            "create table \"02_new\" ( id integer primary key, updated_at text )",
+        ] {
+            sql_query(query).execute(&target)?;
+        }
+
+        // スナップショットと同じ条件で dump し、後から追加されたテーブルは含めない
+        let live_snapshot = dump_live(&conn, &project, &snapshot_id)?;
+        let act = &live_snapshot.table_snapshots;
+        assert_eq!(dump_configs, live_snapshot.dump_configs);
+        assert_eq!(vec!["01_live"], act.iter().map(|table_snapshot| &table_snapshot.table_name).collect_vec());
+        assert_eq!(vec![SimpleString(s("Johnny")), SimpleNumber(s("1"))], act[0].row_snapshots[0].col_values);
+        assert_eq!(2, act[0].row_snapshots.len());
+
+        // テーブルの定義は、後から追加されたテーブルも含める
+        assert_eq!(vec!["01_live", "02_new"], live_snapshot.table_definitions.iter().map(|table_definition| &table_definition.table_name).collect_vec());
+
+        // 保存するまではスナップショットが増えない
+        assert_eq!(1, all_snapshot_summaries(&conn, &project.project_id)?.len());
+
+        let live_snapshot_id = create_snapshot_id();
+        keep_live(&conn, &project, &live_snapshot_id, "live".to_string(), live_snapshot)?;
+
+        assert_eq!(2, all_snapshot_summaries(&conn, &project.project_id)?.len());
+        assert_eq!(Some(2), find_table_definitions(&conn, &live_snapshot_id)?.map(|table_definitions| table_definitions.len()));
+        assert_eq!(Some(vec![]), find_db_objects(&conn, &live_snapshot_id)?);
+        assert_eq!(dump_configs, find_dump_config(&conn, &live_snapshot_id)?);
+        assert_eq!(2, find_table_snapshots(&conn, &live_snapshot_id)?[0].row_snapshots.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_table_definitions() -> anyhow::Result<()> {
+        let project = setup(vec![
+            "create table \"01_groups\" ( id integer primary key, code text not null unique )",
+            "create table \"02_users\" ( id integer primary key, name varchar(255) not null default 'guest', group_id integer references \"01_groups\" (id), updated_at datetime )",
+            "create index idx_users_name on \"02_users\" ( name, group_id )",
+            "create table \"03_user_groups\" ( user_id integer, group_id integer, primary key (user_id, group_id) )",
+        ])?;
+
+        let mut sut = TargetDbSqlite::new(&project)?;
+        let act = sut.get_table_definitions()?;
+
+        assert_eq!(vec!["01_groups", "02_users", "03_user_groups"], act.iter().map(|table_definition| &table_definition.table_name).collect_vec());
+
+        assert_eq!(vec!["primary key (id)", "unique (code)"], act[0].indexes.iter().map(|index| index.as_ddl()).collect_vec());
+
+        let users = &act[1];
+        assert_eq!(
+            vec!["integer", "varchar(255) not null default 'guest'", "integer", "datetime"],
+            users.cols.iter().map(|col| col.as_ddl()).collect_vec()
+        );
+        assert_eq!(vec!["primary key (id)", "index (name, group_id)"], users.indexes.iter().map(|index| index.as_ddl()).collect_vec());
+        assert_eq!(vec!["foreign key (group_id) references 01_groups (id)"], users.foreign_keys.iter().map(|foreign_key| foreign_key.as_ddl()).collect_vec());
+
+        // 複合主キーは、主キーの順に並べる
+        assert_eq!(vec!["primary key (user_id, group_id)"], act[2].indexes.iter().map(|index| index.as_ddl()).collect_vec());
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_db_objects() -> anyhow::Result<()> {
+        let project = setup(vec![
+            "create table \"01_users\" ( id integer primary key, name text, updated_at text )",
+            "create view active_users as select * from \"01_users\" where name is not null",
+            "create trigger users_au after update on \"01_users\" begin\n  update \"01_users\" set updated_at = datetime('now') where id = new.id;\nend",
+        ])?;
+
+        let mut sut = TargetDbSqlite::new(&project)?;
+        let act = sut.get_db_objects()?;
+
+        assert_eq!(
+            vec![(DbObjectType::Trigger, "users_au"), (DbObjectType::View, "active_users")],
+            act.iter().map(|db_object| (db_object.object_type.clone(), db_object.object_name.as_str())).collect_vec()
+        );
+        assert_eq!(3, act[0].definition.lines().count());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod parse_col_value_tests {
+    use crate::domain::schema::ColSchema;
+    use crate::domain::snapshot::ColValue;
+    use crate::dump::sqlite::parse_col_value;
+
+    fn sut(data_type: &str, storage_class: &str, value: &str) -> ColValue {
+        parse_col_value(&ColSchema { col_name: "col_test".to_string(), data_type: data_type.to_string() }, storage_class, value.to_string())
+    }
+
+    #[test]
+    fn parse_integer() {
+        let exp = "42";
+        assert_eq!(exp, sut("integer", "integer", "42").as_display_value());
+    }
+
+    #[test]
+    fn parse_real() {
+        let exp = "3.14";
+        assert_eq!(exp, sut("real", "real", "3.14").as_display_value());
+    }
+
+    #[test]
+    fn parse_text() {
+        let exp = r#""abc""#;
+        assert_eq!(exp, sut("text", "text", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_text_in_integer() {
+        let exp = r#""abc""#;
+        assert_eq!(exp, sut("integer", "text", "abc").as_display_value());
+    }
+
+    #[test]
+    fn parse_datetime() {
+        let exp = r#""2020-01-01 00:00:00""#;
+        assert_eq!(exp, sut("DATETIME", "text", "2020-01-01 00:00:00").as_display_value());
+    }
+
+    #[test]
+    fn parse_json() {
+        let exp = r#"{"id": 1}"#;
+        assert_eq!(exp, sut("json", "text", r#"{"id": 1}"#).as_display_value());
+    }
+
+    #[test]
+    fn parse_blob() {
+        let exp = "binary";
+        assert_eq!(exp, sut("blob", "blob", "616263").as_display_value());
+    }
+
+    #[test]
+    fn parse_no_type() {
+        let exp = "42";
+        assert_eq!(exp, sut("", "integer", "42").as_display_value());
+    }
+}
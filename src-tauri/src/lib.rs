@@ -0,0 +1,64 @@
+#[macro_use]
+extern crate diesel;
+
+use tauri::Manager;
+
+use crate::command::state::AppState;
+use crate::db::migrate_sqlite;
+
+mod archive;
+pub mod cli;
+mod command;
+mod db;
+mod domain;
+mod dump;
+mod logger;
+mod report;
+mod script;
+mod workspace;
+
+// コマンドラインは src/bin/db-diff-cli.rs から cli::run を呼び出す
+pub fn run() -> anyhow::Result<()> {
+    match tauri::Builder::default()
+        .invoke_handler(tauri::generate_handler![
+            command::project::all_projects_command,
+            command::project::test_connection_project_command,
+            command::project::insert_project_command,
+            command::project::update_project_command,
+            command::project::delete_project_command,
+            command::project::select_project_command,
+            command::dump_config::find_recent_dump_configs_command,
+            command::dump_config::find_dump_configs_command,
+            command::snapshot::all_snapshot_summaries_command,
+            command::snapshot::update_snapshot_summary_command,
+            command::snapshot::delete_snapshot_summary_command,
+            command::snapshot::dump_snapshot_command,
+            command::snapshot::get_snapshot_result_command,
+            command::snapshot::restore_snapshot_command,
+            command::diff::find_snapshot_diff_command,
+            command::diff::create_snapshot_diff_command,
+            command::diff::diff_live_command,
+            command::diff::create_cross_project_diff_command,
+            command::diff::create_chained_diff_command,
+            command::script::create_sql_script_command,
+            command::report::export_diff_report_command,
+            command::archive::export_archive_command,
+            command::archive::import_archive_command,
+            command::timeline::find_row_timeline_command,
+        ])
+        .setup(|app| {
+            migrate_sqlite()?;
+
+            let state = AppState::new()?;
+            app.manage(state);
+
+            Ok(())
+        })
+        .run(tauri::generate_context!())
+    {
+        Ok(_) => {}
+        Err(e) => logger::error(e.to_string()),
+    };
+
+    Ok(())
+}
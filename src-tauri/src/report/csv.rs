@@ -0,0 +1,62 @@
+use itertools::Itertools;
+
+use crate::domain::diff::TableDiff;
+use crate::report::{get_rows, Cell};
+
+// 1 行に、カラムごとの変更前と変更後の値を並べる
+pub fn create_csv(table_diff: &TableDiff) -> String {
+    let mut lines = vec![];
+
+    let mut header = vec![table_diff.primary_col_name.clone(), "status".to_string()];
+    for col_name in &table_diff.col_names {
+        header.push(format!("{col_name}_before"));
+        header.push(format!("{col_name}_after"));
+    }
+    lines.push(header.iter().map(|s| escape(s)).join(","));
+
+    for row in get_rows(table_diff) {
+        let mut values = vec![escape(&row.primary_value), row.kind().to_string()];
+        for i in 0..table_diff.col_names.len() {
+            values.push(as_value(row.cells1.as_ref().map(|cells| &cells[i])));
+            values.push(as_value(row.cells2.as_ref().map(|cells| &cells[i])));
+        }
+        lines.push(values.join(","));
+    }
+
+    lines.push("".to_string());
+    lines.join("\n")
+}
+
+fn as_value(cell: Option<&Cell>) -> String {
+    cell.map_or("".to_string(), |cell| escape(&cell.value))
+}
+
+// カンマ、ダブルクォート、改行を含む値はダブルクォートで囲む
+fn escape(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!(r#""{}""#, s.replace('"', r#""""#))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::csv::create_csv;
+    use crate::report::tests::mk_table_diff;
+
+    #[test]
+    fn csv() {
+        assert_eq!(
+            [
+                "id,status,name_before,name_after,age_before,age_after",
+                r#"1,updated,"""John""","""John""",20,21"#,
+                r#"2,deleted,"""Jack | Jr.""",,<null>,"#,
+                r#"3,added,,"""<Jane>""",,<null>"#,
+                "",
+            ]
+            .join("\n"),
+            create_csv(&mk_table_diff())
+        );
+    }
+}
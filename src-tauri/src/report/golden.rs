@@ -0,0 +1,241 @@
+use itertools::Itertools;
+
+use crate::domain::diff::SnapshotDiff;
+use crate::domain::line_diff::diff_lines;
+use crate::report::{get_db_object_lines, get_notes, get_rows, get_schema_line, Cell, Status};
+
+// 形式を変更したら上げる ( 期待値のファイルを記録し直す必要がある )
+const GOLDEN_HEADER: &str = "# db-diff golden diff v1";
+
+// 比較結果と一致しない行の前後に表示する行数
+const CONTEXT_LINES: usize = 2;
+
+// 期待値としてリポジトリに置くための形式で、ID や日時などの実行ごとに変わる値は含めない
+// テーブルは名前順、主キーのない行は内容順に並べ、何度作成しても同じ内容になるようにする
+pub fn create_golden(snapshot_diff: &SnapshotDiff) -> String {
+    let mut lines = vec![GOLDEN_HEADER.to_string()];
+    if !snapshot_diff.ignore_rules.is_empty() {
+        lines.push(format!("ignore: {}", snapshot_diff.ignore_rules.iter().sorted().join(", ")));
+    }
+
+    // テーブルの定義やオブジェクトを記録していないスナップショットの期待値は変わらないように、変更がある場合だけ出力する
+    if !snapshot_diff.schema_changes.is_empty() {
+        lines.push("".to_string());
+        lines.push("[schema]".to_string());
+        lines.extend(snapshot_diff.schema_changes.iter().map(get_schema_line));
+    }
+    if !snapshot_diff.db_object_diffs.is_empty() {
+        lines.push("".to_string());
+        lines.push("[objects]".to_string());
+        lines.extend(snapshot_diff.db_object_diffs.iter().flat_map(get_db_object_lines));
+    }
+
+    for table_diff in snapshot_diff.table_diffs.iter().sorted_by_key(|table_diff| &table_diff.table_name) {
+        lines.push("".to_string());
+        lines.push(format!("[{}]", table_diff.table_name));
+        lines.extend(get_notes(table_diff).iter().map(|note| format!("# {note}")));
+
+        let mut row_lines = vec![];
+        for (primary_col_values, row) in table_diff.primary_col_values.iter().zip(get_rows(table_diff)) {
+            // 主キーのない行の連番は dump した順で変わるので、代わりに出現回数を表示する
+            let primary = match table_diff.row_counts.get(&primary_col_values.as_primary_value()) {
+                Some((count1, count2)) if table_diff.keyless => format!("count: {count1} -> {count2}"),
+                _ => format!("{}={}", table_diff.primary_col_name, escape(&row.primary_value)),
+            };
+
+            let line = match (&row.cells1, &row.cells2) {
+                (Some(cells1), Some(cells2)) => {
+                    let changes = table_diff
+                        .col_names
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| cells1[*i].status != Status::Stay || cells2[*i].status != Status::Stay)
+                        .map(|(i, col_name)| format!("{}: {} -> {}", col_name, as_value(&cells1[i]), as_value(&cells2[i])));
+                    format!("~ {} | {}", primary, changes.collect_vec().join(", "))
+                }
+                (Some(cells), None) | (None, Some(cells)) => {
+                    let sign = if row.cells1.is_some() { "-" } else { "+" };
+                    let values = table_diff.col_names.iter().zip(cells).map(|(col_name, cell)| format!("{}={}", col_name, as_value(cell)));
+                    format!("{} {} | {}", sign, primary, values.collect_vec().join(" "))
+                }
+                (None, None) => continue,
+            };
+            row_lines.push(line);
+        }
+
+        if table_diff.keyless {
+            row_lines.sort();
+        }
+        lines.extend(row_lines);
+    }
+
+    lines.push("".to_string());
+    lines.join("\n")
+}
+
+// 一致しない場合は、期待値 ( - ) と実際の差分 ( + ) の異なる行を前後の行と一緒に返す
+pub fn compare_golden(expected: &str, actual: &str) -> Option<String> {
+    let expected = expected.replace("\r\n", "\n");
+    let expected = expected.trim_end().lines().collect_vec();
+    let actual = actual.trim_end().lines().collect_vec();
+
+    if expected == actual {
+        return None;
+    }
+
+    let edits = diff_lines(&expected, &actual);
+    let changed = edits.iter().enumerate().filter(|(_, (sign, _))| *sign != ' ').map(|(i, _)| i).collect_vec();
+
+    let mut lines = vec!["--- expected".to_string(), "+++ actual".to_string()];
+    let mut last = None;
+    for (i, (sign, line)) in edits.iter().enumerate() {
+        if !changed.iter().any(|&j| i.abs_diff(j) <= CONTEXT_LINES) {
+            continue;
+        }
+        if last.map_or(i != 0, |last| last + 1 != i) {
+            lines.push("...".to_string());
+        }
+        lines.push(format!("{sign} {line}"));
+        last = Some(i);
+    }
+
+    Some(lines.join("\n"))
+}
+
+fn as_value(cell: &Cell) -> String {
+    match cell.status {
+        Status::None => "<none>".to_string(),
+        _ => escape(&cell.value),
+    }
+}
+
+// 1 行に 1 つの差分を書くので、値に含まれる改行はエスケープする
+fn escape(s: &str) -> String {
+    s.replace('\\', r"\\").replace('\r', r"\r").replace('\n', r"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::diff::{create_snapshot_diff, SnapshotDiff};
+    use crate::domain::ignore_rule::IgnoreRules;
+    use crate::domain::snapshot::ColValue::{SimpleNumber, SimpleString};
+    use crate::domain::snapshot::{RowSnapshot, TableSnapshot};
+    use crate::report::golden::{compare_golden, create_golden};
+    use crate::report::tests::{mk_db_object_diffs, mk_schema_changes, mk_table_diff};
+
+    #[test]
+    fn golden() {
+        let mut table_diff = mk_table_diff();
+        table_diff.truncated = true;
+        let snapshot_diff = SnapshotDiff::new(&"diff".to_string(), &"id1".to_string(), &"id2".to_string(), vec![table_diff])
+            .with_ignore_rules(&IgnoreRules::parse(&["*.updated_at".to_string()]).unwrap());
+
+        assert_eq!(
+            [
+                "# db-diff golden diff v1",
+                "ignore: *.updated_at",
+                "",
+                "[users]",
+                "# truncated by row limit",
+                "~ id=1 | age: 20 -> 21",
+                r#"- id=2 | name="Jack | Jr." age=<null>"#,
+                r#"+ id=3 | name="<Jane>" age=<null>"#,
+                "",
+            ]
+            .join("\n"),
+            create_golden(&snapshot_diff)
+        );
+    }
+
+    #[test]
+    fn golden_schema() {
+        let snapshot_diff = SnapshotDiff::new(&"diff".to_string(), &"id1".to_string(), &"id2".to_string(), vec![])
+            .with_schema_changes(mk_schema_changes())
+            .with_db_object_diffs(mk_db_object_diffs());
+
+        assert_eq!(
+            [
+                "# db-diff golden diff v1",
+                "",
+                "[schema]",
+                "~ column users.name | varchar(100) not null -> varchar(255) not null",
+                "- column users.age | int",
+                "",
+                "[objects]",
+                "~ trigger users_bi",
+                "  +   set new.updated_at = now();",
+                "",
+            ]
+            .join("\n"),
+            create_golden(&snapshot_diff)
+        );
+    }
+
+    #[test]
+    fn golden_keyless() {
+        let s = |s: &str| SimpleString(s.to_string());
+        let mk = |rows: Vec<&str>| {
+            let row_snapshots = rows.into_iter().map(|v| RowSnapshot::new(vec![], vec![s(v)])).collect();
+            TableSnapshot::new(&"logs".to_string(), "".to_string(), vec!["message".to_string()], row_snapshots)
+        };
+
+        // dump した順が変わっても同じ内容になる
+        let act1 = create_golden(&create_snapshot_diff(
+            &"id1".to_string(),
+            &[mk(vec!["b", "a", "a"])],
+            &"id2".to_string(),
+            &[mk(vec!["a", "c"])],
+            &IgnoreRules::default(),
+        ));
+        let act2 = create_golden(&create_snapshot_diff(
+            &"id1".to_string(),
+            &[mk(vec!["a", "a", "b"])],
+            &"id2".to_string(),
+            &[mk(vec!["c", "a"])],
+            &IgnoreRules::default(),
+        ));
+
+        assert_eq!(act1, act2);
+        assert!(act1.ends_with(
+            &[
+                "[logs]",
+                "# compared as multiset ( no primary key )",
+                r#"+ count: 0 -> 1 | message="c""#,
+                r#"- count: 1 -> 0 | message="b""#,
+                r#"- count: 2 -> 1 | message="a""#,
+                "",
+            ]
+            .join("\n")
+        ));
+    }
+
+    #[test]
+    fn golden_escape() {
+        let rows1 = vec![RowSnapshot::new(vec![SimpleNumber("1".to_string())], vec![SimpleString("a\nb".to_string())])];
+        let rows2 = vec![];
+        let table_snapshot1 = TableSnapshot::new(&"notes".to_string(), "id".to_string(), vec!["body".to_string()], rows1);
+        let table_snapshot2 = TableSnapshot::new(&"notes".to_string(), "id".to_string(), vec!["body".to_string()], rows2);
+
+        let act = create_golden(&create_snapshot_diff(
+            &"id1".to_string(),
+            &[table_snapshot1],
+            &"id2".to_string(),
+            &[table_snapshot2],
+            &IgnoreRules::default(),
+        ));
+
+        assert!(act.contains(r#"- id=1 | body="a\nb""#));
+    }
+
+    #[test]
+    fn compare() {
+        let expected = ["h", "1", "2", "3", "4", "5", "6", "7", ""].join("\r\n");
+        let actual = ["h", "1", "2", "3", "4", "x", "6", "7", "8"].join("\n");
+
+        assert_eq!(None, compare_golden(&expected, &expected.replace("\r\n", "\n")));
+        assert_eq!(
+            Some(["--- expected", "+++ actual", "...", "  3", "  4", "- 5", "+ x", "  6", "  7", "+ 8"].join("\n")),
+            compare_golden(&expected, &actual)
+        );
+    }
+}
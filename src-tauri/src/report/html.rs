@@ -0,0 +1,92 @@
+use itertools::Itertools;
+
+use crate::domain::diff::TableDiff;
+use crate::report::{get_notes, get_rows, Cell, Status};
+
+// 差分表示と同じ色を使い、外部のファイルを参照しない 1 つの HTML にする
+const STYLE: &str = "body { font-family: sans-serif; margin: 1rem 2rem; }
+table { border-collapse: collapse; font-family: Consolas, monospace; margin-bottom: 2rem; }
+th, td { border: solid 1px gray; padding: 0 16px; height: 2rem; white-space: nowrap; text-align: left; font-weight: normal; }
+.note { font-size: 0.8rem; color: gray; }
+.stay { background-color: white; }
+.added { background-color: #cfc; }
+.deleted { background-color: #fcc; }
+.none { background-color: #ddd; }
+.null { color: gray; }";
+
+pub fn create_html(title: &str, table_diffs: &[&TableDiff]) -> String {
+    let mut lines = vec![
+        "<!DOCTYPE html>".to_string(),
+        "<html>".to_string(),
+        "<head>".to_string(),
+        r#"<meta charset="utf-8">"#.to_string(),
+        format!("<title>{}</title>", escape(title)),
+        format!("<style>\n{STYLE}\n</style>"),
+        "</head>".to_string(),
+        "<body>".to_string(),
+        format!("<h1>{}</h1>", escape(title)),
+    ];
+
+    for table_diff in table_diffs {
+        lines.push(format!("<h2>{}</h2>", escape(&table_diff.table_name)));
+        for note in get_notes(table_diff) {
+            lines.push(format!(r#"<p class="note">{}</p>"#, escape(&note)));
+        }
+
+        lines.push("<table>".to_string());
+        let header = [&table_diff.primary_col_name].into_iter().chain(&table_diff.col_names).map(|col_name| format!("<th>{}</th>", escape(col_name)));
+        lines.push(format!("<tr>{}</tr>", header.collect_vec().join("")));
+
+        // 主キーの列は 2 行にまたがり、片方にしかない行は空のセルで埋める
+        for row in get_rows(table_diff) {
+            let empty_cells = || table_diff.col_names.iter().map(|_| r#"<td class="none"></td>"#.to_string()).collect_vec();
+            let cells1 = row.cells1.as_ref().map_or_else(empty_cells, |cells| cells.iter().map(as_td).collect_vec());
+            let cells2 = row.cells2.as_ref().map_or_else(empty_cells, |cells| cells.iter().map(as_td).collect_vec());
+
+            lines.push(format!(r#"<tr><td rowspan="2">{}</td>{}</tr>"#, escape(&row.primary_value), cells1.join("")));
+            lines.push(format!("<tr>{}</tr>", cells2.join("")));
+        }
+        lines.push("</table>".to_string());
+    }
+
+    lines.push("</body>".to_string());
+    lines.push("</html>".to_string());
+    lines.push("".to_string());
+    lines.join("\n")
+}
+
+fn as_td(cell: &Cell) -> String {
+    let class = match cell.status {
+        Status::Stay => "stay",
+        Status::Added => "added",
+        Status::Deleted => "deleted",
+        Status::None => "none",
+    };
+    let class = if cell.value == "<null>" { format!("{class} null") } else { class.to_string() };
+
+    format!(r#"<td class="{}">{}</td>"#, class, escape(&cell.value))
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::html::create_html;
+    use crate::report::tests::mk_table_diff;
+
+    #[test]
+    fn html() {
+        let table_diff = mk_table_diff();
+
+        let act = create_html("before → after", &[&table_diff]);
+
+        assert!(act.contains("<h1>before → after</h1>"));
+        assert!(act.contains("<tr><th>id</th><th>name</th><th>age</th></tr>"));
+        assert!(act.contains(r#"<tr><td rowspan="2">1</td><td class="stay">&quot;John&quot;</td><td class="deleted">20</td></tr>"#));
+        assert!(act.contains(r#"<tr><td class="stay">&quot;John&quot;</td><td class="added">21</td></tr>"#));
+        assert!(act.contains(r#"<tr><td class="none"></td><td class="none"></td></tr>"#));
+        assert!(act.contains(r#"<td class="added">&quot;&lt;Jane&gt;&quot;</td><td class="added null">&lt;null&gt;</td>"#));
+    }
+}
@@ -0,0 +1,89 @@
+use itertools::Itertools;
+
+use crate::domain::diff::TableDiff;
+use crate::report::{get_notes, get_rows, Cell, Status};
+
+// テーブルごとに、スナップショット 1 の行を - 、スナップショット 2 の行を + として並べる
+pub fn create_markdown(title: &str, table_diffs: &[&TableDiff]) -> String {
+    let mut lines = vec![format!("# {}", escape(title))];
+
+    for table_diff in table_diffs {
+        lines.push("".to_string());
+        lines.push(format!("## {}", escape(&table_diff.table_name)));
+        lines.push("".to_string());
+
+        let notes = get_notes(table_diff);
+        if !notes.is_empty() {
+            lines.extend(notes.iter().map(|note| format!("- {}", escape(note))));
+            lines.push("".to_string());
+        }
+
+        let header =
+            [escape(&table_diff.primary_col_name), "".to_string()].into_iter().chain(table_diff.col_names.iter().map(|col_name| escape(col_name)));
+        lines.push(format!("| {} |", header.clone().join(" | ")));
+        lines.push(format!("|{}|", header.map(|_| "---").join("|")));
+
+        for row in get_rows(table_diff) {
+            // 両方にある行は、変更されたカラムを太字にする
+            let updated = row.cells1.is_some() && row.cells2.is_some();
+            for (sign, cells) in [("-", &row.cells1), ("+", &row.cells2)] {
+                if let Some(cells) = cells {
+                    let values = cells.iter().map(|cell| as_value(cell, updated));
+                    lines.push(format!("| {} | {} | {} |", escape(&row.primary_value), sign, values.collect_vec().join(" | ")));
+                }
+            }
+        }
+    }
+
+    lines.push("".to_string());
+    lines.join("\n")
+}
+
+fn as_value(cell: &Cell, updated: bool) -> String {
+    match cell.status {
+        Status::Added | Status::Deleted if updated => format!("**{}**", escape(&cell.value)),
+        _ => escape(&cell.value),
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', r"\\")
+        .replace('|', r"\|")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('*', r"\*")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::markdown::create_markdown;
+    use crate::report::tests::mk_table_diff;
+
+    #[test]
+    fn markdown() {
+        let mut table_diff = mk_table_diff();
+        table_diff.truncated = true;
+
+        assert_eq!(
+            [
+                "# before → after",
+                "",
+                "## users",
+                "",
+                "- truncated by row limit",
+                "",
+                "| id |  | name | age |",
+                "|---|---|---|---|",
+                r#"| 1 | - | "John" | **20** |"#,
+                r#"| 1 | + | "John" | **21** |"#,
+                r#"| 2 | - | "Jack \| Jr." | &lt;null&gt; |"#,
+                r#"| 3 | + | "&lt;Jane&gt;" | &lt;null&gt; |"#,
+                "",
+            ]
+            .join("\n"),
+            create_markdown("before → after", &[&table_diff])
+        );
+    }
+}
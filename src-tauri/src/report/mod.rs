@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::fs::{create_dir_all, write};
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use itertools::Itertools;
+
+use crate::domain::db_object::{DbObjectDiff, LineStatus};
+use crate::domain::diff::ColDiff::{Added, Deleted, NoValue, Stay};
+use crate::domain::diff::{ColDiff, TableDiff};
+use crate::domain::schema::{ColName, PrimaryValue, TableName};
+use crate::domain::table_definition::{SchemaChange, SchemaObject, SchemaStatus};
+
+mod csv;
+mod golden;
+mod html;
+mod markdown;
+mod text;
+
+pub use golden::{compare_golden, create_golden};
+pub use text::create_text;
+
+#[derive(Eq, PartialEq, Debug)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+    Csv,
+}
+
+impl ReportFormat {
+    pub fn parse(format: &str) -> anyhow::Result<Self> {
+        match format {
+            "markdown" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            "csv" => Ok(ReportFormat::Csv),
+            _ => Err(anyhow!("unknown report format: {}", format)),
+        }
+    }
+}
+
+// Markdown と HTML は 1 つのファイルに、CSV は path をディレクトリとしてテーブルごとのファイルに書き出す
+pub fn write_report(format: &ReportFormat, path: &Path, title: &str, table_diffs: &[TableDiff]) -> anyhow::Result<Vec<PathBuf>> {
+    let table_diffs = table_diffs.iter().sorted_by_key(|table_diff| &table_diff.table_name).collect_vec();
+
+    let files = match format {
+        ReportFormat::Markdown => vec![(path.to_path_buf(), markdown::create_markdown(title, &table_diffs))],
+        ReportFormat::Html => vec![(path.to_path_buf(), html::create_html(title, &table_diffs))],
+        ReportFormat::Csv => {
+            create_dir_all(path)?;
+            let files =
+                table_diffs.iter().map(|table_diff| (path.join(as_file_name(&table_diff.table_name)), csv::create_csv(table_diff))).collect_vec();
+            if let Some((duplicated, _)) = files.iter().duplicates_by(|(path, _)| path).next() {
+                return Err(anyhow!("cannot write report: some table names have the same file name: {}", duplicated.display()));
+            }
+            files
+        }
+    };
+
+    for (path, content) in &files {
+        write(path, content).map_err(|e| anyhow!("cannot write report: {}: {}", path.display(), e))?;
+    }
+
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+// ディレクトリの外に書き出さないように、パスの区切りなどファイル名に使えない文字を _ にする
+fn as_file_name(table_name: &TableName) -> String {
+    let file_name = table_name.chars().map(|c| if c.is_control() || r#"/\:*?"<>|"#.contains(c) { '_' } else { c }).collect::<String>();
+    if file_name.chars().all(|c| c == '.') {
+        format!("_{}.csv", file_name)
+    } else {
+        format!("{}.csv", file_name)
+    }
+}
+
+#[derive(Eq, PartialEq, Debug)]
+enum Status {
+    Stay,
+    Added,
+    Deleted,
+    None,
+}
+
+struct Cell {
+    status: Status,
+    value: String,
+}
+
+// 主キーごとに、スナップショット 1 と 2 の行を並べる ( 片方にしかない行は None )
+struct Row {
+    primary_value: PrimaryValue,
+    cells1: Option<Vec<Cell>>,
+    cells2: Option<Vec<Cell>>,
+}
+
+impl Row {
+    fn kind(&self) -> &'static str {
+        match (&self.cells1, &self.cells2) {
+            (Some(_), Some(_)) => "updated",
+            (Some(_), None) => "deleted",
+            (None, _) => "added",
+        }
+    }
+}
+
+fn get_rows(table_diff: &TableDiff) -> Vec<Row> {
+    let cells = |row_diff: Option<&HashMap<ColName, ColDiff>>| {
+        row_diff.map(|row_diff| {
+            table_diff
+                .col_names
+                .iter()
+                .map(|col_name| match row_diff.get(col_name) {
+                    Some(Stay(v)) => Cell { status: Status::Stay, value: v.as_display_value() },
+                    Some(Added(v)) => Cell { status: Status::Added, value: v.as_display_value() },
+                    Some(Deleted(v)) => Cell { status: Status::Deleted, value: v.as_display_value() },
+                    Some(NoValue) | None => Cell { status: Status::None, value: "".to_string() },
+                })
+                .collect_vec()
+        })
+    };
+
+    table_diff
+        .primary_col_values
+        .iter()
+        .map(|primary_col_values| {
+            let primary_value = primary_col_values.as_primary_value();
+            Row {
+                cells1: cells(table_diff.row_diffs1.get(&primary_value)),
+                cells2: cells(table_diff.row_diffs2.get(&primary_value)),
+                primary_value: get_label(table_diff, primary_value),
+            }
+        })
+        .collect()
+}
+
+// 主キーのないテーブルは、連番と 2 つのスナップショットでの出現回数を表示する
+fn get_label(table_diff: &TableDiff, primary_value: PrimaryValue) -> String {
+    match table_diff.row_counts.get(&primary_value) {
+        Some((count1, count2)) if table_diff.keyless => format!("{primary_value} ( ×{count1} → ×{count2} )"),
+        _ => primary_value,
+    }
+}
+
+// 差分表示と同じく、比較の対象外になった行やカラムを注記する
+fn get_notes(table_diff: &TableDiff) -> Vec<String> {
+    let mut notes = vec![];
+
+    if table_diff.truncated {
+        notes.push("truncated by row limit".to_string());
+    }
+    if table_diff.keyless {
+        notes.push("compared as multiset ( no primary key )".to_string());
+    }
+    for (i, excluded_col_names) in [&table_diff.excluded_col_names1, &table_diff.excluded_col_names2].iter().enumerate() {
+        if !excluded_col_names.is_empty() {
+            notes.push(format!("excluded in snapshot {}: {}", i + 1, excluded_col_names.join(", ")));
+        }
+    }
+    if !table_diff.ignored_col_names.is_empty() {
+        notes.push(format!("ignored: {}", table_diff.ignored_col_names.join(", ")));
+    }
+    if let Some(renamed_from) = &table_diff.renamed_from {
+        notes.push(format!("renamed from {}", renamed_from));
+    }
+    if !table_diff.renamed_col_names.is_empty() {
+        let renamed_col_names = table_diff.renamed_col_names.iter().sorted().map(|(new, old)| format!("{old} -> {new}"));
+        notes.push(format!("renamed columns: {}", renamed_col_names.collect_vec().join(", ")));
+    }
+
+    notes
+}
+
+// テーブルの定義の変更を、行の差分と同じ記号で 1 行にする
+fn get_schema_line(schema_change: &SchemaChange) -> String {
+    let name = match schema_change.object {
+        SchemaObject::Table => schema_change.table_name.clone(),
+        _ => format!("{}.{}", schema_change.table_name, schema_change.name),
+    };
+    let (sign, definition) = match (&schema_change.definition1, &schema_change.definition2) {
+        (Some(definition1), Some(definition2)) => ("~", format!("{definition1} -> {definition2}")),
+        (Some(definition), None) => ("-", definition.clone()),
+        (None, Some(definition)) => ("+", definition.clone()),
+        (None, None) => ("~", "".to_string()),
+    };
+    format!("{} {} {} | {}", sign, schema_change.object.as_str(), name, definition)
+}
+
+// オブジェクトごとに見出しの行と、追加・削除された定義の行を返す ( 変更のない行は含めない )
+fn get_db_object_lines(db_object_diff: &DbObjectDiff) -> Vec<String> {
+    let sign = match db_object_diff.status {
+        SchemaStatus::Added => "+",
+        SchemaStatus::Dropped => "-",
+        SchemaStatus::Altered => "~",
+    };
+    let mut lines = vec![format!("{} {} {}", sign, db_object_diff.object_type.as_str(), db_object_diff.object_name)];
+    lines.extend(
+        db_object_diff
+            .line_diffs
+            .iter()
+            .filter(|line_diff| line_diff.status != LineStatus::Stay)
+            .map(|line_diff| format!("  {} {}", line_diff.status.as_sign(), line_diff.line)),
+    );
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::temp_dir;
+    use std::fs::read_to_string;
+
+    use crate::domain::db_object::DbObjectType::Trigger;
+    use crate::domain::db_object::{create_db_object_diffs, DbObject, DbObjectDiff};
+    use crate::domain::diff::{create_table_diff, TableDiff};
+    use crate::domain::ignore_rule::IgnoreRules;
+    use crate::domain::project::create_project_id;
+    use crate::domain::snapshot::ColValue::{Null, SimpleNumber, SimpleString};
+    use crate::domain::snapshot::{RowSnapshot, TableSnapshot};
+    use crate::domain::table_definition::{create_schema_changes, ColDefinition, SchemaChange, TableDefinition};
+    use crate::report::{as_file_name, write_report, ReportFormat};
+
+    pub fn mk_table_diff() -> TableDiff {
+        let n = |s: &str| SimpleNumber(s.to_string());
+        let s = |s: &str| SimpleString(s.to_string());
+
+        let rows1 = vec![RowSnapshot::new(vec![n("1")], vec![s("John"), n("20")]), RowSnapshot::new(vec![n("2")], vec![s("Jack | Jr."), Null])];
+        let rows2 = vec![RowSnapshot::new(vec![n("1")], vec![s("John"), n("21")]), RowSnapshot::new(vec![n("3")], vec![s("<Jane>"), Null])];
+
+        let col_names = vec!["name".to_string(), "age".to_string()];
+        let users1 = TableSnapshot::new(&"users".to_string(), "id".to_string(), col_names.clone(), rows1);
+        let users2 = TableSnapshot::new(&"users".to_string(), "id".to_string(), col_names, rows2);
+
+        create_table_diff(Some(&users1), Some(&users2), &IgnoreRules::default())
+    }
+
+    pub fn mk_db_object_diffs() -> Vec<DbObjectDiff> {
+        let db_objects1 = vec![DbObject::new(Trigger, "users_bi", "begin\n  set new.created_at = now();\nend")];
+        let db_objects2 = vec![DbObject::new(Trigger, "users_bi", "begin\n  set new.created_at = now();\n  set new.updated_at = now();\nend")];
+
+        create_db_object_diffs(Some(&db_objects1), Some(&db_objects2))
+    }
+
+    pub fn mk_schema_changes() -> Vec<SchemaChange> {
+        let users = |cols: Vec<ColDefinition>| TableDefinition { table_name: "users".to_string(), cols, indexes: vec![], foreign_keys: vec![] };
+
+        let definitions1 = vec![users(vec![ColDefinition::new("name", "varchar(100)", false), ColDefinition::new("age", "int", true)])];
+        let definitions2 = vec![users(vec![ColDefinition::new("name", "varchar(255)", false)])];
+
+        create_schema_changes(Some(&definitions1), Some(&definitions2))
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(ReportFormat::Markdown, ReportFormat::parse("markdown").unwrap());
+        assert_eq!(ReportFormat::Csv, ReportFormat::parse("csv").unwrap());
+        assert_eq!("unknown report format: pdf", ReportFormat::parse("pdf").unwrap_err().to_string());
+    }
+
+    #[test]
+    fn write_csv() -> anyhow::Result<()> {
+        let dir = temp_dir().join(format!("db-diff-test-{}", create_project_id()));
+
+        let act = write_report(&ReportFormat::Csv, &dir, "", &[mk_table_diff()])?;
+
+        assert_eq!(vec![dir.join("users.csv")], act);
+        assert!(read_to_string(dir.join("users.csv"))?.starts_with("id,status,name_before,name_after,age_before,age_after\n"));
+
+        // テーブル名にパスの区切りなどがあっても、ディレクトリの中に書き出す
+        let mk = |table_name: &str| TableDiff { table_name: table_name.to_string(), ..mk_table_diff() };
+        let act = write_report(&ReportFormat::Csv, &dir, "", &[mk("../users"), mk(".."), mk(r"a\b:c")])?;
+
+        assert_eq!(vec![dir.join("_...csv"), dir.join(".._users.csv"), dir.join("a_b_c.csv")], act);
+        assert!(act.iter().all(|path| path.parent() == Some(dir.as_path())));
+
+        let act = write_report(&ReportFormat::Csv, &dir, "", &[mk("a/b"), mk("a_b")]).unwrap_err().to_string();
+        assert!(act.starts_with("cannot write report: some table names have the same file name: "));
+
+        Ok(())
+    }
+
+    #[test]
+    fn file_name() {
+        assert_eq!("users.csv", as_file_name(&"users".to_string()));
+        assert_eq!("public.users.csv", as_file_name(&"public.users".to_string()));
+        assert_eq!(".._.._etc_passwd.csv", as_file_name(&"../../etc/passwd".to_string()));
+        assert_eq!("_..csv", as_file_name(&".".to_string()));
+    }
+}
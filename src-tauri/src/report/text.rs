@@ -0,0 +1,111 @@
+use itertools::Itertools;
+
+use crate::domain::db_object::DbObjectDiff;
+use crate::domain::diff::TableDiff;
+use crate::domain::table_definition::SchemaChange;
+use crate::report::{get_db_object_lines, get_notes, get_rows, get_schema_line, Status};
+
+// 端末で読むための形式で、変更された行は変更のあったカラムだけを表示する
+pub fn create_text(schema_changes: &[SchemaChange], db_object_diffs: &[DbObjectDiff], table_diffs: &[&TableDiff]) -> String {
+    let mut lines = vec![];
+
+    if !schema_changes.is_empty() {
+        lines.push("[schema]".to_string());
+        lines.extend(schema_changes.iter().map(|schema_change| format!("  {}", get_schema_line(schema_change))));
+    }
+    if !db_object_diffs.is_empty() {
+        if !lines.is_empty() {
+            lines.push("".to_string());
+        }
+        lines.push("[objects]".to_string());
+        lines.extend(db_object_diffs.iter().flat_map(get_db_object_lines).map(|line| format!("  {line}")));
+    }
+
+    for table_diff in table_diffs {
+        if !lines.is_empty() {
+            lines.push("".to_string());
+        }
+        lines.push(table_diff.table_name.clone());
+        lines.extend(get_notes(table_diff).iter().map(|note| format!("  # {note}")));
+
+        for row in get_rows(table_diff) {
+            let primary = format!("{}={}", table_diff.primary_col_name, row.primary_value);
+            let line = match (&row.cells1, &row.cells2) {
+                (Some(cells1), Some(cells2)) => {
+                    let changes = table_diff
+                        .col_names
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| cells1[*i].status != Status::Stay || cells2[*i].status != Status::Stay)
+                        .map(|(i, col_name)| format!("{}: {} -> {}", col_name, cells1[i].value, cells2[i].value));
+                    format!("  ~ {} {}", primary, changes.collect_vec().join(", "))
+                }
+                (Some(cells), None) | (None, Some(cells)) => {
+                    let sign = if row.cells1.is_some() { "-" } else { "+" };
+                    let values = table_diff.col_names.iter().zip(cells).map(|(col_name, cell)| format!("{}={}", col_name, cell.value));
+                    format!("  {} {} {}", sign, primary, values.collect_vec().join(" "))
+                }
+                (None, None) => continue,
+            };
+            lines.push(line);
+        }
+    }
+
+    lines.push("".to_string());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::tests::{mk_schema_changes, mk_table_diff};
+    use crate::report::text::create_text;
+
+    #[test]
+    fn text() {
+        let mut table_diff = mk_table_diff();
+        table_diff.truncated = true;
+
+        assert_eq!(
+            [
+                "users",
+                "  # truncated by row limit",
+                "  ~ id=1 age: 20 -> 21",
+                r#"  - id=2 name="Jack | Jr." age=<null>"#,
+                r#"  + id=3 name="<Jane>" age=<null>"#,
+                "",
+            ]
+            .join("\n"),
+            create_text(&[], &[], &[&table_diff])
+        );
+    }
+
+    #[test]
+    fn text_renamed() {
+        let mut table_diff = mk_table_diff();
+        table_diff.renamed_from = Some("members".to_string());
+        table_diff.renamed_col_names.insert("full_name".to_string(), "name".to_string());
+
+        assert_eq!(
+            ["users", "  # renamed from members", "  # renamed columns: name -> full_name", "  ~ id=1 age: 20 -> 21"].join("\n"),
+            create_text(&[], &[], &[&table_diff]).lines().take(4).collect::<Vec<_>>().join("\n")
+        );
+    }
+
+    #[test]
+    fn text_schema() {
+        let table_diff = mk_table_diff();
+
+        assert_eq!(
+            [
+                "[schema]",
+                "  ~ column users.name | varchar(100) not null -> varchar(255) not null",
+                "  - column users.age | int",
+                "",
+                "users",
+                "  ~ id=1 age: 20 -> 21",
+            ]
+            .join("\n"),
+            create_text(&mk_schema_changes(), &[], &[&table_diff]).lines().take(6).collect::<Vec<_>>().join("\n")
+        );
+    }
+}
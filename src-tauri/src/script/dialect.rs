@@ -0,0 +1,157 @@
+use anyhow::anyhow;
+use itertools::Itertools;
+
+use crate::domain::project::Rdbms;
+use crate::domain::snapshot::ColValue;
+use crate::domain::snapshot::ColValue::*;
+
+#[derive(Eq, PartialEq, Debug)]
+enum Kind {
+    Mysql,
+    Postgres,
+    Sqlite,
+}
+
+pub struct Dialect {
+    kind: Kind,
+}
+
+impl Dialect {
+    pub fn new(rdbms: &Rdbms) -> Self {
+        let kind = match rdbms {
+            Rdbms::Mysql | Rdbms::Mariadb => Kind::Mysql,
+            Rdbms::Postgres => Kind::Postgres,
+            Rdbms::Sqlite => Kind::Sqlite,
+        };
+        Self { kind }
+    }
+
+    pub fn delete(&self, table_name: &str, keys: &[(&str, &ColValue)]) -> anyhow::Result<String> {
+        Ok(format!("delete from {} where {}", self.quote_identifier(table_name), self.conditions(keys)?))
+    }
+
+    // 同じ値の行が複数ある場合に、指定した行数だけ削除する
+    pub fn delete_limit(&self, table_name: &str, values: &[(&str, &ColValue)], limit: usize) -> anyhow::Result<String> {
+        let table_name = self.quote_identifier(table_name);
+        let conditions = self.conditions(values)?;
+
+        Ok(match self.kind {
+            Kind::Mysql => format!("delete from {table_name} where {conditions} limit {limit}"),
+            Kind::Postgres => format!("delete from {table_name} where ctid in (select ctid from {table_name} where {conditions} limit {limit})"),
+            Kind::Sqlite => format!("delete from {table_name} where rowid in (select rowid from {table_name} where {conditions} limit {limit})"),
+        })
+    }
+
+    pub fn update(&self, table_name: &str, keys: &[(&str, &ColValue)], changes: &[(&str, &ColValue)]) -> anyhow::Result<String> {
+        let changes = changes
+            .iter()
+            .map(|(col_name, col_value)| Ok(format!("{} = {}", self.quote_identifier(col_name), self.as_literal(col_value)?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(format!("update {} set {} where {}", self.quote_identifier(table_name), changes.join(", "), self.conditions(keys)?))
+    }
+
+    pub fn insert(&self, table_name: &str, values: &[(&str, &ColValue)]) -> anyhow::Result<String> {
+        let col_names = values.iter().map(|(col_name, _)| self.quote_identifier(col_name)).join(", ");
+        let col_values = values.iter().map(|(_, col_value)| self.as_literal(col_value)).collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(format!("insert into {} ({}) values ({})", self.quote_identifier(table_name), col_names, col_values.join(", ")))
+    }
+
+    fn conditions(&self, values: &[(&str, &ColValue)]) -> anyhow::Result<String> {
+        let conditions = values
+            .iter()
+            .map(|(col_name, col_value)| match col_value {
+                Null => Ok(format!("{} is null", self.quote_identifier(col_name))),
+                _ => Ok(format!("{} = {}", self.quote_identifier(col_name), self.as_literal(col_value)?)),
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(conditions.join(" and "))
+    }
+
+    pub fn quote_identifier(&self, identifier: &str) -> String {
+        match self.kind {
+            Kind::Mysql => format!("`{}`", identifier.replace('`', "``")),
+            Kind::Postgres | Kind::Sqlite => format!(r#""{}""#, identifier.replace('"', r#""""#)),
+        }
+    }
+
+    // バイナリは dump 時の取得方法が RDBMS ごとに異なる ( MySQL はそのままの値、PostgreSQL は \x から始まる 16 進数、SQLite は 16 進数 )
+    // PostgreSQL の NaN と Infinity は数値リテラルとして書けないので、文字列として書く
+    pub fn as_literal(&self, col_value: &ColValue) -> anyhow::Result<String> {
+        match col_value {
+            SimpleNumber(v) if v.parse::<f64>().is_ok_and(|v| !v.is_finite()) => Ok(self.quote_string(v)),
+            SimpleNumber(v) => Ok(v.clone()),
+            BitNumber(v) => Ok(format!("b'{v}'")),
+            SimpleString(v) | DateString(v) | JsonString(v) => Ok(self.quote_string(v)),
+            BinaryString(v) => Ok(match self.kind {
+                Kind::Mysql => format!("x'{}'", v.bytes().map(|b| format!("{b:02x}")).join("")),
+                Kind::Postgres => format!("{}::bytea", self.quote_string(v)),
+                Kind::Sqlite => format!("x'{v}'"),
+            }),
+            Null => Ok("null".to_string()),
+            ParseError => Err(anyhow!("cannot create sql from a value that failed to parse")),
+        }
+    }
+
+    fn quote_string(&self, v: &str) -> String {
+        match self.kind {
+            Kind::Mysql => format!("'{}'", v.replace('\\', r"\\").replace('\'', "''")),
+            Kind::Postgres | Kind::Sqlite => format!("'{}'", v.replace('\'', "''")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::project::Rdbms::{Mariadb, Mysql, Postgres, Sqlite};
+    use crate::domain::snapshot::ColValue::*;
+    use crate::script::dialect::Dialect;
+
+    fn s(s: &str) -> String {
+        s.to_string()
+    }
+
+    #[test]
+    fn as_literal() -> anyhow::Result<()> {
+        let mysql = Dialect::new(&Mysql);
+        let mariadb = Dialect::new(&Mariadb);
+        let postgres = Dialect::new(&Postgres);
+        let sqlite = Dialect::new(&Sqlite);
+
+        assert_eq!("42", mysql.as_literal(&SimpleNumber(s("42")))?);
+        assert_eq!("-3.14", postgres.as_literal(&SimpleNumber(s("-3.14")))?);
+        assert_eq!("1e10", postgres.as_literal(&SimpleNumber(s("1e10")))?);
+        assert_eq!("'NaN'", postgres.as_literal(&SimpleNumber(s("NaN")))?);
+        assert_eq!("'Infinity'", postgres.as_literal(&SimpleNumber(s("Infinity")))?);
+        assert_eq!("'-Infinity'", postgres.as_literal(&SimpleNumber(s("-Infinity")))?);
+
+        assert_eq!("b'101'", mysql.as_literal(&BitNumber(s("101")))?);
+        assert_eq!("b'101'", postgres.as_literal(&BitNumber(s("101")))?);
+
+        assert_eq!(r"'it''s \\ ok'", mysql.as_literal(&SimpleString(s(r"it's \ ok")))?);
+        assert_eq!(r"'it''s \\ ok'", mariadb.as_literal(&SimpleString(s(r"it's \ ok")))?);
+        assert_eq!(r"'it''s \ ok'", postgres.as_literal(&SimpleString(s(r"it's \ ok")))?);
+        assert_eq!(r"'it''s \ ok'", sqlite.as_literal(&SimpleString(s(r"it's \ ok")))?);
+
+        assert_eq!("'2023-09-01 12:34:56'", mysql.as_literal(&DateString(s("2023-09-01 12:34:56")))?);
+        assert_eq!(r#"'{"name": "John"}'"#, postgres.as_literal(&JsonString(s(r#"{"name": "John"}"#)))?);
+
+        assert_eq!("x'616263'", mysql.as_literal(&BinaryString(s("abc")))?);
+        assert_eq!(r"'\x616263'::bytea", postgres.as_literal(&BinaryString(s(r"\x616263")))?);
+        assert_eq!("x'616263'", sqlite.as_literal(&BinaryString(s("616263")))?);
+
+        assert_eq!("null", sqlite.as_literal(&Null)?);
+        assert!(mysql.as_literal(&ParseError).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn quote_identifier() {
+        assert_eq!("`user``s`", Dialect::new(&Mysql).quote_identifier("user`s"));
+        assert_eq!(r#""user""s""#, Dialect::new(&Postgres).quote_identifier(r#"user"s"#));
+        assert_eq!(r#""users""#, Dialect::new(&Sqlite).quote_identifier("users"));
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::anyhow;
+use itertools::Itertools;
+
+use crate::domain::diff::ColDiff::{Added, Deleted, NoValue, Stay};
+use crate::domain::diff::{ColDiff, TableDiff};
+use crate::domain::project::Rdbms;
+use crate::domain::schema::{ColName, TableName};
+use crate::domain::snapshot::{ColValue, PrimaryColValues};
+use crate::domain::table_definition::TableDefinition;
+use crate::script::dialect::Dialect;
+
+mod dialect;
+
+type Values<'a> = Vec<(&'a str, &'a ColValue)>;
+
+// スナップショット 1 の状態をスナップショット 2 の状態にする SQL を、削除してからテーブルごとに更新 → 追加の順に作成する
+pub fn create_statements(rdbms: &Rdbms, table_diffs: &[TableDiff]) -> anyhow::Result<Vec<String>> {
+    create_ordered_statements(rdbms, table_diffs, &[])
+}
+
+// 外部キーを検査したまま実行できるように、削除は参照する側のテーブルから、更新と追加は参照される側のテーブルから行う
+pub fn create_ordered_statements(rdbms: &Rdbms, table_diffs: &[TableDiff], table_definitions: &[TableDefinition]) -> anyhow::Result<Vec<String>> {
+    let dialect = Dialect::new(rdbms);
+    let table_diffs = sort_by_foreign_keys(table_diffs, table_definitions)?;
+
+    let mut deletes = vec![];
+    let mut upserts = vec![];
+
+    for table_diff in table_diffs {
+        let mut table_deletes = vec![];
+        let mut updates = vec![];
+        let mut inserts = vec![];
+        let primary_col_names = table_diff.get_primary_col_names();
+        for primary_col_values in &table_diff.primary_col_values {
+            let primary_value = primary_col_values.as_primary_value();
+            let row_diff1 = table_diff.row_diffs1.get(&primary_value);
+            let row_diff2 = table_diff.row_diffs2.get(&primary_value);
+
+            if table_diff.keyless {
+                // 主キーのない行は、全カラムの値が一致する行を増減した行数だけ削除・追加する
+                let (count1, count2) = table_diff.row_counts.get(&primary_value).copied().unwrap_or((0, 0));
+                match (row_diff1, row_diff2) {
+                    (Some(row_diff1), None) => table_deletes.push(dialect.delete_limit(
+                        &table_diff.table_name,
+                        &get_values(table_diff, row_diff1),
+                        count1.saturating_sub(count2),
+                    )?),
+                    (None, Some(row_diff2)) => {
+                        let insert = dialect.insert(&table_diff.table_name, &get_values(table_diff, row_diff2))?;
+                        inserts.extend(vec![insert; count2.saturating_sub(count1)]);
+                    }
+                    _ => {}
+                }
+            } else {
+                let primary_values = get_primary_values(table_diff, &primary_col_names, primary_col_values)?;
+                match (row_diff1, row_diff2) {
+                    (Some(_), None) => table_deletes.push(dialect.delete(&table_diff.table_name, &primary_values)?),
+                    (Some(_), Some(row_diff2)) => {
+                        let changes = get_changes(table_diff, row_diff2);
+                        if !changes.is_empty() {
+                            updates.push(dialect.update(&table_diff.table_name, &primary_values, &changes)?);
+                        }
+                    }
+                    (None, Some(row_diff2)) => {
+                        let mut values = primary_values;
+                        values.extend(get_values(table_diff, row_diff2));
+                        inserts.push(dialect.insert(&table_diff.table_name, &values)?);
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+
+        deletes.push(table_deletes);
+        upserts.extend(updates.into_iter().chain(inserts));
+    }
+
+    Ok(deletes.into_iter().rev().flatten().chain(upserts).collect())
+}
+
+// 参照される側のテーブルが先になるように並べる ( 依存関係がなければテーブル名順 )
+fn sort_by_foreign_keys<'a>(table_diffs: &'a [TableDiff], table_definitions: &[TableDefinition]) -> anyhow::Result<Vec<&'a TableDiff>> {
+    let table_names: BTreeSet<&TableName> = table_diffs.iter().map(|table_diff| &table_diff.table_name).collect();
+
+    // 自分自身への参照と、差分に含まれないテーブルへの参照は順序に影響しない
+    let mut ref_table_names: HashMap<&TableName, BTreeSet<&TableName>> = table_definitions
+        .iter()
+        .filter(|table_definition| table_names.contains(&table_definition.table_name))
+        .map(|table_definition| {
+            let ref_table_names = table_definition
+                .foreign_keys
+                .iter()
+                .map(|foreign_key| &foreign_key.ref_table_name)
+                .filter(|&ref_table_name| ref_table_name != &table_definition.table_name && table_names.contains(ref_table_name))
+                .collect();
+            (&table_definition.table_name, ref_table_names)
+        })
+        .collect();
+
+    let mut sorted_table_names = vec![];
+    let mut rest_table_names = table_names;
+    while let Some(&table_name) = rest_table_names.iter().find(|&table_name| ref_table_names.get(table_name).into_iter().all(BTreeSet::is_empty)) {
+        rest_table_names.remove(table_name);
+        for ref_table_names in ref_table_names.values_mut() {
+            ref_table_names.remove(table_name);
+        }
+        sorted_table_names.push(table_name);
+    }
+
+    if !rest_table_names.is_empty() {
+        return Err(anyhow!("cannot order tables by foreign keys, circular references: {}", rest_table_names.iter().join(", ")));
+    }
+
+    Ok(sorted_table_names
+        .into_iter()
+        .flat_map(|table_name| table_diffs.iter().filter(move |table_diff| &table_diff.table_name == table_name))
+        .collect())
+}
+
+// SQL の前に、差分に含まれない行やカラムがあるテーブルをコメントとして記載する
+pub fn create_script(rdbms: &Rdbms, table_diffs: &[TableDiff]) -> anyhow::Result<String> {
+    let mut lines = vec![];
+
+    for table_diff in table_diffs.iter().sorted_by_key(|table_diff| &table_diff.table_name) {
+        if table_diff.truncated {
+            lines.push(format!("-- {}: truncated by row limit, rows over the limit are not included", table_diff.table_name));
+        }
+
+        let col_names =
+            table_diff.excluded_col_names1.iter().chain(&table_diff.excluded_col_names2).chain(&table_diff.ignored_col_names).unique().join(", ");
+        if !col_names.is_empty() {
+            lines.push(format!("-- {}: excluded or ignored columns are not included: {}", table_diff.table_name, col_names));
+        }
+    }
+
+    for statement in create_statements(rdbms, table_diffs)? {
+        lines.push(format!("{statement};"));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+// 複合主キーは、記録したカラム名の一覧と値を組み合わせる
+fn get_primary_values<'a>(
+    table_diff: &TableDiff,
+    primary_col_names: &'a [ColName],
+    primary_col_values: &'a PrimaryColValues,
+) -> anyhow::Result<Values<'a>> {
+    if primary_col_names.len() != primary_col_values.col_values.len() {
+        return Err(anyhow!("cannot split primary key columns: {}.{}", table_diff.table_name, table_diff.primary_col_name));
+    }
+
+    Ok(primary_col_names.iter().map(String::as_str).zip(&primary_col_values.col_values).collect())
+}
+
+fn get_values<'a>(table_diff: &'a TableDiff, row_diff: &'a HashMap<ColName, ColDiff>) -> Values<'a> {
+    table_diff
+        .col_names
+        .iter()
+        .filter_map(|col_name| match row_diff.get(col_name) {
+            Some(Stay(col_value) | Added(col_value) | Deleted(col_value)) => Some((col_name.as_str(), col_value)),
+            Some(NoValue) | None => None,
+        })
+        .collect()
+}
+
+fn get_changes<'a>(table_diff: &'a TableDiff, row_diff: &'a HashMap<ColName, ColDiff>) -> Values<'a> {
+    table_diff
+        .col_names
+        .iter()
+        .filter_map(|col_name| match row_diff.get(col_name) {
+            Some(Added(col_value)) => Some((col_name.as_str(), col_value)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::diff::create_table_diff;
+    use crate::domain::ignore_rule::IgnoreRules;
+    use crate::domain::project::Rdbms::{Mysql, Postgres};
+    use crate::domain::snapshot::ColValue::{Null, SimpleNumber, SimpleString};
+    use crate::domain::snapshot::{ColValue, RowSnapshot, TableSnapshot};
+    use crate::domain::table_definition::{ForeignKeyDefinition, TableDefinition};
+    use crate::script::{create_ordered_statements, create_script, create_statements};
+
+    fn n(s: &str) -> ColValue {
+        SimpleNumber(s.to_string())
+    }
+
+    fn s(s: &str) -> ColValue {
+        SimpleString(s.to_string())
+    }
+
+    fn mk_table_snapshot(table_name: &str, primary_col_name: &str, col_names: Vec<&str>, row_snapshots: Vec<RowSnapshot>) -> TableSnapshot {
+        TableSnapshot::new(
+            &table_name.to_string(),
+            primary_col_name.to_string(),
+            col_names.iter().map(|col_name| col_name.to_string()).collect(),
+            row_snapshots,
+        )
+    }
+
+    #[test]
+    fn statements() -> anyhow::Result<()> {
+        let rows1 = vec![
+            RowSnapshot::new(vec![n("1")], vec![s("John"), n("20")]),
+            RowSnapshot::new(vec![n("2")], vec![s("Jack"), n("30")]),
+            RowSnapshot::new(vec![n("3")], vec![s("Jane"), Null]),
+        ];
+        let users1 = mk_table_snapshot("users", "id", vec!["name", "age"], rows1);
+
+        let rows2 = vec![
+            RowSnapshot::new(vec![n("1")], vec![s("John"), n("21")]),
+            RowSnapshot::new(vec![n("3")], vec![s("Jane"), Null]),
+            RowSnapshot::new(vec![n("4")], vec![s("O'Brien"), Null]),
+        ];
+        let users2 = mk_table_snapshot("users", "id", vec!["name", "age"], rows2);
+
+        let rows1 = vec![RowSnapshot::new(vec![n("1"), s("a")], vec![s("x")])];
+        let groups1 = mk_table_snapshot("groups", "tenant_id-code", vec!["label"], rows1);
+        let rows2 = vec![RowSnapshot::new(vec![n("1"), s("a")], vec![s("y")])];
+        let groups2 = mk_table_snapshot("groups", "tenant_id-code", vec!["label"], rows2);
+
+        let table_diffs = vec![
+            create_table_diff(Some(&users1), Some(&users2), &IgnoreRules::default()),
+            create_table_diff(Some(&groups1), Some(&groups2), &IgnoreRules::default()),
+        ];
+
+        assert_eq!(
+            vec![
+                "delete from `users` where `id` = 2",
+                "update `groups` set `label` = 'y' where `tenant_id` = 1 and `code` = 'a'",
+                "update `users` set `age` = 21 where `id` = 1",
+                "insert into `users` (`id`, `name`, `age`) values (4, 'O''Brien', null)",
+            ],
+            create_statements(&Mysql, &table_diffs)?
+        );
+
+        assert_eq!(
+            vec![
+                r#"delete from "users" where "id" = 2"#,
+                r#"update "groups" set "label" = 'y' where "tenant_id" = 1 and "code" = 'a'"#,
+                r#"update "users" set "age" = 21 where "id" = 1"#,
+                r#"insert into "users" ("id", "name", "age") values (4, 'O''Brien', null)"#,
+            ],
+            create_statements(&Postgres, &table_diffs)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn statements_hyphenated_keys() -> anyhow::Result<()> {
+        let primary_col_names = vec!["order-no".to_string(), "line".to_string()];
+        let rows1 = vec![RowSnapshot::new(vec![s("A-1"), n("1")], vec![n("2")])];
+        let items1 = mk_table_snapshot("items", "order-no-line", vec!["qty"], rows1).with_primary_col_names(primary_col_names.clone());
+        let rows2 = vec![RowSnapshot::new(vec![s("A-1"), n("1")], vec![n("3")])];
+        let items2 = mk_table_snapshot("items", "order-no-line", vec!["qty"], rows2).with_primary_col_names(primary_col_names);
+
+        // - を含むカラム名も、記録した一覧で主キーを組み立てる
+        let table_diffs = vec![create_table_diff(Some(&items1), Some(&items2), &IgnoreRules::default())];
+        assert_eq!(vec!["update `items` set `qty` = 3 where `order-no` = 'A-1' and `line` = 1"], create_statements(&Mysql, &table_diffs)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn statements_keyless() -> anyhow::Result<()> {
+        let row = |message: &str| RowSnapshot::new(vec![], vec![s(message), Null]);
+
+        let logs1 = mk_table_snapshot("logs", "", vec!["message", "note"], vec![row("login"), row("login"), row("login"), row("error")]);
+        let logs2 = mk_table_snapshot("logs", "", vec!["message", "note"], vec![row("login"), row("error"), row("retry"), row("retry")]);
+
+        let table_diffs = vec![create_table_diff(Some(&logs1), Some(&logs2), &IgnoreRules::default())];
+
+        assert_eq!(
+            vec![
+                "delete from `logs` where `message` = 'login' and `note` is null limit 2",
+                "insert into `logs` (`message`, `note`) values ('retry', null)",
+                "insert into `logs` (`message`, `note`) values ('retry', null)",
+            ],
+            create_statements(&Mysql, &table_diffs)?
+        );
+
+        assert_eq!(
+            vec![
+                r#"delete from "logs" where ctid in (select ctid from "logs" where "message" = 'login' and "note" is null limit 2)"#,
+                r#"insert into "logs" ("message", "note") values ('retry', null)"#,
+                r#"insert into "logs" ("message", "note") values ('retry', null)"#,
+            ],
+            create_statements(&Postgres, &table_diffs)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn statements_ordered() -> anyhow::Result<()> {
+        let definition = |table_name: &str, ref_table_names: Vec<&str>| TableDefinition {
+            table_name: table_name.to_string(),
+            cols: vec![],
+            indexes: vec![],
+            foreign_keys: ref_table_names
+                .into_iter()
+                .map(|ref_table_name| ForeignKeyDefinition {
+                    constraint_name: format!("fk_{table_name}_{ref_table_name}"),
+                    col_names: vec![format!("{ref_table_name}_id")],
+                    ref_table_name: ref_table_name.to_string(),
+                    ref_col_names: vec!["id".to_string()],
+                })
+                .collect(),
+        };
+
+        let accounts1 = mk_table_snapshot("accounts", "id", vec!["name"], vec![RowSnapshot::new(vec![n("1")], vec![s("a")])]);
+        let accounts2 = mk_table_snapshot("accounts", "id", vec!["name"], vec![RowSnapshot::new(vec![n("2")], vec![s("b")])]);
+        let users1 = mk_table_snapshot("users", "id", vec!["accounts_id"], vec![RowSnapshot::new(vec![n("1")], vec![n("1")])]);
+        let users2 = mk_table_snapshot("users", "id", vec!["accounts_id"], vec![RowSnapshot::new(vec![n("2")], vec![n("2")])]);
+        let orders1 = mk_table_snapshot("orders", "id", vec!["users_id"], vec![RowSnapshot::new(vec![n("1")], vec![n("1")])]);
+        let orders2 = mk_table_snapshot("orders", "id", vec!["users_id"], vec![RowSnapshot::new(vec![n("1")], vec![n("2")])]);
+
+        let table_diffs = vec![
+            create_table_diff(Some(&accounts1), Some(&accounts2), &IgnoreRules::default()),
+            create_table_diff(Some(&orders1), Some(&orders2), &IgnoreRules::default()),
+            create_table_diff(Some(&users1), Some(&users2), &IgnoreRules::default()),
+        ];
+
+        // 削除は参照する側から、更新と追加は参照される側から行う ( 自分自身への参照は無視する )
+        let table_definitions =
+            vec![definition("accounts", vec![]), definition("orders", vec!["users", "orders"]), definition("users", vec!["accounts"])];
+        assert_eq!(
+            vec![
+                r#"delete from "users" where "id" = 1"#,
+                r#"delete from "accounts" where "id" = 1"#,
+                r#"insert into "accounts" ("id", "name") values (2, 'b')"#,
+                r#"insert into "users" ("id", "accounts_id") values (2, 2)"#,
+                r#"update "orders" set "users_id" = 2 where "id" = 1"#,
+            ],
+            create_ordered_statements(&Postgres, &table_diffs, &table_definitions)?
+        );
+
+        // 循環する参照は順序を決められない
+        let table_definitions = vec![definition("accounts", vec!["users"]), definition("users", vec!["accounts"])];
+        let act = create_ordered_statements(&Postgres, &table_diffs, &table_definitions).unwrap_err().to_string();
+        assert_eq!("cannot order tables by foreign keys, circular references: accounts, users", act);
+
+        Ok(())
+    }
+
+    #[test]
+    fn script() -> anyhow::Result<()> {
+        let users1 = mk_table_snapshot("users", "id", vec!["name", "updated_at"], vec![RowSnapshot::new(vec![n("1")], vec![s("John"), s("a")])]);
+        let users2 = mk_table_snapshot("users", "id", vec!["name", "updated_at"], vec![RowSnapshot::new(vec![n("1")], vec![s("Jane"), s("b")])])
+            .with_truncated(true);
+
+        let ignore_rules = IgnoreRules::parse(&["*.updated_at".to_string()])?;
+        let table_diffs = vec![create_table_diff(Some(&users1), Some(&users2), &ignore_rules)];
+
+        assert_eq!(
+            [
+                "-- users: truncated by row limit, rows over the limit are not included",
+                "-- users: excluded or ignored columns are not included: updated_at",
+                "update `users` set `name` = 'Jane' where `id` = 1;",
+            ]
+            .join("\n"),
+            create_script(&Mysql, &table_diffs)?
+        );
+
+        Ok(())
+    }
+}
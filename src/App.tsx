@@ -8,6 +8,10 @@ import { SnapshotCreatePage } from './page/SnapshotCreatePage'
 import { SnapshotListPage } from './page/SnapshotListPage'
 import { SnapshotUpdatePage } from './page/SnapshotUpdatePage'
 import { DiffPage } from './page/DiffPage'
+import { LiveDiffPage } from './page/LiveDiffPage'
+import { CrossProjectDiffPage } from './page/CrossProjectDiffPage'
+import { ChainedDiffPage } from './page/ChainedDiffPage'
+import { RowTimelinePage } from './page/RowTimelinePage'
 import { Toaster } from 'react-hot-toast'
 import { ErrorPage } from './page/ErrorPage'
 
@@ -25,6 +29,10 @@ export const App: FC = () => {
           <Route path="/snapshot-summary/create" element={<SnapshotCreatePage />} />
           <Route path="/snapshot-summary/update" element={<SnapshotUpdatePage />} />
           <Route path="/diff" element={<DiffPage />} />
+          <Route path="/diff/live" element={<LiveDiffPage />} />
+          <Route path="/diff/cross-project" element={<CrossProjectDiffPage />} />
+          <Route path="/diff/chain" element={<ChainedDiffPage />} />
+          <Route path="/timeline" element={<RowTimelinePage />} />
           <Route path="/error" element={<ErrorPage />} />
         </Routes>
       </Router>
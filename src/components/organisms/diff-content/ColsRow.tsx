@@ -1,5 +1,6 @@
 import React, { type FC, Fragment } from 'react'
 import styles from './Color.module.scss'
+import { type InlineSegment } from '../../../types'
 
 const colors = {
   stay: styles.stay,
@@ -15,6 +16,7 @@ interface Props {
   n: number
   noDiffColNames: string[]
   isShowNoDiffCol: boolean
+  inlineDiff?: Record<string, InlineSegment[]>
 }
 
 export const ColsRow: FC<Props> = (props) => {
@@ -30,9 +32,10 @@ export const ColsRow: FC<Props> = (props) => {
           if (colName in props.rowDiff) {
             if (props.isShowNoDiffCol || !props.noDiffColNames.includes(colName)) {
               const nullStyle = props.rowDiff[colName].value === '<null>' ? styles.null : ''
+              const segments = props.inlineDiff?.[colName]
               return (
                 <td key={i} className={[colors[props.rowDiff[colName].status], nullStyle].join(' ')} align={'left'}>
-                  {props.rowDiff[colName].value}
+                  {segments !== undefined ? <Segments segments={segments} n={props.n} /> : props.rowDiff[colName].value}
                 </td>
               )
             } else {
@@ -48,3 +51,18 @@ export const ColsRow: FC<Props> = (props) => {
     </tr>
   )
 }
+
+// 長い文字列は、スナップショット 1 では削除された部分を、スナップショット 2 では追加された部分を強調する
+const Segments: FC<{ segments: InlineSegment[]; n: number }> = (props) => (
+  <>
+    &quot;
+    {props.segments
+      .filter((segment) => segment.status === 'stay' || segment.status === (props.n === 1 ? 'deleted' : 'added'))
+      .map((segment, i) => (
+        <span key={i} className={segment.status !== 'stay' ? styles.changed : ''}>
+          {segment.text}
+        </span>
+      ))}
+    &quot;
+  </>
+)
@@ -490,3 +490,71 @@ export const MultiByteDiff: Story = {
     },
   },
 }
+
+export const JsonDiff: Story = {
+  args: {
+    tableDiff: {
+      tableName: 'users',
+      primaryValues: ['1'],
+      primaryColName: 'id',
+      colNames: ['profile'],
+      rowDiffs1: {
+        '1': {
+          profile: { status: 'deleted', value: '{"age": 20, "tags": ["a", "b"], "option": {"id": 1}}' },
+        },
+      },
+      rowDiffs2: {
+        '1': {
+          profile: { status: 'added', value: '{"tags": ["a"], "option": {"id": 2}, "email": "john@example.com"}' },
+        },
+      },
+      jsonDiffs: {
+        '1': {
+          profile: [
+            { path: '$.age', status: 'removed', value1: '20', value2: null },
+            { path: '$.email', status: 'added', value1: null, value2: '"john@example.com"' },
+            { path: '$.option.id', status: 'changed', value1: '1', value2: '2' },
+            { path: '$.tags[1]', status: 'removed', value1: '"b"', value2: null },
+          ],
+        },
+      },
+    },
+  },
+}
+
+export const InlineDiff: Story = {
+  args: {
+    tableDiff: {
+      tableName: 'pages',
+      primaryValues: ['1'],
+      primaryColName: 'id',
+      colNames: ['body'],
+      rowDiffs1: {
+        '1': {
+          body: { status: 'deleted', value: '"<p class="note">Welcome to our store, John.</p>"' },
+        },
+      },
+      rowDiffs2: {
+        '1': {
+          body: { status: 'added', value: '"<p class="notice">Welcome to our shop, John!</p>"' },
+        },
+      },
+      inlineDiffs: {
+        '1': {
+          body: [
+            { status: 'stay', text: '<p class="' },
+            { status: 'deleted', text: 'note' },
+            { status: 'added', text: 'notice' },
+            { status: 'stay', text: '">Welcome to our ' },
+            { status: 'deleted', text: 'store' },
+            { status: 'added', text: 'shop' },
+            { status: 'stay', text: ', John' },
+            { status: 'deleted', text: '.' },
+            { status: 'added', text: '!' },
+            { status: 'stay', text: '</p>' },
+          ],
+        },
+      },
+    },
+  },
+}
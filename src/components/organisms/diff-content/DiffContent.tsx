@@ -1,5 +1,5 @@
 import React, { type FC, Fragment, useEffect, useRef, useState } from 'react'
-import { type PrimaryValue, type RowDiff, type TableDiff } from '../../../types'
+import { type PrimaryValue, type TableDiff } from '../../../types'
 import styles from './DiffContent.module.scss'
 import { Resizer } from './Resizer'
 import { ColsRow } from './ColsRow'
@@ -42,7 +42,31 @@ export const DiffContent: FC<Props> = (props) => {
 
   return (
     <div id={tableDiff.tableName} className={styles.component}>
-      <span className={styles.label}>{tableDiff.tableName}</span>
+      <span className={styles.label}>
+        {tableDiff.tableName}
+        {tableDiff.truncated === true && <span className={styles.truncated}>truncated by row limit</span>}
+      </span>
+      {tableDiff.keyless === true && <span className={styles.excluded}>compared as multiset ( no primary key )</span>}
+      {[tableDiff.excludedColNames1 ?? [], tableDiff.excludedColNames2 ?? []].map(
+        (excludedColNames, i) =>
+          excludedColNames.length !== 0 && (
+            <span key={i} className={styles.excluded}>
+              excluded in snapshot {i + 1}: {excludedColNames.join(', ')}
+            </span>
+          )
+      )}
+      {(tableDiff.ignoredColNames ?? []).length !== 0 && (
+        <span className={styles.excluded}>ignored: {(tableDiff.ignoredColNames ?? []).join(', ')}</span>
+      )}
+      {(tableDiff.renamedFrom ?? null) !== null && <span className={styles.excluded}>renamed from {tableDiff.renamedFrom}</span>}
+      {Object.keys(tableDiff.renamedColNames ?? {}).length !== 0 && (
+        <span className={styles.excluded}>
+          renamed columns:{' '}
+          {Object.entries(tableDiff.renamedColNames ?? {})
+            .map(([newName, oldName]) => `${oldName} -> ${newName}`)
+            .join(', ')}
+        </span>
+      )}
       <div className={styles.header}>
         <NavPage s={s} perpage={perpage} len={len} setS={setS} />
         {pxs.ellipsized && <NavExpand isExpanded={isExpanded} setIsExpanded={setIsExpanded} />}
@@ -84,12 +108,13 @@ export const DiffContent: FC<Props> = (props) => {
                   <Fragment key={i}>
                     <ColsRow
                       key={`${i}-1`}
-                      primaryValue={primaryValue}
+                      primaryValue={label(tableDiff, primaryValue)}
                       colNames={tableDiff.colNames}
                       rowDiff={tableDiff.rowDiffs1[primaryValue]}
                       n={1}
                       noDiffColNames={noDiffColNames}
                       isShowNoDiffCol={isShowNoDiffCol}
+                      inlineDiff={tableDiff.inlineDiffs?.[primaryValue]}
                     />
                     <ColsRow
                       key={`${i}-2`}
@@ -99,6 +124,7 @@ export const DiffContent: FC<Props> = (props) => {
                       n={2}
                       noDiffColNames={noDiffColNames}
                       isShowNoDiffCol={isShowNoDiffCol}
+                      inlineDiff={tableDiff.inlineDiffs?.[primaryValue]}
                     />
                   </Fragment>
                 ) : (
@@ -115,6 +141,26 @@ export const DiffContent: FC<Props> = (props) => {
           </table>
         </div>
       </div>
+      {Object.keys(tableDiff.jsonDiffs ?? {}).length !== 0 && (
+        <div className={styles.json}>
+          {Object.entries(tableDiff.jsonDiffs ?? {}).map(([primaryValue, cols]) =>
+            Object.entries(cols).map(([colName, jsonPathDiffs]) => (
+              <div key={`${primaryValue}-${colName}`}>
+                <span className={styles.excluded}>
+                  {tableDiff.primaryColName}={primaryValue} {colName}
+                </span>
+                {jsonPathDiffs.map((jsonPathDiff) => (
+                  <p key={jsonPathDiff.path}>
+                    <span>{jsonPathDiff.path}</span>
+                    {(jsonPathDiff.value1 ?? null) !== null && <span className={styles.removed}>{jsonPathDiff.value1}</span>}
+                    {(jsonPathDiff.value2 ?? null) !== null && <span className={styles.added}>{jsonPathDiff.value2}</span>}
+                  </p>
+                ))}
+              </div>
+            ))
+          )}
+        </div>
+      )}
     </div>
   )
 }
@@ -122,8 +168,8 @@ export const DiffContent: FC<Props> = (props) => {
 const pick: (tableDiff: TableDiff, s: number, e: number) => TableDiff = (tableDiff: TableDiff, s: number, e: number) => {
   const primaryValues = tableDiff.primaryValues.slice(s, e)
 
-  const record: (primaryValues: PrimaryValue[], rowDiff: RowDiff) => RowDiff = (primaryValues: PrimaryValue[], rowDiff: RowDiff) => {
-    const filtered = primaryValues.map((primaryValue) => [primaryValue, rowDiff[primaryValue]])
+  const record: <T>(primaryValues: PrimaryValue[], rows: Record<PrimaryValue, T>) => Record<PrimaryValue, T> = (primaryValues, rows) => {
+    const filtered = primaryValues.map((primaryValue) => [primaryValue, rows[primaryValue]])
     return Object.fromEntries(filtered.filter((x) => x[1]))
   }
 
@@ -134,9 +180,25 @@ const pick: (tableDiff: TableDiff, s: number, e: number) => TableDiff = (tableDi
     colNames: tableDiff.colNames,
     rowDiffs1: record(primaryValues, tableDiff.rowDiffs1),
     rowDiffs2: record(primaryValues, tableDiff.rowDiffs2),
+    truncated: tableDiff.truncated,
+    excludedColNames1: tableDiff.excludedColNames1,
+    excludedColNames2: tableDiff.excludedColNames2,
+    ignoredColNames: tableDiff.ignoredColNames,
+    keyless: tableDiff.keyless,
+    rowCounts: tableDiff.rowCounts,
+    renamedFrom: tableDiff.renamedFrom,
+    renamedColNames: tableDiff.renamedColNames,
+    jsonDiffs: tableDiff.jsonDiffs !== undefined ? record(primaryValues, tableDiff.jsonDiffs) : undefined,
+    inlineDiffs: tableDiff.inlineDiffs !== undefined ? record(primaryValues, tableDiff.inlineDiffs) : undefined,
   }
 }
 
+// 主キーのないテーブルは、連番と 2 つのスナップショットでの出現回数を表示する
+const label: (tableDiff: TableDiff, primaryValue: PrimaryValue) => string = (tableDiff, primaryValue) => {
+  const counts = tableDiff.rowCounts?.[primaryValue]
+  return tableDiff.keyless === true && counts !== undefined ? `${primaryValue} ( ×${counts[0]} → ×${counts[1]} )` : primaryValue
+}
+
 const calcPxs: (tableDiff: TableDiff) => { primary: number; expand: number[]; ellipsis: number[]; sum: number; ellipsized: boolean } = (
   tableDiff: TableDiff
 ) => {
@@ -150,7 +212,8 @@ const calcPxs: (tableDiff: TableDiff) => { primary: number; expand: number[]; el
       .map((c) => (c.match(isMultibyte) != null ? 1 : 2))
       .reduce((a, b) => a + b, 0)
 
-  const primary = [...tableDiff.primaryValues.map((v) => len(v)), tableDiff.primaryColName.length].reduce((a, b) => (a > b ? a : b)) * rate
+  const primary =
+    [...tableDiff.primaryValues.map((v) => len(label(tableDiff, v))), tableDiff.primaryColName.length].reduce((a, b) => (a > b ? a : b)) * rate
   const rows = [...Object.values(tableDiff.rowDiffs1), ...Object.values(tableDiff.rowDiffs2)]
   const expand = tableDiff.colNames.map((colName) => {
     return [len(colName), ...rows.map((row) => len(row[colName]?.value ?? ''))].reduce((a, b) => (a > b ? a : b)) * rate
@@ -3,17 +3,28 @@ import styles from './DumpConfigInput.module.scss'
 import { LabeledRadioText } from '../../molecules/labeled-radio-text/LabeledRadioText'
 import { type DumpConfigValue } from '../../../types'
 import { Select } from '../../atoms/select/Select'
+import { InputText } from '../../atoms/input-text/InputText'
 
 interface Props {
   tableName: string
   colNames: string[]
   value: DumpConfigValue
+  rowLimit?: number | null
+  filter?: string | null
+  excludedColNames?: string[]
+  keyColNames?: string[]
+  multiset?: boolean
   input: boolean
   onChange: (value: string) => void
+  onChangeRowLimit?: (rowLimit: number | null) => void
+  onChangeFilter?: (filter: string | null) => void
+  onChangeExcludedColNames?: (excludedColNames: string[]) => void
+  onChangeKeyColNames?: (keyColNames: string[]) => void
+  onChangeMultiset?: (multiset: boolean) => void
 }
 
-function isOrdered(value: 'limited' | 'ignore' | string): boolean {
-  return value !== 'limited' && value !== 'ignore'
+function isOrdered(value: 'limited' | 'ignore' | 'all' | string): boolean {
+  return value !== 'limited' && value !== 'ignore' && value !== 'all'
 }
 
 export const DumpConfigInput: FC<Props> = (props) => {
@@ -24,8 +35,8 @@ export const DumpConfigInput: FC<Props> = (props) => {
       <LabeledRadioText
         label={props.tableName}
         value={isOrdered(props.value) ? 'ordered' : props.value}
-        values={['ordered', 'ignore', 'limited']}
-        displayValues={['Ordered 1,000', 'Ignore', 'Limited 1,000']}
+        values={['ordered', 'ignore', 'limited', 'all']}
+        displayValues={['Ordered', 'Ignore', 'Limited', 'All']}
         name={props.tableName}
         disabled={!props.input}
         onChange={(v) => {
@@ -46,6 +57,110 @@ export const DumpConfigInput: FC<Props> = (props) => {
           <span>desc</span>
         </div>
       )}
+      {props.value !== 'ignore' && (
+        <div className={styles.text}>
+          <span>up to</span>
+          {props.input ? (
+            <InputText
+              value={props.rowLimit?.toString() ?? ''}
+              maxLength={9}
+              chars={'number'}
+              onChange={(v) => {
+                props.onChangeRowLimit?.(v === '' ? null : Number(v))
+              }}
+            />
+          ) : (
+            <span>{props.rowLimit?.toLocaleString() ?? ''}</span>
+          )}
+          <span>rows {props.rowLimit == null && (props.value === 'all' ? '( no limit )' : '( default 1,000 )')}</span>
+        </div>
+      )}
+      {props.value !== 'ignore' && (props.input || (props.filter ?? '') !== '') && (
+        <div className={styles.text}>
+          <span>where</span>
+          {props.input ? (
+            <InputText
+              value={props.filter ?? ''}
+              maxLength={500}
+              chars={'all'}
+              onChange={(v) => {
+                props.onChangeFilter?.(v === '' ? null : v)
+              }}
+            />
+          ) : (
+            <span>{props.filter}</span>
+          )}
+        </div>
+      )}
+      {props.value !== 'ignore' && (props.input || (props.excludedColNames ?? []).length !== 0) && (
+        <div className={styles.text}>
+          <span>exclude</span>
+          {props.colNames
+            .filter((colName) => props.input || (props.excludedColNames ?? []).includes(colName))
+            .map((colName) => {
+              const excluded = (props.excludedColNames ?? []).includes(colName)
+              return (
+                <span
+                  key={colName}
+                  className={[styles.col, excluded ? styles.excluded : ''].join(' ')}
+                  onClick={() => {
+                    if (props.input) {
+                      props.onChangeExcludedColNames?.(
+                        excluded ? (props.excludedColNames ?? []).filter((v) => v !== colName) : [...(props.excludedColNames ?? []), colName]
+                      )
+                    }
+                  }}
+                >
+                  {colName}
+                </span>
+              )
+            })}
+        </div>
+      )}
+      {props.value !== 'ignore' && (props.input || (props.keyColNames ?? []).length !== 0) && (
+        <div className={styles.text}>
+          <span>key</span>
+          {(props.input ? props.colNames : props.keyColNames ?? []).map((colName) => {
+            const index = (props.keyColNames ?? []).indexOf(colName)
+            return (
+              <span
+                key={colName}
+                className={[styles.col, index !== -1 ? styles.key : ''].join(' ')}
+                onClick={() => {
+                  if (props.input) {
+                    props.onChangeKeyColNames?.(
+                      index !== -1 ? (props.keyColNames ?? []).filter((v) => v !== colName) : [...(props.keyColNames ?? []), colName]
+                    )
+                  }
+                }}
+              >
+                {index !== -1 && props.input ? `${index + 1}. ${colName}` : colName}
+              </span>
+            )
+          })}
+          {(props.keyColNames ?? []).length === 0 && <span>( primary key )</span>}
+        </div>
+      )}
+      {props.value !== 'ignore' && (props.keyColNames ?? []).length === 0 && (props.input || props.multiset === true) && (
+        <div className={styles.text}>
+          <span>without primary key</span>
+          {[false, true]
+            .filter((multiset) => props.input || multiset === (props.multiset ?? false))
+            .map((multiset) => (
+              <span
+                key={`${multiset}`}
+                className={[styles.col, multiset === (props.multiset ?? false) ? styles.key : ''].join(' ')}
+                onClick={() => {
+                  if (props.input) {
+                    props.onChangeMultiset?.(multiset)
+                  }
+                }}
+              >
+                {multiset ? 'compare as multiset' : 'skip'}
+              </span>
+            ))}
+        </div>
+      )}
     </div>
   )
 }
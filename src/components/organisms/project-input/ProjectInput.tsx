@@ -22,13 +22,17 @@ export const ProjectInput: FC<Props> = (props) => {
   const [host, setHost] = useState(props.project?.host ?? '')
   const [port, setPort] = useState(props.project?.port ?? '')
   const [schema, setSchema] = useState(props.project?.schema ?? '')
+  const [local, setLocal] = useState(props.project?.local ?? false)
+
+  // SQLite はファイルパスだけで接続する
+  const isFile = rdbms === 'SQLite'
 
   const v = z.object({
     name: z.string().min(1, { message: '入力してください' }),
-    user: z.string().min(1, { message: '入力してください' }),
-    password: z.string().min(1, { message: '入力してください' }),
-    host: z.string().min(1, { message: '入力してください' }),
-    port: z.string().min(1, { message: '入力してください' }),
+    user: isFile ? z.string() : z.string().min(1, { message: '入力してください' }),
+    password: isFile ? z.string() : z.string().min(1, { message: '入力してください' }),
+    host: isFile ? z.string() : z.string().min(1, { message: '入力してください' }),
+    port: isFile ? z.string() : z.string().min(1, { message: '入力してください' }),
     schema: z.string().min(1, { message: '入力してください' }),
   })
   const [errors, setErrors] = useState<{
@@ -55,24 +59,46 @@ export const ProjectInput: FC<Props> = (props) => {
         />
       </div>
 
-      <LabeledRadioText label={'System'} value={rdbms} values={['MySQL', 'PostgreSQL']} name={'rdbms'} onChange={setRdbms} />
+      <LabeledRadioText label={'System'} value={rdbms} values={['MySQL', 'MariaDB', 'PostgreSQL', 'SQLite']} name={'rdbms'} onChange={setRdbms} />
 
-      <div className={styles.grid} style={{ gridTemplateColumns: '1fr 1rem 1fr' }}>
-        <LabeledInputText value={user} label={'User'} maxLength={50} onChange={setUser} chars={'half'} errors={errors.user} />
-        <div></div>
-        <LabeledInputText value={password} label={'Password'} maxLength={50} onChange={setPassword} chars={'half'} errors={errors.password} />
-      </div>
+      {!isFile && (
+        <>
+          <div className={styles.grid} style={{ gridTemplateColumns: '1fr 1rem 1fr' }}>
+            <LabeledInputText value={user} label={'User'} maxLength={50} onChange={setUser} chars={'half'} errors={errors.user} />
+            <div></div>
+            <LabeledInputText value={password} label={'Password'} maxLength={50} onChange={setPassword} chars={'half'} errors={errors.password} />
+          </div>
 
-      <div className={styles.grid} style={{ gridTemplateColumns: '1fr 1rem 130px' }}>
-        <LabeledInputText value={host} label={'Host'} maxLength={200} onChange={setHost} chars={'half'} errors={errors.host} />
-        <div></div>
-        <LabeledInputText value={port} label={'Port'} maxLength={6} onChange={setPort} chars={'number'} errors={errors.port} />
-      </div>
+          <div className={styles.grid} style={{ gridTemplateColumns: '1fr 1rem 130px' }}>
+            <LabeledInputText value={host} label={'Host'} maxLength={200} onChange={setHost} chars={'half'} errors={errors.host} />
+            <div></div>
+            <LabeledInputText value={port} label={'Port'} maxLength={6} onChange={setPort} chars={'number'} errors={errors.port} />
+          </div>
+        </>
+      )}
 
       <div className={styles.grid}>
-        <LabeledInputText value={schema} label={'Database'} maxLength={50} onChange={setSchema} chars={'half'} errors={errors.schema} />
+        <LabeledInputText
+          value={schema}
+          label={isFile ? 'File' : 'Database'}
+          maxLength={isFile ? 500 : 50}
+          onChange={setSchema}
+          chars={'half'}
+          errors={errors.schema}
+        />
       </div>
 
+      {/* ローカル環境は確認なしでスナップショットの状態に戻せる */}
+      <LabeledRadioText
+        label={'Environment'}
+        value={local ? 'Local' : 'Shared'}
+        values={['Shared', 'Local']}
+        name={'local'}
+        onChange={(value) => {
+          setLocal(value === 'Local')
+        }}
+      />
+
       <div className={styles.buttons}>
         <Button
           variant={'primary'}
@@ -90,12 +116,13 @@ export const ProjectInput: FC<Props> = (props) => {
                 projectId,
                 name,
                 color,
-                rdbms: 'MySQL',
+                rdbms,
                 user,
                 password,
                 host,
                 port,
                 schema,
+                local,
               })
             }
           }}
@@ -108,12 +135,13 @@ export const ProjectInput: FC<Props> = (props) => {
               projectId: '',
               name,
               color,
-              rdbms: 'MySQL',
+              rdbms,
               user,
               password,
               host,
               port,
               schema,
+              local,
             })
           }}
         />
@@ -17,6 +17,13 @@ interface Props {
 export const SnapshotInput: FC<Props> = (props) => {
   const [snapshotName, setSnapshotName] = useState(props.snapshotSummary?.snapshotName ?? '')
   const [dumpConfigValues, setDumpConfigValues] = useState(props.dumpConfigs.map((dumpConfig) => dumpConfig.value))
+  const [dumpConfigRowLimits, setDumpConfigRowLimits] = useState(props.dumpConfigs.map((dumpConfig) => dumpConfig.rowLimit ?? null))
+  const [dumpConfigFilters, setDumpConfigFilters] = useState(props.dumpConfigs.map((dumpConfig) => dumpConfig.filter ?? null))
+  const [dumpConfigExcludedColNames, setDumpConfigExcludedColNames] = useState(
+    props.dumpConfigs.map((dumpConfig) => dumpConfig.excludedColNames ?? [])
+  )
+  const [dumpConfigKeyColNames, setDumpConfigKeyColNames] = useState(props.dumpConfigs.map((dumpConfig) => dumpConfig.keyColNames ?? []))
+  const [dumpConfigMultisets, setDumpConfigMultisets] = useState(props.dumpConfigs.map((dumpConfig) => dumpConfig.multiset ?? false))
 
   const v = z.object({
     snapshotName: z.string().min(1, { message: '入力してください' }),
@@ -58,12 +65,42 @@ export const SnapshotInput: FC<Props> = (props) => {
               tableName={dumpConfig.tableName}
               colNames={dumpConfig.colNames}
               value={dumpConfigValues[i]}
+              rowLimit={dumpConfigRowLimits[i]}
+              filter={dumpConfigFilters[i]}
+              excludedColNames={dumpConfigExcludedColNames[i]}
+              keyColNames={dumpConfigKeyColNames[i]}
+              multiset={dumpConfigMultisets[i]}
               input={props.snapshotSummary === undefined}
               onChange={(value) => {
                 const vs = [...dumpConfigValues]
                 vs[i] = value
                 setDumpConfigValues(vs)
               }}
+              onChangeRowLimit={(rowLimit) => {
+                const vs = [...dumpConfigRowLimits]
+                vs[i] = rowLimit
+                setDumpConfigRowLimits(vs)
+              }}
+              onChangeFilter={(filter) => {
+                const vs = [...dumpConfigFilters]
+                vs[i] = filter
+                setDumpConfigFilters(vs)
+              }}
+              onChangeExcludedColNames={(excludedColNames) => {
+                const vs = [...dumpConfigExcludedColNames]
+                vs[i] = excludedColNames
+                setDumpConfigExcludedColNames(vs)
+              }}
+              onChangeKeyColNames={(keyColNames) => {
+                const vs = [...dumpConfigKeyColNames]
+                vs[i] = keyColNames
+                setDumpConfigKeyColNames(vs)
+              }}
+              onChangeMultiset={(multiset) => {
+                const vs = [...dumpConfigMultisets]
+                vs[i] = multiset
+                setDumpConfigMultisets(vs)
+              }}
             />
           </div>
         ))}
@@ -94,6 +131,11 @@ export const SnapshotInput: FC<Props> = (props) => {
                   tableName: dumpConfig.tableName,
                   colNames: dumpConfig.colNames,
                   value: dumpConfigValues[i],
+                  rowLimit: dumpConfigRowLimits[i],
+                  filter: dumpConfigFilters[i],
+                  excludedColNames: dumpConfigExcludedColNames[i],
+                  keyColNames: dumpConfigKeyColNames[i],
+                  multiset: dumpConfigMultisets[i],
                 }))
                 props.dump(snapshotId, snapshotName, dumpConfigs)
               }
@@ -0,0 +1,42 @@
+import type { Meta, StoryObj } from '@storybook/react'
+
+import { CrossProjectSelect } from './CrossProjectSelect'
+import { withRouter } from 'storybook-addon-react-router-v6'
+
+const meta = {
+  title: 'Templates/CrossProjectSelect',
+  component: CrossProjectSelect,
+  tags: ['autodocs'],
+  argTypes: {},
+  decorators: [withRouter],
+} satisfies Meta<typeof CrossProjectSelect>
+
+export default meta
+type Story = StoryObj<typeof meta>
+
+export const Component: Story = {
+  args: {
+    projects: [
+      {
+        projectId: '92B07638-8EBA-471D-BDC1-71685B21EFE4',
+        name: 'My Laravel Project ( staging )',
+        color: '#c2e0c6',
+        rdbms: 'MySQL',
+        user: 'admin',
+        password: 'admin-pw',
+        host: 'staging.example.com',
+        port: '3306',
+        schema: 'laravel',
+      },
+    ],
+    snapshotSummaries: [
+      {
+        snapshotId: 'D5C0BCF5-B8E1-4B3D-B2B3-6AF4B0A5E3D7',
+        snapshotName: '初期状態',
+        createAt: '2023-06-01 12:00:00',
+      },
+    ],
+    selectProject: console.log,
+    select: console.log,
+  },
+}
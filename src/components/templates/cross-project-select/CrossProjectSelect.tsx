@@ -0,0 +1,71 @@
+import { type FC, useState } from 'react'
+import styles from './CrossProjectSelect.module.scss'
+import { type Project, type SnapshotSummary } from '../../../types'
+import { Header } from '../../molecules/header/Header'
+import { IconBack } from '../../atoms/icon-back/IconBack'
+import { ColorTagCard } from '../../molecules/color-tag-card/ColorTagCard'
+import { TextCard } from '../../molecules/text-card/TextCard'
+import { useNavigate } from 'react-router-dom'
+
+interface Props {
+  projects: Project[]
+  snapshotSummaries: SnapshotSummary[]
+  selectProject: (projectId: string) => void
+  select: (snapshotId: string) => void
+}
+
+// 比較するスナップショット 2 を、別のプロジェクトから選ぶ
+export const CrossProjectSelect: FC<Props> = (props) => {
+  const [selectedProject, setSelectedProject] = useState<Project | null>(null)
+
+  const navigate = useNavigate()
+
+  return (
+    <div className={styles.template}>
+      <Header
+        globals={
+          <IconBack
+            variant={'large'}
+            onClick={() => {
+              navigate('/snapshot-summary/list')
+            }}
+          />
+        }
+        locals={<></>}
+      />
+      <div className={styles.component}>
+        <div className={styles.content}>
+          <p>比較するプロジェクトを選択してください</p>
+          {props.projects.map((project) => (
+            <ColorTagCard
+              key={project.projectId}
+              label={selectedProject?.projectId === project.projectId ? `${project.name} ( 選択中 )` : project.name}
+              color={project.color}
+              onClick={() => {
+                setSelectedProject(project)
+                props.selectProject(project.projectId)
+              }}
+            />
+          ))}
+          {selectedProject !== null && (
+            <>
+              <p>比較するスナップショットを選択してください</p>
+              {props.snapshotSummaries.map((snapshotSummary) => (
+                <TextCard
+                  key={snapshotSummary.snapshotId}
+                  label={snapshotSummary.snapshotName}
+                  text={snapshotSummary.createAt}
+                  selected={false}
+                  onClick={() => {
+                    props.select(snapshotSummary.snapshotId)
+                  }}
+                />
+              ))}
+              {props.snapshotSummaries.length === 0 && <p>スナップショットがありません</p>}
+            </>
+          )}
+        </div>
+      </div>
+    </div>
+  )
+}
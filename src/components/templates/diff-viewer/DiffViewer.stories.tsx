@@ -60,30 +60,138 @@ const create: (tablesN: number, primaryValuesN: number, colsN: number) => TableD
 
 export const Small: Story = {
   args: {
+    ignoreRules: [],
+    onChangeIgnoreRules: () => {},
+    createSqlScript: async () => '',
+    exportReport: async () => [],
     tableDiffs: create(5, 3, 5),
   },
 }
 
 export const Medium: Story = {
   args: {
+    ignoreRules: [],
+    onChangeIgnoreRules: () => {},
+    createSqlScript: async () => '',
+    exportReport: async () => [],
     tableDiffs: create(5, 10, 10),
   },
 }
 
 export const Large: Story = {
   args: {
+    ignoreRules: [],
+    onChangeIgnoreRules: () => {},
+    createSqlScript: async () => '',
+    exportReport: async () => [],
     tableDiffs: create(10, 25, 20),
   },
 }
 
 export const Huge: Story = {
   args: {
+    ignoreRules: [],
+    onChangeIgnoreRules: () => {},
+    createSqlScript: async () => '',
+    exportReport: async () => [],
     tableDiffs: create(3, 1000, 5),
   },
 }
 
 export const Empty: Story = {
   args: {
+    ignoreRules: [],
+    onChangeIgnoreRules: () => {},
+    createSqlScript: async () => '',
+    exportReport: async () => [],
     tableDiffs: [],
   },
 }
+
+export const Live: Story = {
+  args: {
+    ignoreRules: [],
+    onChangeIgnoreRules: () => {},
+    keep: async (snapshotName: string) => {
+      console.log(snapshotName)
+    },
+    tableDiffs: create(5, 3, 5),
+  },
+}
+
+export const CrossProject: Story = {
+  args: {
+    ignoreRules: [],
+    onChangeIgnoreRules: () => {},
+    tableMappings: ['users=app_users'],
+    onChangeTableMappings: () => {},
+    warnings: ['users: different filter ( tenant_id = 1 / none )', 'logs: table not found in snapshot 2'],
+    tableDiffs: create(5, 3, 5),
+  },
+}
+
+export const Renamed: Story = {
+  args: {
+    ignoreRules: [],
+    onChangeIgnoreRules: () => {},
+    renames: ['members=users', 'members.name=full_name'],
+    onChangeRenames: () => {},
+    tableDiffs: [{ ...create(1, 3, 5)[0], tableName: 'users', renamedFrom: 'members', renamedColNames: { 'col-0': 'name' } }],
+  },
+}
+
+export const SuggestedRenames: Story = {
+  args: {
+    ignoreRules: [],
+    onChangeIgnoreRules: () => {},
+    renames: [],
+    suggestedRenames: ['members=users', 'members.name=full_name'],
+    onChangeRenames: () => {},
+    tableDiffs: create(2, 3, 5),
+  },
+}
+
+export const Schema: Story = {
+  args: {
+    ignoreRules: [],
+    onChangeIgnoreRules: () => {},
+    schemaChanges: [
+      {
+        tableName: 'users',
+        object: 'column',
+        name: 'name',
+        status: 'altered',
+        definition1: 'varchar(100) not null',
+        definition2: 'varchar(255) not null',
+      },
+      { tableName: 'users', object: 'index', name: 'idx_email', status: 'added', definition2: 'unique (email)' },
+      { tableName: 'logs', object: 'table', name: 'logs', status: 'dropped', definition1: 'id int not null, message text' },
+    ],
+    dbObjectDiffs: [
+      {
+        objectType: 'trigger',
+        objectName: 'users_bi',
+        status: 'altered',
+        lineDiffs: [
+          { status: 'stay', line: 'begin' },
+          { status: 'stay', line: '  set new.created_at = now();' },
+          { status: 'added', line: '  set new.updated_at = now();' },
+          { status: 'stay', line: 'end' },
+        ],
+      },
+      { objectType: 'procedure', objectName: 'cleanup', status: 'dropped', lineDiffs: [{ status: 'deleted', line: 'delete from logs' }] },
+    ],
+    tableDiffs: create(2, 3, 5),
+  },
+}
+
+export const Chained: Story = {
+  args: {
+    ignoreRules: [],
+    onChangeIgnoreRules: () => {},
+    steps: ['初期状態 → カートに追加', 'カートに追加 → 決済完了', 'Net'],
+    selectedStep: 0,
+    onSelectStep: console.log,
+    tableDiffs: create(5, 3, 5),
+  },
+}
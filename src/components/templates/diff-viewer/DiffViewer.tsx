@@ -1,7 +1,7 @@
 import { type FC, Fragment, useState } from 'react'
 
 import styles from './DiffViewer.module.scss'
-import { type TableDiff } from '../../../types'
+import { type DbObjectDiff, type SchemaChange, type TableDiff } from '../../../types'
 import { DiffContent } from '../../organisms/diff-content/DiffContent'
 import { IconVisible } from '../../atoms/icon-visible/IconVisible'
 import { Header } from '../../molecules/header/Header'
@@ -12,16 +12,48 @@ import { useNavigate } from 'react-router-dom'
 import { TourWrapper } from '../../atoms/tour-wrapper/TourWrapper'
 import { type ReactourStep } from 'reactour'
 import { IconHelp } from '../../atoms/icon-help/IconHelp'
+import { InputText } from '../../atoms/input-text/InputText'
+import { Button } from '../../atoms/button/Button'
+import { toast } from 'react-hot-toast'
+import { LabeledRadioText } from '../../molecules/labeled-radio-text/LabeledRadioText'
+import { LabeledInputText } from '../../molecules/labeled-input-text/LabeledInputText'
 
 interface Props {
   tableDiffs: TableDiff[]
+  schemaChanges?: SchemaChange[]
+  dbObjectDiffs?: DbObjectDiff[]
+  ignoreRules: string[]
+  onChangeIgnoreRules: (ignoreRules: string[]) => void
+  createSqlScript?: () => Promise<string>
+  exportReport?: (format: string, path: string) => Promise<string[]>
+  keep?: (snapshotName: string) => Promise<void>
+  tableMappings?: string[]
+  onChangeTableMappings?: (tableMappings: string[]) => void
+  renames?: string[]
+  suggestedRenames?: string[]
+  onChangeRenames?: (renames: string[]) => void
+  warnings?: string[]
+  steps?: string[]
+  selectedStep?: number
+  onSelectStep?: (step: number) => void
 }
 
 export const DiffViewer: FC<Props> = (props) => {
   const [ignoreTableNames, setIgnoreTableNames] = useState<string[]>([])
+  const [ignoreRulesText, setIgnoreRulesText] = useState(props.ignoreRules.join(', '))
+  const [tableMappingsText, setTableMappingsText] = useState((props.tableMappings ?? []).join(', '))
+  // 名前の変更を指定していなければ、検出した候補を入れておく ( Apply で確定するまでは比較に使わない )
+  const isSuggested = (props.renames ?? []).length === 0 && (props.suggestedRenames ?? []).length !== 0
+  const [renamesText, setRenamesText] = useState((isSuggested ? props.suggestedRenames ?? [] : props.renames ?? []).join(', '))
 
   const [isTouring, setIsTouring] = useState(false)
   const [isModalOpen, setIsModalOpen] = useState(false)
+  const [sqlScript, setSqlScript] = useState<string | undefined>(undefined)
+  const [isExporting, setIsExporting] = useState(false)
+  const [reportFormat, setReportFormat] = useState('markdown')
+  const [reportPath, setReportPath] = useState('')
+  const [isKeeping, setIsKeeping] = useState(false)
+  const [snapshotName, setSnapshotName] = useState('')
 
   const navigate = useNavigate()
 
@@ -58,7 +90,149 @@ export const DiffViewer: FC<Props> = (props) => {
             </>
           }
         />
+        <div className={styles.ignoreRules}>
+          <span>ignore</span>
+          <InputText value={ignoreRulesText} maxLength={1000} onChange={setIgnoreRulesText} chars={'half'} />
+          <Button
+            variant={'secondary'}
+            label={'Apply'}
+            onClick={() => {
+              props.onChangeIgnoreRules(
+                ignoreRulesText
+                  .split(/[,\s]+/)
+                  .map((rule) => rule.trim())
+                  .filter((rule) => rule !== '')
+              )
+            }}
+          />
+          {props.tableDiffs.length !== 0 && props.createSqlScript !== undefined && (
+            <Button
+              variant={'secondary'}
+              label={'SQL'}
+              onClick={() => {
+                props
+                  .createSqlScript?.()
+                  .then((script) => {
+                    setSqlScript(script)
+                  })
+                  .catch((e: string) => {
+                    toast.error(e)
+                  })
+              }}
+            />
+          )}
+          {props.tableDiffs.length !== 0 && props.exportReport !== undefined && (
+            <Button
+              variant={'secondary'}
+              label={'Export'}
+              onClick={() => {
+                setIsExporting(true)
+              }}
+            />
+          )}
+          {props.keep !== undefined && (
+            <Button
+              variant={'primary'}
+              label={'Keep'}
+              onClick={() => {
+                setIsKeeping(true)
+              }}
+            />
+          )}
+        </div>
+        {props.onChangeTableMappings !== undefined && (
+          <div className={styles.ignoreRules}>
+            <span>mapping</span>
+            <InputText value={tableMappingsText} maxLength={1000} onChange={setTableMappingsText} chars={'half'} />
+            <Button
+              variant={'secondary'}
+              label={'Apply'}
+              onClick={() => {
+                props.onChangeTableMappings?.(
+                  tableMappingsText
+                    .split(/[,\s]+/)
+                    .map((mapping) => mapping.trim())
+                    .filter((mapping) => mapping !== '')
+                )
+              }}
+            />
+          </div>
+        )}
+        {props.onChangeRenames !== undefined && (
+          <div className={styles.ignoreRules}>
+            <span>rename</span>
+            <InputText value={renamesText} maxLength={1000} onChange={setRenamesText} chars={'half'} />
+            {isSuggested && <span className={styles.suggestion}>suggested</span>}
+            <Button
+              variant={'secondary'}
+              label={'Apply'}
+              onClick={() => {
+                props.onChangeRenames?.(
+                  renamesText
+                    .split(/[,\s]+/)
+                    .map((rename) => rename.trim())
+                    .filter((rename) => rename !== '')
+                )
+              }}
+            />
+          </div>
+        )}
+        {props.steps !== undefined && (
+          <div className={styles.steps}>
+            {props.steps.map((step, i) => (
+              <Button
+                key={i}
+                variant={props.selectedStep === i ? 'primary' : 'secondary'}
+                label={step}
+                onClick={() => {
+                  props.onSelectStep?.(i)
+                }}
+              />
+            ))}
+          </div>
+        )}
+        {(props.warnings ?? []).length !== 0 && (
+          <div className={styles.warnings}>
+            {(props.warnings ?? []).map((warning) => (
+              <p key={warning}>{warning}</p>
+            ))}
+          </div>
+        )}
         <div className={styles.component}>
+          {(props.schemaChanges ?? []).length !== 0 && (
+            <div className={styles.schema}>
+              <h2>schema</h2>
+              {(props.schemaChanges ?? []).map((schemaChange, i) => (
+                <p key={i}>
+                  <span className={styles.object}>
+                    {schemaChange.object} {getSchemaName(schemaChange)}
+                  </span>
+                  {(schemaChange.definition1 ?? null) !== null && <span className={styles.dropped}>{schemaChange.definition1}</span>}
+                  {(schemaChange.definition2 ?? null) !== null && <span className={styles.added}>{schemaChange.definition2}</span>}
+                </p>
+              ))}
+            </div>
+          )}
+          {(props.dbObjectDiffs ?? []).length !== 0 && (
+            <div className={styles.objects}>
+              <h2>objects</h2>
+              {(props.dbObjectDiffs ?? []).map((dbObjectDiff) => (
+                <div key={`${dbObjectDiff.objectType} ${dbObjectDiff.objectName}`}>
+                  <span className={styles.object}>
+                    {dbObjectDiff.objectType} {dbObjectDiff.objectName} ( {dbObjectDiff.status} )
+                  </span>
+                  <pre>
+                    {dbObjectDiff.lineDiffs.map((lineDiff, i) => (
+                      <div key={i} className={styles[lineDiff.status]}>
+                        {lineDiff.status === 'added' ? '+ ' : lineDiff.status === 'deleted' ? '- ' : '  '}
+                        {lineDiff.line}
+                      </div>
+                    ))}
+                  </pre>
+                </div>
+              ))}
+            </div>
+          )}
           {props.tableDiffs.map((tableDiff) =>
             !ignoreTableNames.includes(tableDiff.tableName) ? (
               <DiffContent key={tableDiff.tableName} tableDiff={tableDiff} />
@@ -66,7 +240,7 @@ export const DiffViewer: FC<Props> = (props) => {
               <Fragment key={tableDiff.tableName}></Fragment>
             )
           )}
-          {props.tableDiffs.length === 0 && (
+          {props.tableDiffs.length === 0 && (props.schemaChanges ?? []).length === 0 && (props.dbObjectDiffs ?? []).length === 0 && (
             <div className={styles.empty}>
               <p>2 つのスナップショットに差分がありません</p>
               <p>新たなスナップショットを作成して、別の差分表示を見てみましょう</p>
@@ -111,6 +285,79 @@ export const DiffViewer: FC<Props> = (props) => {
             ))}
         </ModalWrapper>
       </div>
+      <ModalWrapper
+        isOpen={sqlScript !== undefined}
+        setIsOpen={() => {
+          setSqlScript(undefined)
+        }}
+      >
+        <textarea className={styles.script} value={sqlScript} readOnly={true} />
+      </ModalWrapper>
+      <ModalWrapper
+        isOpen={isExporting}
+        setIsOpen={setIsExporting}
+        button={
+          <Button
+            variant={'primary'}
+            label={'Export'}
+            onClick={() => {
+              props
+                .exportReport?.(reportFormat, reportPath)
+                .then((paths) => {
+                  toast.success(`${paths.length} ファイルを書き出しました`)
+                  setIsExporting(false)
+                })
+                .catch((e: string) => {
+                  toast.error(e)
+                })
+            }}
+          />
+        }
+      >
+        <div className={styles.report}>
+          <LabeledRadioText
+            label={'Format'}
+            value={reportFormat}
+            values={['markdown', 'html', 'csv']}
+            displayValues={['Markdown', 'HTML', 'CSV']}
+            name={'format'}
+            onChange={setReportFormat}
+          />
+          {/* CSV はテーブルごとのファイルを、指定したディレクトリに書き出す */}
+          <LabeledInputText
+            value={reportPath}
+            label={reportFormat === 'csv' ? 'Directory' : 'File'}
+            maxLength={500}
+            onChange={setReportPath}
+            chars={'half'}
+          />
+        </div>
+      </ModalWrapper>
+      <ModalWrapper
+        isOpen={isKeeping}
+        setIsOpen={setIsKeeping}
+        button={
+          <Button
+            variant={'primary'}
+            label={'Keep'}
+            onClick={() => {
+              props
+                .keep?.(snapshotName)
+                .then(() => {
+                  setIsKeeping(false)
+                })
+                .catch((e: string) => {
+                  toast.error(e)
+                })
+            }}
+          />
+        }
+      >
+        <div className={styles.report}>
+          {/* 表示中の状態ではなく、保存する時点のデータベースの状態が保存される */}
+          <LabeledInputText value={snapshotName} label={'Name'} maxLength={100} onChange={setSnapshotName} chars={'all'} />
+        </div>
+      </ModalWrapper>
       <TourWrapper
         steps={steps}
         isTouring={isTouring}
@@ -122,11 +369,40 @@ export const DiffViewer: FC<Props> = (props) => {
   )
 }
 
+// テーブル以外はテーブル名を付けて表示する
+const getSchemaName = (schemaChange: SchemaChange): string =>
+  schemaChange.object === 'table' ? schemaChange.tableName : `${schemaChange.tableName}.${schemaChange.name}`
+
 const steps: ReactourStep[] = [
   {
     selector: '.icon_search',
     content: '表示するテーブルをフィルタリングできます',
   },
+  {
+    content: 'users.updated_at や *.updated_at のように指定したカラムを、比較から除外して差分を再計算できます',
+  },
+  {
+    content: 'SQL でスナップショット 1 の状態をスナップショット 2 の状態にする SQL を作成できます',
+  },
+  {
+    content: 'Export で差分を Markdown 、HTML 、CSV のファイルに書き出せます',
+  },
+  {
+    content: '現在のデータベースとの差分では、Keep で現在の状態をスナップショットとして保存できます',
+  },
+  {
+    content: '別のプロジェクトとの比較では、users=app_users のように名前の異なるテーブルを対応させられます',
+  },
+  {
+    content:
+      '名前を変えたとみられるテーブルやカラムは rename に候補として表示され、Apply で確定すると同じテーブルやカラムとして再計算します ( members=users や users.name=full_name のように修正もできます )',
+  },
+  {
+    content: 'テーブルの定義を記録したスナップショット同士では、カラムの型やインデックス、外部キーの変更が schema に表示されます',
+  },
+  {
+    content: 'ビュー、トリガー、ストアドルーチン、イベントの定義の変更は、行ごとの差分が objects に表示されます',
+  },
   {
     content: '主キーごとに変更差分が表示されます',
   },
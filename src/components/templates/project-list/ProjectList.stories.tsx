@@ -53,6 +53,9 @@ export const Component: Story = {
     ],
     select: console.log,
     remove: console.log,
+    importArchive: async (projectId: string, path: string) => {
+      console.log(projectId, path)
+    },
   },
 }
 
@@ -61,5 +64,8 @@ export const Empty: Story = {
     projects: [],
     select: console.log,
     remove: console.log,
+    importArchive: async (projectId: string, path: string) => {
+      console.log(projectId, path)
+    },
   },
 }
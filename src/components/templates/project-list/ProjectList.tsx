@@ -11,16 +11,23 @@ import { useNavigate } from 'react-router-dom'
 import { IconHelp } from '../../atoms/icon-help/IconHelp'
 import { type ReactourStep } from 'reactour'
 import { TourWrapper } from '../../atoms/tour-wrapper/TourWrapper'
+import { Button } from '../../atoms/button/Button'
+import { ModalWrapper } from '../../molecules/modal-wrapper/ModalWrapper'
+import { LabeledInputText } from '../../molecules/labeled-input-text/LabeledInputText'
+import { toast } from 'react-hot-toast'
 
 interface Props {
   projects: Project[]
   select: (projectId: string) => void
   remove: (id: string) => void
+  importArchive: (projectId: string, path: string) => Promise<void>
 }
 
 export const ProjectList: FC<Props> = (props) => {
   const [isTouring, setIsTouring] = useState(false)
   const [isSetting, setIsSetting] = useState(false)
+  const [importingProject, setImportingProject] = useState<Project | null>(null)
+  const [archivePath, setArchivePath] = useState('')
 
   const navigate = useNavigate()
 
@@ -78,6 +85,13 @@ export const ProjectList: FC<Props> = (props) => {
                         props.remove(project.projectId)
                       }}
                     />
+                    <Button
+                      variant={'secondary'}
+                      label={'Import'}
+                      onClick={() => {
+                        setImportingProject(project)
+                      }}
+                    />
                   </div>
                 )}
               </div>
@@ -100,6 +114,34 @@ export const ProjectList: FC<Props> = (props) => {
           )}
         </div>
       </div>
+      <ModalWrapper
+        isOpen={importingProject !== null}
+        setIsOpen={(isOpen) => {
+          if (!isOpen) setImportingProject(null)
+        }}
+        button={
+          <Button
+            variant={'primary'}
+            label={'Import'}
+            onClick={() => {
+              if (importingProject === null) return
+              props
+                .importArchive(importingProject.projectId, archivePath)
+                .then(() => {
+                  setImportingProject(null)
+                })
+                .catch((e: string) => {
+                  toast.error(e)
+                })
+            }}
+          />
+        }
+      >
+        <div className={styles.archive}>
+          <p>{importingProject?.name} にスナップショットを取り込みます</p>
+          <LabeledInputText value={archivePath} label={'File'} maxLength={500} onChange={setArchivePath} chars={'half'} />
+        </div>
+      </ModalWrapper>
       <TourWrapper
         steps={steps(props.projects.length === 0)}
         isTouring={isTouring}
@@ -148,5 +190,8 @@ const steps: (isEmpty: boolean) => ReactourStep[] = (isEmpty) => {
           selector: '.icon_delete',
           content: '接続設定を削除できます',
         },
+        {
+          content: 'Import で書き出されたスナップショットを接続設定に取り込むことができます',
+        },
       ]
 }
@@ -0,0 +1,60 @@
+import type { Meta, StoryObj } from '@storybook/react'
+
+import { RowTimeline } from './RowTimeline'
+import { withRouter } from 'storybook-addon-react-router-v6'
+
+const meta = {
+  title: 'Templates/RowTimeline',
+  component: RowTimeline,
+  tags: ['autodocs'],
+  argTypes: {},
+  decorators: [withRouter],
+} satisfies Meta<typeof RowTimeline>
+
+export default meta
+type Story = StoryObj<typeof meta>
+
+export const Component: Story = {
+  args: {
+    rowTimeline: {
+      tableName: 'orders',
+      primaryColName: 'id',
+      primaryValue: '1',
+      colNames: ['status', 'amount', 'updated_at'],
+      points: [
+        {
+          snapshotId: 'D5C0BCF5-B8E1-4B3D-B2B3-6AF4B0A5E3D7',
+          snapshotName: '初期状態',
+          createAt: '2023-06-01 12:00:00',
+          state: 'absent',
+          values: {},
+          changedColNames: [],
+        },
+        {
+          snapshotId: '5A7D3D2C-8E3C-4F0F-9E36-44F1C5D6B0A1',
+          snapshotName: 'カートに追加',
+          createAt: '2023-06-01 12:01:00',
+          state: 'present',
+          values: { status: '"cart"', amount: '1200', updated_at: '"2023-06-01 12:00:30"' },
+          changedColNames: ['status', 'amount', 'updated_at'],
+        },
+        {
+          snapshotId: '0E0B5A39-7A3B-4C5E-8B7D-2D9B8D8B1F57',
+          snapshotName: 'クーポン適用',
+          createAt: '2023-06-01 12:02:00',
+          state: 'unknown',
+          values: {},
+          changedColNames: [],
+        },
+        {
+          snapshotId: 'B8E9F3A2-1C4D-4E5F-9A6B-7C8D9E0F1A2B',
+          snapshotName: '決済完了',
+          createAt: '2023-06-01 12:03:00',
+          state: 'present',
+          values: { status: '"paid"', amount: '1000', updated_at: '"2023-06-01 12:02:45"' },
+          changedColNames: ['status', 'amount', 'updated_at'],
+        },
+      ],
+    },
+  },
+}
@@ -0,0 +1,76 @@
+import { type FC } from 'react'
+import styles from './RowTimeline.module.scss'
+import { type RowTimeline as RowTimelineType } from '../../../types'
+import { Header } from '../../molecules/header/Header'
+import { IconBack } from '../../atoms/icon-back/IconBack'
+import { useNavigate } from 'react-router-dom'
+
+interface Props {
+  rowTimeline: RowTimelineType
+}
+
+// 1 つの行の値をスナップショットの作成順に並べ、直前のスナップショットから変更されたカラムを強調する
+export const RowTimeline: FC<Props> = (props) => {
+  const navigate = useNavigate()
+
+  const rowTimeline = props.rowTimeline
+
+  return (
+    <div className={styles.template}>
+      <Header
+        globals={
+          <IconBack
+            variant={'large'}
+            onClick={() => {
+              navigate('/snapshot-summary/list')
+            }}
+          />
+        }
+        locals={<></>}
+      />
+      <div className={styles.component}>
+        <span className={styles.label}>
+          {rowTimeline.tableName} {rowTimeline.primaryColName !== '' && `( ${rowTimeline.primaryColName} = ${rowTimeline.primaryValue} )`}
+        </span>
+        {rowTimeline.points.length === 0 ? (
+          <p>スナップショットがありません</p>
+        ) : (
+          <div className={styles.body}>
+            <table>
+              <thead>
+                <tr>
+                  <th align={'left'}>snapshot</th>
+                  {rowTimeline.colNames.map((colName) => (
+                    <th key={colName} align={'left'}>
+                      {colName}
+                    </th>
+                  ))}
+                </tr>
+              </thead>
+              <tbody>
+                {rowTimeline.points.map((point) => (
+                  <tr key={point.snapshotId} className={styles[point.state]}>
+                    <td title={point.createAt}>{point.snapshotName}</td>
+                    {point.state === 'present' ? (
+                      rowTimeline.colNames.map((colName) => (
+                        <td
+                          key={colName}
+                          title={point.values[colName]}
+                          className={point.changedColNames.includes(colName) ? styles.changed : undefined}
+                        >
+                          {point.values[colName] ?? ''}
+                        </td>
+                      ))
+                    ) : (
+                      <td colSpan={Math.max(rowTimeline.colNames.length, 1)}>{point.state === 'absent' ? '( no row )' : '( not dumped )'}</td>
+                    )}
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          </div>
+        )}
+      </div>
+    </div>
+  )
+}
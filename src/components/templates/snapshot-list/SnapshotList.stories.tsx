@@ -39,6 +39,12 @@ export const Component: Story = {
       },
     ],
     remove: console.log,
+    restore: async (id: string, confirmed: boolean) => {
+      console.log(id, confirmed)
+    },
+    exportArchive: async (ids: string[], path: string) => {
+      console.log(ids, path)
+    },
   },
 }
 
@@ -46,5 +52,11 @@ export const Empty: Story = {
   args: {
     snapshotSummaries: [],
     remove: console.log,
+    restore: async (id: string, confirmed: boolean) => {
+      console.log(id, confirmed)
+    },
+    exportArchive: async (ids: string[], path: string) => {
+      console.log(ids, path)
+    },
   },
 }
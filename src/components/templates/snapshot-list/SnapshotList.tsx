@@ -12,16 +12,31 @@ import { useNavigate } from 'react-router-dom'
 import { IconHelp } from '../../atoms/icon-help/IconHelp'
 import { TourWrapper } from '../../atoms/tour-wrapper/TourWrapper'
 import { type ReactourStep } from 'reactour'
+import { Button } from '../../atoms/button/Button'
+import { ModalWrapper } from '../../molecules/modal-wrapper/ModalWrapper'
+import { toast } from 'react-hot-toast'
+import { LabeledInputText } from '../../molecules/labeled-input-text/LabeledInputText'
 
 interface Props {
   snapshotSummaries: SnapshotSummary[]
   remove: (id: string) => void
+  restore: (id: string, confirmed: boolean) => Promise<void>
+  exportArchive: (ids: string[], path: string) => Promise<void>
 }
 
 export const SnapshotList: FC<Props> = (props) => {
   const [isTouring, setIsTouring] = useState(false)
   const [isSetting, setIsSetting] = useState(false)
   const [selectedSnapshotSummary, setSelectedSnapshotSummary] = useState<SnapshotSummary | null>(null)
+  const [restoringSnapshotSummary, setRestoringSnapshotSummary] = useState<SnapshotSummary | null>(null)
+  const [isExporting, setIsExporting] = useState(false)
+  const [exportSnapshotIds, setExportSnapshotIds] = useState<string[]>([])
+  const [archivePath, setArchivePath] = useState('')
+  const [isChaining, setIsChaining] = useState(false)
+  const [chainSnapshotIds, setChainSnapshotIds] = useState<string[]>([])
+  const [isTimeline, setIsTimeline] = useState(false)
+  const [timelineTableName, setTimelineTableName] = useState('')
+  const [timelinePrimaryValue, setTimelinePrimaryValue] = useState('')
 
   const navigate = useNavigate()
 
@@ -58,6 +73,33 @@ export const SnapshotList: FC<Props> = (props) => {
                   setIsTouring(true)
                 }}
               />
+              {props.snapshotSummaries.length !== 0 && (
+                <Button
+                  variant={'secondary'}
+                  label={'Export'}
+                  onClick={() => {
+                    setIsExporting(true)
+                  }}
+                />
+              )}
+              {props.snapshotSummaries.length > 2 && (
+                <Button
+                  variant={'secondary'}
+                  label={'Chain'}
+                  onClick={() => {
+                    setIsChaining(true)
+                  }}
+                />
+              )}
+              {props.snapshotSummaries.length !== 0 && (
+                <Button
+                  variant={'secondary'}
+                  label={'Timeline'}
+                  onClick={() => {
+                    setIsTimeline(true)
+                  }}
+                />
+              )}
             </>
           }
         />
@@ -102,6 +144,34 @@ export const SnapshotList: FC<Props> = (props) => {
                         props.remove(snapshotSummary.snapshotId)
                       }}
                     />
+                    <Button
+                      variant={'secondary'}
+                      label={'Live'}
+                      onClick={() => {
+                        navigate('/diff/live', { state: { snapshotId: snapshotSummary.snapshotId } })
+                      }}
+                    />
+                    <Button
+                      variant={'secondary'}
+                      label={'Compare'}
+                      onClick={() => {
+                        navigate('/diff/cross-project', { state: { snapshotId1: snapshotSummary.snapshotId } })
+                      }}
+                    />
+                    <Button
+                      variant={'warn'}
+                      label={'Restore'}
+                      onClick={() => {
+                        // ローカル環境でないプロジェクトは、確認してからリストアする
+                        props.restore(snapshotSummary.snapshotId, false).catch((e: string) => {
+                          if (e.startsWith('restore not confirmed')) {
+                            setRestoringSnapshotSummary(snapshotSummary)
+                          } else {
+                            toast.error(e)
+                          }
+                        })
+                      }}
+                    />
                   </div>
                 )}
               </div>
@@ -124,6 +194,136 @@ export const SnapshotList: FC<Props> = (props) => {
           </div>
         </div>
       </div>
+      <ModalWrapper
+        isOpen={restoringSnapshotSummary !== null}
+        setIsOpen={() => {
+          setRestoringSnapshotSummary(null)
+        }}
+        button={
+          <Button
+            variant={'warn'}
+            label={'Restore'}
+            onClick={() => {
+              if (restoringSnapshotSummary !== null) {
+                props
+                  .restore(restoringSnapshotSummary.snapshotId, true)
+                  .catch((e: string) => {
+                    toast.error(e)
+                  })
+                  .finally(() => {
+                    setRestoringSnapshotSummary(null)
+                  })
+              }
+            }}
+          />
+        }
+      >
+        <div className={styles.restore}>
+          <p>接続中の DB はローカル環境に設定されていません</p>
+          <p>「{restoringSnapshotSummary?.snapshotName}」の状態に戻すため、現在の行を更新・削除します</p>
+        </div>
+      </ModalWrapper>
+      <ModalWrapper
+        isOpen={isExporting}
+        setIsOpen={setIsExporting}
+        button={
+          <Button
+            variant={'primary'}
+            label={'Export'}
+            onClick={() => {
+              props
+                .exportArchive(exportSnapshotIds, archivePath)
+                .then(() => {
+                  setIsExporting(false)
+                })
+                .catch((e: string) => {
+                  toast.error(e)
+                })
+            }}
+          />
+        }
+      >
+        <div className={styles.archive}>
+          {/* 選択したスナップショット同士の差分も一緒に書き出される */}
+          {props.snapshotSummaries.map((snapshotSummary) => (
+            <label key={snapshotSummary.snapshotId}>
+              <input
+                type={'checkbox'}
+                checked={exportSnapshotIds.includes(snapshotSummary.snapshotId)}
+                onChange={(e) => {
+                  if (e.target.checked) {
+                    setExportSnapshotIds(exportSnapshotIds.concat([snapshotSummary.snapshotId]))
+                  } else {
+                    setExportSnapshotIds(exportSnapshotIds.filter((snapshotId) => snapshotId !== snapshotSummary.snapshotId))
+                  }
+                }}
+              />
+              {snapshotSummary.snapshotName} ( {snapshotSummary.createAt} )
+            </label>
+          ))}
+          <LabeledInputText value={archivePath} label={'File'} maxLength={500} onChange={setArchivePath} chars={'half'} />
+        </div>
+      </ModalWrapper>
+      <ModalWrapper
+        isOpen={isChaining}
+        setIsOpen={setIsChaining}
+        button={
+          <Button
+            variant={'primary'}
+            label={'Show'}
+            onClick={() => {
+              if (chainSnapshotIds.length < 2) {
+                toast.error('スナップショットを 2 つ以上選択してください')
+                return
+              }
+              navigate('/diff/chain', { state: { snapshotIds: chainSnapshotIds } })
+            }}
+          />
+        }
+      >
+        <div className={styles.chain}>
+          {/* 選択した順ではなく、作成した順に並べて比較する */}
+          {props.snapshotSummaries.map((snapshotSummary) => (
+            <label key={snapshotSummary.snapshotId}>
+              <input
+                type={'checkbox'}
+                checked={chainSnapshotIds.includes(snapshotSummary.snapshotId)}
+                onChange={(e) => {
+                  if (e.target.checked) {
+                    setChainSnapshotIds(chainSnapshotIds.concat([snapshotSummary.snapshotId]))
+                  } else {
+                    setChainSnapshotIds(chainSnapshotIds.filter((snapshotId) => snapshotId !== snapshotSummary.snapshotId))
+                  }
+                }}
+              />
+              {snapshotSummary.snapshotName} ( {snapshotSummary.createAt} )
+            </label>
+          ))}
+        </div>
+      </ModalWrapper>
+      <ModalWrapper
+        isOpen={isTimeline}
+        setIsOpen={setIsTimeline}
+        button={
+          <Button
+            variant={'primary'}
+            label={'Show'}
+            onClick={() => {
+              if (timelineTableName === '' || timelinePrimaryValue === '') {
+                toast.error('テーブルと主キーの値を入力してください')
+                return
+              }
+              navigate('/timeline', { state: { tableName: timelineTableName, primaryValue: timelinePrimaryValue } })
+            }}
+          />
+        }
+      >
+        <div className={styles.timeline}>
+          {/* 複合主キーは差分表示と同じく - で区切って指定する */}
+          <LabeledInputText value={timelineTableName} label={'Table'} maxLength={64} onChange={setTimelineTableName} chars={'half'} />
+          <LabeledInputText value={timelinePrimaryValue} label={'Primary Key'} maxLength={500} onChange={setTimelinePrimaryValue} chars={'all'} />
+        </div>
+      </ModalWrapper>
       <TourWrapper
         steps={steps(props.snapshotSummaries.length === 0)}
         isTouring={isTouring}
@@ -186,5 +386,23 @@ const steps: (isEmpty: boolean) => ReactourStep[] = (isEmpty) => {
           selector: '.icon_delete',
           content: 'スナップショットを削除できます',
         },
+        {
+          content: 'Live でスナップショットと接続中の DB の現在の状態を、スナップショットを作成せずに比較できます',
+        },
+        {
+          content: 'Compare で別のプロジェクト ( ステージング環境など ) のスナップショットと比較できます',
+        },
+        {
+          content: 'Restore で接続中の DB をスナップショットの状態に戻すことができます',
+        },
+        {
+          content: 'Export でスナップショットを 1 つのファイルに書き出して、他の人と共有できます',
+        },
+        {
+          content: 'Chain で 3 つ以上のスナップショットを選択して、作成順に 1 ステップずつの差分と全体の変化を確認できます',
+        },
+        {
+          content: 'Timeline でテーブルと主キーの値を指定して、1 つの行がスナップショットごとにどう変わったかを確認できます',
+        },
       ]
 }
@@ -0,0 +1,55 @@
+import { type FC, useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/tauri'
+import { inlineDiffMinChars, type ChainedDiff } from '../types'
+import { useLocation, useNavigate } from 'react-router-dom'
+import { DiffViewer } from '../components/templates/diff-viewer/DiffViewer'
+import { toast } from 'react-hot-toast'
+
+// 選択したスナップショットを作成順にたどり、1 ステップずつの差分と全体の変化 ( Net ) を切り替えて表示する
+export const ChainedDiffPage: FC = () => {
+  const [chainedDiff, setChainedDiff] = useState<ChainedDiff | undefined>(undefined)
+  const [ignoreRules, setIgnoreRules] = useState<string[]>([])
+  const [selectedStep, setSelectedStep] = useState(0)
+
+  const navigate = useNavigate()
+  const location = useLocation()
+
+  const { snapshotIds } = location.state as { snapshotIds: string[] }
+
+  useEffect(() => {
+    toast
+      .promise(
+        invoke<ChainedDiff>('create_chained_diff_command', { snapshotIds, ignoreRules, inlineDiffMinChars }),
+        { loading: '差分を計算中...', success: '差分を計算しました', error: '計算に失敗しました' },
+        { style: { minWidth: '200px' } }
+      )
+      .then((data) => {
+        setChainedDiff(data)
+      })
+      .catch((e: string) => {
+        navigate('/error', { state: { message: e } })
+      })
+  }, [snapshotIds, ignoreRules])
+
+  if (chainedDiff === undefined) {
+    return <></>
+  }
+
+  const summaries = chainedDiff.snapshotSummaries
+  const steps = chainedDiff.snapshotDiffs.map((_, i) => `${summaries[i].snapshotName} → ${summaries[i + 1].snapshotName}`).concat(['Net'])
+  const snapshotDiff = selectedStep < chainedDiff.snapshotDiffs.length ? chainedDiff.snapshotDiffs[selectedStep] : chainedDiff.netDiff
+
+  return (
+    <DiffViewer
+      key={selectedStep}
+      tableDiffs={snapshotDiff.tableDiffs}
+      schemaChanges={snapshotDiff.schemaChanges}
+      dbObjectDiffs={snapshotDiff.dbObjectDiffs}
+      ignoreRules={snapshotDiff.ignoreRules ?? []}
+      onChangeIgnoreRules={setIgnoreRules}
+      steps={steps}
+      selectedStep={selectedStep}
+      onSelectStep={setSelectedStep}
+    />
+  )
+}
@@ -0,0 +1,67 @@
+import { type FC, useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/tauri'
+import { inlineDiffMinChars, type CrossProjectDiff, type Project, type SnapshotSummary } from '../types'
+import { useLocation, useNavigate } from 'react-router-dom'
+import { DiffViewer } from '../components/templates/diff-viewer/DiffViewer'
+import { CrossProjectSelect } from '../components/templates/cross-project-select/CrossProjectSelect'
+
+export const CrossProjectDiffPage: FC = () => {
+  const [projects, setProjects] = useState<Project[]>([])
+  const [snapshotSummaries, setSnapshotSummaries] = useState<SnapshotSummary[]>([])
+  const [snapshotId2, setSnapshotId2] = useState<string | undefined>(undefined)
+  const [crossProjectDiff, setCrossProjectDiff] = useState<CrossProjectDiff | undefined>(undefined)
+  const [ignoreRules, setIgnoreRules] = useState<string[]>([])
+  const [tableMappings, setTableMappings] = useState<string[]>([])
+
+  const navigate = useNavigate()
+  const location = useLocation()
+
+  const { snapshotId1 } = location.state as { snapshotId1: string }
+
+  useEffect(() => {
+    invoke<Project[]>('all_projects_command')
+      .then((data) => {
+        setProjects(data)
+      })
+      .catch((e: string) => {
+        navigate('/error', { state: { message: e } })
+      })
+  }, [])
+
+  useEffect(() => {
+    if (snapshotId2 === undefined) return
+
+    invoke<CrossProjectDiff>('create_cross_project_diff_command', { snapshotId1, snapshotId2, ignoreRules, tableMappings, inlineDiffMinChars })
+      .then((data) => {
+        setCrossProjectDiff(data)
+      })
+      .catch((e: string) => {
+        navigate('/error', { state: { message: e } })
+      })
+  }, [snapshotId2, ignoreRules, tableMappings])
+
+  const selectProject: (projectId: string) => void = (projectId) => {
+    invoke<SnapshotSummary[]>('all_snapshot_summaries_command', { projectId })
+      .then((data) => {
+        setSnapshotSummaries(data)
+      })
+      .catch((e: string) => {
+        navigate('/error', { state: { message: e } })
+      })
+  }
+
+  return crossProjectDiff === undefined ? (
+    <CrossProjectSelect projects={projects} snapshotSummaries={snapshotSummaries} selectProject={selectProject} select={setSnapshotId2} />
+  ) : (
+    <DiffViewer
+      tableDiffs={crossProjectDiff.snapshotDiff.tableDiffs}
+      schemaChanges={crossProjectDiff.snapshotDiff.schemaChanges}
+      dbObjectDiffs={crossProjectDiff.snapshotDiff.dbObjectDiffs}
+      ignoreRules={crossProjectDiff.snapshotDiff.ignoreRules ?? []}
+      onChangeIgnoreRules={setIgnoreRules}
+      tableMappings={crossProjectDiff.tableMappings}
+      onChangeTableMappings={setTableMappings}
+      warnings={crossProjectDiff.warnings}
+    />
+  )
+}
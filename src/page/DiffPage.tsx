@@ -1,12 +1,15 @@
 import { type FC, useEffect, useRef, useState } from 'react'
 import { invoke } from '@tauri-apps/api/tauri'
-import { type SnapshotDiff } from '../types'
+import { inlineDiffMinChars, type SnapshotDiff } from '../types'
 import { useLocation, useNavigate } from 'react-router-dom'
 import { DiffViewer } from '../components/templates/diff-viewer/DiffViewer'
 import { toast } from 'react-hot-toast'
 
 export const DiffPage: FC = () => {
   const [snapshotDiff, setSnapshotDiff] = useState<SnapshotDiff | undefined>(undefined)
+  const [ignoreRules, setIgnoreRules] = useState<string[]>([])
+  // 指定するまでは名前の変更なしで比較し、検出した候補を表示する
+  const [renames, setRenames] = useState<string[] | undefined>(undefined)
 
   const navigate = useNavigate()
   const location = useLocation()
@@ -15,7 +18,7 @@ export const DiffPage: FC = () => {
   const { snapshotId1, snapshotId2 } = location.state as { snapshotId1: string; snapshotId2: string }
 
   useEffect(() => {
-    invoke<SnapshotDiff>('find_snapshot_diff_command', { snapshotId1, snapshotId2 })
+    invoke<SnapshotDiff>('find_snapshot_diff_command', { snapshotId1, snapshotId2, ignoreRules, renames, inlineDiffMinChars })
       .then((data) => {
         setSnapshotDiff(data)
       })
@@ -30,7 +33,7 @@ export const DiffPage: FC = () => {
 
           toast
             .promise(
-              invoke<SnapshotDiff>('create_snapshot_diff_command', { snapshotId1, snapshotId2 }),
+              invoke<SnapshotDiff>('create_snapshot_diff_command', { snapshotId1, snapshotId2, ignoreRules, renames, inlineDiffMinChars }),
               { loading: '差分を計算中...', success: '差分を保存しました', error: '計算に失敗しました' },
               { style: { minWidth: '200px' } }
             )
@@ -44,7 +47,33 @@ export const DiffPage: FC = () => {
           navigate('/error', { state: { message: e } })
         }
       })
-  }, [snapshotId1, snapshotId2])
+  }, [snapshotId1, snapshotId2, ignoreRules, renames])
 
-  return snapshotDiff !== undefined ? <DiffViewer tableDiffs={snapshotDiff.tableDiffs} /> : <></>
+  return snapshotDiff !== undefined ? (
+    <DiffViewer
+      tableDiffs={snapshotDiff.tableDiffs}
+      schemaChanges={snapshotDiff.schemaChanges}
+      dbObjectDiffs={snapshotDiff.dbObjectDiffs}
+      ignoreRules={snapshotDiff.ignoreRules ?? []}
+      onChangeIgnoreRules={setIgnoreRules}
+      renames={snapshotDiff.renames ?? []}
+      suggestedRenames={snapshotDiff.suggestedRenames ?? []}
+      onChangeRenames={setRenames}
+      createSqlScript={async () =>
+        await invoke<string>('create_sql_script_command', { snapshotId1, snapshotId2, ignoreRules: snapshotDiff.ignoreRules ?? [], renames })
+      }
+      exportReport={async (format, path) =>
+        await invoke<string[]>('export_diff_report_command', {
+          snapshotId1,
+          snapshotId2,
+          ignoreRules: snapshotDiff.ignoreRules ?? [],
+          renames,
+          format,
+          path,
+        })
+      }
+    />
+  ) : (
+    <></>
+  )
 }
@@ -0,0 +1,52 @@
+import { type FC, useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/tauri'
+import { inlineDiffMinChars, type SnapshotDiff } from '../types'
+import { useLocation, useNavigate } from 'react-router-dom'
+import { DiffViewer } from '../components/templates/diff-viewer/DiffViewer'
+import { toast } from 'react-hot-toast'
+
+// スナップショットと現在のデータベースの差分は保存せずに表示する
+export const LiveDiffPage: FC = () => {
+  const [snapshotDiff, setSnapshotDiff] = useState<SnapshotDiff | undefined>(undefined)
+  const [ignoreRules, setIgnoreRules] = useState<string[]>([])
+
+  const navigate = useNavigate()
+  const location = useLocation()
+
+  const { snapshotId } = location.state as { snapshotId: string }
+
+  useEffect(() => {
+    toast
+      .promise(
+        invoke<SnapshotDiff>('diff_live_command', { snapshotId, ignoreRules, snapshotName: null, inlineDiffMinChars }),
+        { loading: '現在の状態を取得中...', success: '差分を計算しました', error: '計算に失敗しました' },
+        { style: { minWidth: '200px' } }
+      )
+      .then((data) => {
+        setSnapshotDiff(data)
+      })
+      .catch((e: string) => {
+        navigate('/error', { state: { message: e } })
+      })
+  }, [snapshotId, ignoreRules])
+
+  // 保存したら、保存したスナップショットとの差分表示に切り替える
+  const keep: (snapshotName: string) => Promise<void> = async (snapshotName) => {
+    const data = await invoke<SnapshotDiff>('diff_live_command', { snapshotId, ignoreRules, snapshotName, inlineDiffMinChars })
+    toast.success('現在の状態を保存しました')
+    navigate('/diff', { state: { snapshotId1: data.snapshotId1, snapshotId2: data.snapshotId2 } })
+  }
+
+  return snapshotDiff !== undefined ? (
+    <DiffViewer
+      tableDiffs={snapshotDiff.tableDiffs}
+      schemaChanges={snapshotDiff.schemaChanges}
+      dbObjectDiffs={snapshotDiff.dbObjectDiffs}
+      ignoreRules={snapshotDiff.ignoreRules ?? []}
+      onChangeIgnoreRules={setIgnoreRules}
+      keep={keep}
+    />
+  ) : (
+    <></>
+  )
+}
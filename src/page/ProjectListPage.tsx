@@ -43,5 +43,10 @@ export const ProjectListPage: FC = () => {
       })
   }
 
-  return <ProjectList projects={projects} select={select} remove={remove} />
+  const importArchive: (projectId: string, path: string) => Promise<void> = async (projectId, path) => {
+    const count = await invoke<number>('import_archive_command', { projectId, path })
+    toast.success(`${count} 件のスナップショットを取り込みました`)
+  }
+
+  return <ProjectList projects={projects} select={select} remove={remove} importArchive={importArchive} />
 }
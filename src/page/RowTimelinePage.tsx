@@ -0,0 +1,26 @@
+import { type FC, useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/tauri'
+import { type RowTimeline as RowTimelineType } from '../types'
+import { useLocation, useNavigate } from 'react-router-dom'
+import { RowTimeline } from '../components/templates/row-timeline/RowTimeline'
+
+export const RowTimelinePage: FC = () => {
+  const [rowTimeline, setRowTimeline] = useState<RowTimelineType | undefined>(undefined)
+
+  const navigate = useNavigate()
+  const location = useLocation()
+
+  const { tableName, primaryValue } = location.state as { tableName: string; primaryValue: string }
+
+  useEffect(() => {
+    invoke<RowTimelineType>('find_row_timeline_command', { tableName, primaryValue })
+      .then((data) => {
+        setRowTimeline(data)
+      })
+      .catch((e: string) => {
+        navigate('/error', { state: { message: e } })
+      })
+  }, [tableName, primaryValue])
+
+  return rowTimeline !== undefined ? <RowTimeline rowTimeline={rowTimeline} /> : <></>
+}
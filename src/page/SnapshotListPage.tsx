@@ -32,5 +32,15 @@ export const SnapshotListPage: FC = () => {
       })
   }
 
-  return <SnapshotList snapshotSummaries={snapshotSummaries} remove={remove} />
+  const restore: (snapshotId: string, confirmed: boolean) => Promise<void> = async (snapshotId, confirmed) => {
+    const count = await invoke<number>('restore_snapshot_command', { snapshotId, confirmed })
+    toast.success(`リストアしました ( ${count} 件の SQL を実行 )`)
+  }
+
+  const exportArchive: (snapshotIds: string[], path: string) => Promise<void> = async (snapshotIds, path) => {
+    await invoke('export_archive_command', { snapshotIds, path })
+    toast.success('書き出しました')
+  }
+
+  return <SnapshotList snapshotSummaries={snapshotSummaries} remove={remove} restore={restore} exportArchive={exportArchive} />
 }
@@ -12,10 +12,14 @@ export interface Project {
   host: string
   port: string
   schema: string
+  local?: boolean
 }
 
 export const createSnapshotId: () => string = () => v4()
 
+// 変更前後のどちらかがこの文字数以上の文字列だけ、単語単位で比較する
+export const inlineDiffMinChars = 40
+
 export interface SnapshotSummary {
   snapshotId: string
   snapshotName: string
@@ -26,14 +30,57 @@ export interface DumpConfig {
   tableName: string
   colNames: string[]
   value: DumpConfigValue
+  rowLimit?: number | null
+  filter?: string | null
+  excludedColNames?: string[]
+  keyColNames?: string[]
+  multiset?: boolean
 }
-export type DumpConfigValue = 'limited' | 'ignore' | string
+export type DumpConfigValue = 'limited' | 'ignore' | 'all' | string
 
 export interface SnapshotDiff {
   diffId: string
   snapshotId1: string
   snapshotId2: string
   tableDiffs: TableDiff[]
+  ignoreRules?: string[]
+  schemaChanges?: SchemaChange[]
+  dbObjectDiffs?: DbObjectDiff[]
+  renames?: string[]
+  suggestedRenames?: string[]
+}
+
+export interface SchemaChange {
+  tableName: string
+  object: 'table' | 'column' | 'index' | 'foreign key'
+  name: string
+  status: 'added' | 'dropped' | 'altered'
+  definition1?: string | null
+  definition2?: string | null
+}
+
+export interface LineDiff {
+  status: 'stay' | 'added' | 'deleted'
+  line: string
+}
+
+export interface DbObjectDiff {
+  objectType: 'view' | 'trigger' | 'procedure' | 'function' | 'event'
+  objectName: string
+  status: 'added' | 'dropped' | 'altered'
+  lineDiffs: LineDiff[]
+}
+
+export interface CrossProjectDiff {
+  snapshotDiff: SnapshotDiff
+  tableMappings: string[]
+  warnings: string[]
+}
+
+export interface ChainedDiff {
+  snapshotSummaries: SnapshotSummary[]
+  snapshotDiffs: SnapshotDiff[]
+  netDiff: SnapshotDiff
 }
 
 export type PrimaryValue = string
@@ -53,4 +100,43 @@ export interface TableDiff {
   colNames: ColName[]
   rowDiffs1: RowDiff
   rowDiffs2: RowDiff
+  truncated?: boolean
+  excludedColNames1?: ColName[]
+  excludedColNames2?: ColName[]
+  ignoredColNames?: ColName[]
+  keyless?: boolean
+  rowCounts?: Record<PrimaryValue, [number, number]>
+  renamedFrom?: string | null
+  renamedColNames?: Record<ColName, ColName>
+  jsonDiffs?: Record<PrimaryValue, Record<ColName, JsonPathDiff[]>>
+  inlineDiffs?: Record<PrimaryValue, Record<ColName, InlineSegment[]>>
+}
+
+export interface InlineSegment {
+  status: 'stay' | 'added' | 'deleted'
+  text: string
+}
+
+export interface JsonPathDiff {
+  path: string
+  status: 'added' | 'removed' | 'changed'
+  value1?: string | null
+  value2?: string | null
+}
+
+export interface RowPoint {
+  snapshotId: string
+  snapshotName: string
+  createAt: string
+  state: 'present' | 'absent' | 'unknown'
+  values: Record<ColName, string>
+  changedColNames: ColName[]
+}
+
+export interface RowTimeline {
+  tableName: string
+  primaryColName: ColName
+  primaryValue: PrimaryValue
+  colNames: ColName[]
+  points: RowPoint[]
 }